@@ -0,0 +1,271 @@
+# Prompts
+select-article = Vælg artikel
+select-article-to-remove = Vælg artikler der skal fjernes
+select-article-to-share = Vælg artikel der skal deles
+select-article-to-queue = Vælg artikel der skal i køen
+select-article-to-mark = Vælg artikler der skal markeres
+select-article-to-rate = Vælg artikler der skal bedømmes
+select-article-to-archive = Vælg artikler der skal arkiveres
+select-article-to-refetch = Vælg artikel der skal hentes igen
+select-article-to-tag = Vælg artikler der skal tagges
+select-changes = Vælg ændringer der skal anvendes
+confirm-remove = Fjern { $title }?
+confirm-remove-many = Fjern { $count } artikler?
+confirm-restore = Gendan fra { $from }?
+confirm-write = Skriv disse ændringer til bibliografien?
+
+# Progress
+looking-up = Slår artikler op...
+downloading-bibliography = Henter bibliografi...
+added = Tilføjet!
+fetching-record = Henter { $id } fra { $source }...
+doctor-checking = Tjekker for tilbagetrækninger og rettelser...
+doctor-checked = Tjekkede for tilbagetrækninger og rettelser
+
+# Errors
+no-entry-found = Ingen artikel matcher: { $query }
+remove-failed = Kunne ikke fjerne artiklen
+write-declined = Ændringerne blev ikke skrevet
+
+# Summaries
+no-matches = Ingen artikler matcher
+record-unchanged = { $key } stemmer overens med { $source } ({ $id })
+added-entry = Tilføjede { $key }
+already-in-library = Findes allerede i biblioteket som { $key }
+nothing-to-restore = Intet at gendanne, biblioteket svarer allerede til { $from }
+pdf-no-doi = ingen DOI
+pdf-not-attempted = ikke forsøgt
+pdfs-summary = { $present } af { $total } artikler har en PDF
+pdfs-naming-ok = Alle PDF'er følger den nuværende navngivning
+pdfs-outdated-names =
+    { $count ->
+        [one] 1 PDF er navngivet efter en ældre navngivning og ville blive hentet igen, kør `zime migrate` først
+       *[other] { $count } PDF'er er navngivet efter en ældre navngivning og ville blive hentet igen, kør `zime migrate` først
+    }
+pdfs-downloading =
+    { $count ->
+        [one] Henter 1 PDF...
+       *[other] Henter { $count } PDF'er...
+    }
+doctor-none = Ingen tilbagetrukne artikler fundet
+doctor-retracted =
+    { $count ->
+        [one] 1 tilbagetrukket artikel fundet
+       *[other] { $count } tilbagetrukne artikler fundet
+    }
+languages-detected =
+    { $count ->
+        [one] Fandt sproget for 1 artikel
+       *[other] Fandt sproget for { $count } artikler
+    }
+queue-empty = Læsekøen er tom
+queue-more = { $count } mere i køen
+
+# Notifikationer
+notify-pdfs-downloaded =
+    { $count ->
+        [one] Hentede 1 PDF
+       *[other] Hentede { $count } PDF'er
+    }
+notify-pdfs-failed =
+    { $count ->
+        [0] Ingen hentninger fejlede
+        [one] 1 hentning fejlede
+       *[other] { $count } hentninger fejlede
+    }
+notify-sync-failed = Synkronisering af biblioteket fejlede
+
+# Prøvekørsel
+dry-run-write = Ville skrive { $path }
+dry-run-sync = Ville committe ændringerne og synkronisere med git-remoten
+
+# Konferencer
+venue-not-tracked = { $name } følges ikke
+venues-empty = Ingen konferencer følges, tilføj en med `zime venues add`
+venue-entries =
+    { $count ->
+        [one] 1 artikel
+       *[other] { $count } artikler
+    }
+venue-deadline = Deadline: { $date }
+venue-authors = Hyppige forfattere
+
+# Blame
+blame-no-git = Biblioteket er ikke et git-repository og har derfor ingen historik
+blame-source = Hentet fra { $source } ({ $id }){ $fetched }
+blame-uncommitted = Endnu ikke committet
+blame-added = tilføjet
+blame-changed = ændret
+blame-removed = fjernet
+
+# Cancellation
+cancelling = Afbryder, tryk Ctrl-C igen for at afslutte med det samme
+cancelled = Afbrudt
+
+# Summaries
+summary-succeeded = lykkedes
+summary-failed = fejlede
+summary-skipped = sprunget over
+
+# Which
+which-local = den nærmeste `.zime`-mappe over { $cwd }
+which-global = det globale bibliotek, ingen `.zime`-mappe over { $cwd }
+which-explicit = angivet med --root
+which-missing = mangler
+
+# Altmetrics
+altmetrics-fetching = Henter hændelser fra Crossref Event Data
+altmetrics-fetched =
+    { $count ->
+        [one] Opdaterede tallene for 1 post
+       *[other] Opdaterede tallene for { $count } poster
+    }
+
+# Cross-references
+remove-dependents = Stadig refereret gennem crossref eller xdata af: { $keys }
+
+# Import
+import-fetching = Henter værker fra { $source }
+import-fetched =
+    { $count ->
+        [one] Hentede 1 værk
+       *[other] Hentede { $count } værker
+    }
+select-author = Vælg forfatteren
+select-works-to-import = Vælg de værker, der skal importeres
+
+# Links
+select-link = Vælg linket, der skal åbnes
+link-exists = { $key } har allerede dette link
+links-checking = Tjekker links
+links-checked =
+    { $count ->
+        [one] Tjekkede 1 link
+       *[other] Tjekkede { $count } links
+    }
+
+# Versions
+select-version = Vælg den anden version
+relate-self = En artikel kan ikke være sin egen version
+
+# Tag rules
+retagged =
+    { $count ->
+        [one] Tilføjede tags til 1 artikel
+       *[other] Tilføjede tags til { $count } artikler
+    }
+
+# Search
+load-more = Hent flere resultater…
+
+# Mirrors
+mirrors-probing = Tjekker Sci-Hub-spejle
+
+# Fmt
+titles-formatted =
+    { $count ->
+        [one] Formaterede 1 titel
+       *[other] Formaterede { $count } titler
+    }
+
+# Add from PDF
+grobid-extracting = Udtrækker metadata fra { $file } med GROBID
+
+# Markup
+markup-found =
+    { $count ->
+        [one] 1 post har en ødelagt titel eller resumé
+       *[other] { $count } poster har en ødelagt titel eller resumé
+    }
+markup-repaired = Repareret fra { $source }
+
+# Local
+select-article-to-localize = Vælg de poster, der skal ændres
+localized =
+    { $count ->
+        [one] 1 post er nu kun lokal
+       *[other] { $count } poster er nu kun lokale
+    }
+shared =
+    { $count ->
+        [one] 1 post er delt igen
+       *[other] { $count } poster er delt igen
+    }
+
+# Refs
+select-article-for-refs = Vælg posten, hvis referencer skal tilføjes
+refs-no-doi = { $key } har ingen DOI at slå referencerne op med
+refs-fetched =
+    { $count ->
+        [one] Fandt 1 reference
+       *[other] Fandt { $count } referencer
+    }
+refs-skipped = { $present } er allerede i biblioteket, { $missing } uden DOI
+
+# Recommend
+select-article-to-recommend = Vælg posten, der skal findes relaterede artikler til
+recommend-no-id = { $key } har ingen DOI eller arXiv-ID at finde relaterede artikler med
+recommend-none = Alle artikler anbefalet til { $key } er allerede i biblioteket
+select-recommendation-to-add =
+    { $count ->
+        [0] Vælg en artikel at tilføje
+        [one] Tilføjede 1 artikel, vælg en til at tilføje
+       *[other] Tilføjede { $count } artikler, vælg en til at tilføje
+    }
+recommend-done = Færdig
+
+# Import from a paper
+aux-unresolved =
+    { $count ->
+        [one] Kunne ikke finde 1 citation: { $keys }
+       *[other] Kunne ikke finde { $count } citationer: { $keys }
+    }
+
+# Cited by
+select-article-for-cited-by = Vælg posten, hvis citerende værker skal tilføjes
+cited-by-no-id = { $key } har ingen DOI eller arXiv-ID at finde citerende værker med
+cited-by-found =
+    { $count ->
+        [one] Fandt 1 citerende værk
+       *[other] Fandt { $count } citerende værker
+    }
+cited-by-present = { $count } er allerede i biblioteket
+
+# Rekey
+select-article-to-rekey = Vælg posterne, der skal omdøbes
+rekey-one-only = Kun én post kan få en nøgle med --to, men { $count } blev valgt
+rekey-taken = Nøglen { $key } er allerede i brug
+rekeyed =
+    { $count ->
+        [one] Omdøbte 1 post
+       *[other] Omdøbte { $count } poster
+    }
+
+# Check updates
+updates-checking =
+    { $count ->
+        [one] Tjekker 1 preprint på arXiv...
+       *[other] Tjekker { $count } preprints på arXiv...
+    }
+updates-found =
+    { $count ->
+        [0] Alle preprints er i deres nyeste version
+        [one] 1 preprint har en nyere version
+       *[other] { $count } preprints har en nyere version
+    }
+select-updates = Vælg de preprints, der skal opdateres
+
+# Prompts
+prompt-select = Nummer, eller Enter for { $default }:
+prompt-multiselect = Numre såsom 1 3 5-7, all eller none, eller Enter for de markerede:
+prompt-invalid = Ikke et valg: { $answer }
+query-ambiguous = { $query } matcher { $count } artikler, gør den mere præcis
+query-ambiguous-many = { $query } matcher { $count } artikler, gør den mere præcis eller brug --all
+
+# Remotes status
+remotes-checking =
+    { $count ->
+        [one] Tjekker 1 kilde...
+       *[other] Tjekker { $count } kilder...
+    }
+remotes-not-configured = ikke sat op, sæt { $setting }
@@ -0,0 +1,271 @@
+# Prompts
+select-article = Select article
+select-article-to-remove = Select articles to remove
+select-article-to-share = Select article to share
+select-article-to-queue = Select article to queue
+select-article-to-mark = Select articles to mark
+select-article-to-rate = Select articles to rate
+select-article-to-archive = Select articles to archive
+select-article-to-refetch = Select article to refetch
+select-article-to-tag = Select articles to tag
+select-changes = Select changes to apply
+confirm-remove = Remove { $title }?
+confirm-remove-many = Remove { $count } entries?
+confirm-restore = Restore from { $from }?
+confirm-write = Write these changes to the bibliography?
+
+# Progress
+looking-up = Looking up articles...
+downloading-bibliography = Downloading bibliography...
+added = Added!
+fetching-record = Fetching { $id } from { $source }...
+doctor-checking = Checking for retractions and errata...
+doctor-checked = Checked for retractions and errata
+
+# Errors
+no-entry-found = No entry found matching: { $query }
+remove-failed = Failed to remove entry
+write-declined = Changes not written
+
+# Summaries
+no-matches = No entries match
+record-unchanged = { $key } matches { $source } ({ $id })
+added-entry = Added { $key }
+already-in-library = Already in the library as { $key }
+nothing-to-restore = Nothing to restore, the library already matches { $from }
+pdf-no-doi = no DOI
+pdf-not-attempted = not attempted
+pdfs-summary = { $present } of { $total } entries have a PDF
+pdfs-naming-ok = All PDFs follow the current naming scheme
+pdfs-outdated-names =
+    { $count ->
+        [one] 1 PDF is named after an older scheme and would be downloaded again, run `zime migrate` first
+       *[other] { $count } PDFs are named after an older scheme and would be downloaded again, run `zime migrate` first
+    }
+pdfs-downloading =
+    { $count ->
+        [one] Downloading 1 PDF...
+       *[other] Downloading { $count } PDFs...
+    }
+doctor-none = No retracted entries found
+doctor-retracted =
+    { $count ->
+        [one] 1 retracted entry found
+       *[other] { $count } retracted entries found
+    }
+languages-detected =
+    { $count ->
+        [one] Detected the language of 1 entry
+       *[other] Detected the language of { $count } entries
+    }
+queue-empty = The reading queue is empty
+queue-more = { $count } more in the queue
+
+# Notifications
+notify-pdfs-downloaded =
+    { $count ->
+        [one] Downloaded 1 PDF
+       *[other] Downloaded { $count } PDFs
+    }
+notify-pdfs-failed =
+    { $count ->
+        [0] No downloads failed
+        [one] 1 download failed
+       *[other] { $count } downloads failed
+    }
+notify-sync-failed = Syncing the library failed
+
+# Dry run
+dry-run-write = Would write { $path }
+dry-run-sync = Would commit the changes and sync with the git remote
+
+# Venues
+venue-not-tracked = { $name } is not a tracked venue
+venues-empty = No venues are tracked, add one with `zime venues add`
+venue-entries =
+    { $count ->
+        [one] 1 entry
+       *[other] { $count } entries
+    }
+venue-deadline = Deadline: { $date }
+venue-authors = Frequent authors
+
+# Blame
+blame-no-git = The library is not a git repository, so it has no history
+blame-source = Fetched from { $source } ({ $id }){ $fetched }
+blame-uncommitted = Not committed yet
+blame-added = added
+blame-changed = changed
+blame-removed = removed
+
+# Cancellation
+cancelling = Cancelling, press Ctrl-C again to exit immediately
+cancelled = Cancelled
+
+# Summaries
+summary-succeeded = succeeded
+summary-failed = failed
+summary-skipped = skipped
+
+# Which
+which-local = the nearest `.zime` directory above { $cwd }
+which-global = the global library, no `.zime` directory above { $cwd }
+which-explicit = given with --root
+which-missing = missing
+
+# Altmetrics
+altmetrics-fetching = Fetching events from Crossref Event Data
+altmetrics-fetched =
+    { $count ->
+        [one] Updated the counts of 1 entry
+       *[other] Updated the counts of { $count } entries
+    }
+
+# Cross-references
+remove-dependents = Still referenced through crossref or xdata by: { $keys }
+
+# Import
+import-fetching = Fetching works from { $source }
+import-fetched =
+    { $count ->
+        [one] Fetched 1 work
+       *[other] Fetched { $count } works
+    }
+select-author = Select the author
+select-works-to-import = Select the works to import
+
+# Links
+select-link = Select the link to open
+link-exists = { $key } already has this link
+links-checking = Checking links
+links-checked =
+    { $count ->
+        [one] Checked 1 link
+       *[other] Checked { $count } links
+    }
+
+# Versions
+select-version = Select the other version
+relate-self = A paper cannot be its own version
+
+# Tag rules
+retagged =
+    { $count ->
+        [one] Tagged 1 entry
+       *[other] Tagged { $count } entries
+    }
+
+# Search
+load-more = Load more results…
+
+# Mirrors
+mirrors-probing = Checking Sci-Hub mirrors
+
+# Fmt
+titles-formatted =
+    { $count ->
+        [one] Formatted 1 title
+       *[other] Formatted { $count } titles
+    }
+
+# Add from PDF
+grobid-extracting = Extracting the metadata of { $file } with GROBID
+
+# Markup
+markup-found =
+    { $count ->
+        [one] 1 entry has a mangled title or abstract
+       *[other] { $count } entries have a mangled title or abstract
+    }
+markup-repaired = Repaired from { $source }
+
+# Local
+select-article-to-localize = Select the entries to change
+localized =
+    { $count ->
+        [one] 1 entry is now local-only
+       *[other] { $count } entries are now local-only
+    }
+shared =
+    { $count ->
+        [one] 1 entry is shared again
+       *[other] { $count } entries are shared again
+    }
+
+# Refs
+select-article-for-refs = Select the entry whose references to add
+refs-no-doi = { $key } has no DOI to look up its references by
+refs-fetched =
+    { $count ->
+        [one] Found 1 reference
+       *[other] Found { $count } references
+    }
+refs-skipped = { $present } already in the library, { $missing } without a DOI
+
+# Recommend
+select-article-to-recommend = Select the entry to find related papers for
+recommend-no-id = { $key } has no DOI or arXiv ID to find related papers by
+recommend-none = Every paper recommended for { $key } is already in the library
+select-recommendation-to-add =
+    { $count ->
+        [0] Select a paper to add
+        [one] Added 1 paper, select another to add
+       *[other] Added { $count } papers, select another to add
+    }
+recommend-done = Done
+
+# Import from a paper
+aux-unresolved =
+    { $count ->
+        [one] Could not find 1 citation: { $keys }
+       *[other] Could not find { $count } citations: { $keys }
+    }
+
+# Cited by
+select-article-for-cited-by = Select the entry whose citing works to add
+cited-by-no-id = { $key } has no DOI or arXiv ID to find citing works by
+cited-by-found =
+    { $count ->
+        [one] Found 1 citing work
+       *[other] Found { $count } citing works
+    }
+cited-by-present = { $count } already in the library
+
+# Rekey
+select-article-to-rekey = Select the entries to rename
+rekey-one-only = Only one entry can be given a key with --to, but { $count } were selected
+rekey-taken = The key { $key } is already taken
+rekeyed =
+    { $count ->
+        [one] Renamed 1 entry
+       *[other] Renamed { $count } entries
+    }
+
+# Check updates
+updates-checking =
+    { $count ->
+        [one] Checking 1 preprint on arXiv...
+       *[other] Checking { $count } preprints on arXiv...
+    }
+updates-found =
+    { $count ->
+        [0] All preprints are at their latest version
+        [one] 1 preprint has a newer version
+       *[other] { $count } preprints have a newer version
+    }
+select-updates = Select the preprints to update
+
+# Prompts
+prompt-select = Number, or Enter for { $default }:
+prompt-multiselect = Numbers such as 1 3 5-7, all or none, or Enter for the marked ones:
+prompt-invalid = Not a choice: { $answer }
+query-ambiguous = { $query } matches { $count } entries, make it match only one
+query-ambiguous-many = { $query } matches { $count } entries, make it match only one or pass --all
+
+# Remotes status
+remotes-checking =
+    { $count ->
+        [one] Checking 1 remote...
+       *[other] Checking { $count } remotes...
+    }
+remotes-not-configured = not configured, set { $setting }
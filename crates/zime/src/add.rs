@@ -0,0 +1,174 @@
+//! Adding entries fetched from a remote, or found from a PDF, to the library.
+
+use std::fs;
+
+use biblatex::Entry;
+use camino::Utf8Path;
+use color_eyre::{
+    eyre::{bail, eyre},
+    owo_colors::OwoColorize,
+};
+use tracing::debug;
+
+use crate::{
+    Result,
+    config::Setup,
+    doi, dry_run,
+    i18n::t,
+    language, merge, pdf_steps, pdf_text,
+    pdfs::Manifest,
+    prompt,
+    provenance::{self, Provenance},
+    refetch, remotes,
+    tag_rules::Rules,
+    title_case,
+    transaction::Transaction,
+};
+
+/// Adds `entry` to the library, unless it is already there, detecting its
+/// language, applying the tag rules, casing its title and recording who added
+/// it, and syncs. Returns the key of the entry in the library.
+pub fn insert(setup: &Setup, mut entry: Entry) -> Result<String> {
+    let mut bib = setup.bib()?;
+    if let Some(existing) = merge::find_duplicate(&bib, &entry) {
+        println!("{}", t!("already-in-library", key = existing.as_str()));
+        return Ok(existing);
+    }
+    language::fill(&mut entry);
+    Rules::load(setup)?.apply(&mut entry);
+    title_case::normalize(&mut entry, setup.config()?.title_case.style);
+    if let Some(name) = provenance::identity(setup) {
+        provenance::set_added_by(&mut entry, &name);
+    }
+    let key = entry.key.clone();
+    let tx = Transaction::begin(setup)?;
+    let report = merge::merge(&mut bib, [entry]);
+    setup.write_bib(&bib)?;
+    setup.sync_git()?;
+    tx.commit();
+    let key = report.new_key(&key).unwrap_or(&key).to_string();
+    println!("{}", t!("added-entry", key = key.as_str()).green());
+    Ok(key)
+}
+
+/// The publisher's record of the work with the given DOI, if the config
+/// prefers publisher metadata and zime can fetch it from the publisher.
+pub fn publisher_provenance(setup: &Setup, id: &str) -> Result<Option<Provenance>> {
+    if !setup.config()?.prefer_publisher {
+        return Ok(None);
+    }
+    Ok(remotes::acm::is_acm(id).then(|| Provenance::new("acm", &doi::normalize(id))))
+}
+
+/// Adds the work with the given DOI, with metadata from Crossref, or from
+/// the DOI's registration agency if Crossref does not know it. Preprints on
+/// bioRxiv and medRxiv are fetched from there, and so are works from
+/// publishers zime knows if the config prefers publisher metadata.
+pub fn by_doi(setup: &Setup, id: &str) -> Result<String> {
+    let id = doi::normalize(id);
+    let spinner = prompt::spinner();
+    let direct = if remotes::biorxiv::is_biorxiv(&id) {
+        Some(Provenance::new("biorxiv", &id))
+    } else {
+        publisher_provenance(setup, &id)?
+    };
+    if let Some(provenance) = direct {
+        spinner.start(t!(
+            "fetching-record",
+            id = id.as_str(),
+            source = provenance.source.as_str()
+        ));
+        let mut entry = refetch::fetch_record(&provenance)?;
+        spinner.stop("");
+        provenance::set(&mut entry, &provenance);
+        return insert(setup, entry);
+    }
+    spinner.start(t!("fetching-record", id = id.as_str(), source = "crossref"));
+    let (mut entry, provenance) = match remotes::crossref::fetch_work(&id) {
+        Ok(work) => (work.record().into_entry(), Provenance::new("crossref", &id)),
+        Err(err) => {
+            debug!(%id, %err, "Crossref has no record, resolving the DOI");
+            spinner.set_message(t!("fetching-record", id = id.as_str(), source = "doi.org"));
+            let provenance = Provenance::new("doi", &id);
+            (refetch::fetch_record(&provenance)?, provenance)
+        }
+    };
+    spinner.stop("");
+
+    provenance::set(&mut entry, &provenance);
+    insert(setup, entry)
+}
+
+/// The DOI printed on the first page of the PDF, for PDFs without one in
+/// their metadata. Later pages are skipped, as they cite other papers.
+fn first_page_doi(path: &Utf8Path) -> Option<String> {
+    match pdf_text::extract(path) {
+        Ok(text) => doi::find(text.page(1)),
+        Err(err) => {
+            debug!(%err, "could not read the text of the PDF");
+            None
+        }
+    }
+}
+
+/// Adds the work in a PDF file, found by the DOI embedded in the PDF or else
+/// by the metadata GROBID extracts from it, and keeps a copy of the PDF: in
+/// the PDF directory if the work has a DOI, otherwise as an attachment.
+pub fn by_pdf(setup: &Setup, path: &Utf8Path) -> Result<String> {
+    let pdf = fs::read(path).map_err(|err| eyre!("failed to read {path}: {err}"))?;
+    if !pdf.starts_with(b"%PDF") {
+        bail!("{path} is not a PDF");
+    }
+    let file_name = path.file_name().unwrap_or("paper.pdf");
+
+    let (key, id) = match doi::find(&String::from_utf8_lossy(&pdf)).or_else(|| first_page_doi(path))
+    {
+        Some(id) => {
+            debug!(%id, "found DOI in PDF");
+            (by_doi(setup, &id)?, Some(id))
+        }
+        None => {
+            let spinner = prompt::spinner();
+            spinner.start(t!("grobid-extracting", file = file_name));
+            let record = remotes::grobid::extract(&pdf, file_name)?;
+            spinner.stop("");
+            match record.doi.clone() {
+                Some(id) => (by_doi(setup, &id)?, Some(id)),
+                None => {
+                    let mut entry = record.into_entry();
+                    provenance::set(&mut entry, &Provenance::new("grobid", file_name));
+                    (insert(setup, entry)?, None)
+                }
+            }
+        }
+    };
+
+    match id {
+        Some(id) => {
+            let target = setup.pdf_path(&id);
+            if dry_run::enabled() {
+                dry_run::would_write(&target);
+            } else if !target.exists() {
+                fs::create_dir_all(setup.pdf_dir())?;
+                fs::write(&target, &pdf)?;
+                pdf_steps::run(&setup.config()?.pdf_steps, &target, "local");
+                let mut manifest = Manifest::load(setup)?;
+                manifest.record(&id, &target, Some("local"))?;
+                manifest.save(setup)?;
+                debug!(%target, "stored PDF");
+            }
+        }
+        None => {
+            let dir = setup.attachments_dir().join(&key);
+            if dry_run::enabled() {
+                dry_run::would_write(&dir.join(file_name));
+            } else {
+                fs::create_dir_all(&dir)?;
+                fs::write(dir.join(file_name), &pdf)?;
+                debug!(%dir, "stored PDF as attachment");
+            }
+            setup.sync_git()?;
+        }
+    }
+    Ok(key)
+}
@@ -0,0 +1,102 @@
+//! Attention beyond citations, from Crossref Event Data.
+//!
+//! `zime altmetrics` counts the events mentioning each entry's DOI, such as
+//! Wikipedia citations and Reddit posts, and caches them in the sidecar, where
+//! `show` and `list --sort attention` read them. Counts fetched within the
+//! last week are not fetched again unless forced.
+
+use std::collections::BTreeMap;
+
+use biblatex::Entry;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::{
+    Result, bulk,
+    config::Setup,
+    doi,
+    i18n::t,
+    prompt, remotes,
+    sidecar::{EntryMeta, Sidecar},
+};
+
+const MAX_AGE: jiff::SignedDuration = jiff::SignedDuration::from_hours(7 * 24);
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Altmetrics {
+    pub fetched: Option<jiff::Timestamp>,
+    /// Number of events by source, e.g. `wikipedia`
+    pub counts: BTreeMap<String, usize>,
+}
+
+impl Altmetrics {
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// The counts on a single line, e.g. `wikipedia 3, reddit 1`, most
+    /// frequent first.
+    pub fn describe(&self) -> String {
+        let mut counts: Vec<_> = self.counts.iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(a.1));
+        counts
+            .into_iter()
+            .map(|(source, count)| format!("{source} {count}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn is_fresh(&self) -> bool {
+        self.fetched
+            .is_some_and(|fetched| jiff::Timestamp::now().duration_since(fetched) < MAX_AGE)
+    }
+}
+
+/// The total attention of an entry, for sorting.
+pub fn total(meta: Option<&EntryMeta>) -> usize {
+    meta.and_then(|meta| meta.altmetrics.as_ref())
+        .map_or(0, Altmetrics::total)
+}
+
+/// Fetches the counts of `entries` with a DOI whose cached counts are missing
+/// or stale, or of all of them if `force` is set.
+pub fn refresh(setup: &Setup, entries: &[&Entry], force: bool) -> Result<()> {
+    let mut sidecar = Sidecar::load(setup)?;
+    let stale: Vec<(&str, String)> = entries
+        .iter()
+        .filter(|entry| {
+            let fresh = sidecar
+                .get(&entry.key)
+                .and_then(|meta| meta.altmetrics.as_ref())
+                .is_some_and(Altmetrics::is_fresh);
+            if fresh && !force {
+                debug!(key=%entry.key, "altmetrics are fresh");
+            }
+            force || !fresh
+        })
+        .filter_map(|entry| Some((entry.key.as_str(), doi::normalize(&entry.doi().ok()?))))
+        .collect();
+
+    let spinner = prompt::spinner();
+    spinner.start(t!("altmetrics-fetching"));
+    let counts = bulk::fetch(
+        stale.iter().map(|(_, doi)| doi.clone()),
+        |doi| spinner.set_message(doi),
+        remotes::eventdata::count_by_source,
+    )?;
+    let mut fetched = 0;
+    for (key, doi) in &stale {
+        match &counts[doi] {
+            Ok(counts) => {
+                sidecar.get_mut(key).altmetrics = Some(Altmetrics {
+                    fetched: Some(jiff::Timestamp::now()),
+                    counts: counts.clone(),
+                });
+                fetched += 1;
+            }
+            Err(err) => warn!(%doi, %err, "failed to fetch events"),
+        }
+    }
+    spinner.stop(t!("altmetrics-fetched", count = fetched));
+    sidecar.save(setup)
+}
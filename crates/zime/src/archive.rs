@@ -0,0 +1,69 @@
+//! Archived entries, kept in `archive.bib` next to the bibliography.
+//!
+//! Archived entries no longer show up in `list` or in the generated
+//! bibliography, but can still be found with `list --archived`. Their notes,
+//! metadata and sidecar data are kept.
+//!
+//! Archived local-only entries go in `local-archive.bib` instead, which is
+//! kept out of git like `local.bib`, see [`crate::local`].
+
+use std::fs;
+
+use biblatex::{Bibliography, Entry};
+use camino::{Utf8Path, Utf8PathBuf};
+use color_eyre::eyre::eyre;
+use tracing::info;
+
+use crate::{Result, config::Setup, dry_run, local};
+
+pub const LOCAL_FILE: &str = "local-archive.bib";
+
+pub fn local_path(setup: &Setup) -> Utf8PathBuf {
+    setup.root().join(LOCAL_FILE)
+}
+
+/// The archived entries, with the local-only ones.
+pub fn load(setup: &Setup) -> Result<Bibliography> {
+    let mut archive = read(&setup.archive_path())?;
+    for entry in load_local(setup)? {
+        archive.insert(entry);
+    }
+    Ok(archive)
+}
+
+/// The archived local-only entries.
+pub fn load_local(setup: &Setup) -> Result<Bibliography> {
+    read(&local_path(setup))
+}
+
+fn read(path: &Utf8Path) -> Result<Bibliography> {
+    if !path.exists() {
+        return Ok(Bibliography::new());
+    }
+    let src = fs::read_to_string(path)?;
+    Bibliography::parse(&src).map_err(|err| eyre!("failed to parse {path}: {err}"))
+}
+
+/// Moves `entries` from the bibliography to the archive.
+pub fn archive(setup: &Setup, entries: &[Entry]) -> Result<()> {
+    let mut bib = setup.bib()?;
+    let mut archive = load(setup)?;
+    for entry in entries {
+        bib.remove(&entry.key);
+        archive.insert(entry.clone());
+        info!(key=%entry.key, "archived entry");
+    }
+    let (shared, local) = local::split(setup, &archive)?;
+    if dry_run::enabled() {
+        dry_run::would_write(&setup.archive_path());
+    } else {
+        fs::write(setup.archive_path(), shared.to_biblatex_string())?;
+        let local_path = local_path(setup);
+        if !local.is_empty() || local_path.exists() {
+            fs::write(local_path, local.to_biblatex_string())?;
+        }
+    }
+    // Writing the bibliography also keeps the files of the archived
+    // local-only entries out of git
+    setup.write_bib(&bib)
+}
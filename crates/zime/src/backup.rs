@@ -0,0 +1,151 @@
+//! Timestamped snapshots of the library.
+//!
+//! Snapshots are zip archives of the whole library, the bibliography with
+//! `zime.lock`, the archive, local-only entries, notes, metadata, venues and
+//! templates, and optionally PDFs. They are written to the backup directory,
+//! which defaults to a per-library directory in the local data dir so that
+//! they are never picked up by git. Only git's own files and the cache are
+//! left out.
+
+use std::{
+    fs,
+    io::{Read, Write},
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use color_eyre::eyre::eyre;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+use zip::{ZipWriter, write::SimpleFileOptions};
+
+use crate::{Result, bundle, config::Setup, dry_run};
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BackupConfig {
+    /// Directory to write snapshots to
+    pub dir: Option<Utf8PathBuf>,
+    /// Number of snapshots to keep, the newest one is always kept
+    pub keep: usize,
+    /// Take a snapshot before destructive operations
+    pub auto: bool,
+    /// Include downloaded PDFs in snapshots
+    pub include_pdfs: bool,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            keep: 10,
+            auto: true,
+            include_pdfs: false,
+        }
+    }
+}
+
+pub fn backup_dir(setup: &Setup, config: &BackupConfig) -> Result<Utf8PathBuf> {
+    if let Some(dir) = &config.dir {
+        return Ok(dir.clone());
+    }
+    let dirs = directories::ProjectDirs::from("com", "zime", "zime")
+        .ok_or_else(|| eyre!("Could not determine data directory"))?;
+    let data = Utf8PathBuf::from_path_buf(dirs.data_local_dir().to_path_buf())
+        .map_err(|_| eyre!("Data path is not valid UTF-8"))?;
+    let library = setup
+        .root()
+        .as_str()
+        .trim_start_matches('/')
+        .replace(['/', '\\', ':'], "_");
+    Ok(data.join("backups").join(library))
+}
+
+/// Takes a snapshot and removes the oldest ones beyond the retention limit.
+pub fn snapshot(setup: &Setup, config: &BackupConfig) -> Result<Utf8PathBuf> {
+    let dir = backup_dir(setup, config)?;
+    fs::create_dir_all(&dir)?;
+
+    let name = jiff::Timestamp::now().strftime("%Y%m%dT%H%M%S%.3fZ");
+    let path = dir.join(format!("zime-{name}.zip"));
+    write(setup, config, &path)?;
+    info!(%path, "created backup");
+
+    let snapshots = list(setup, config)?;
+    let keep = config.keep.max(1);
+    if snapshots.len() > keep {
+        for old in &snapshots[..snapshots.len() - keep] {
+            debug!(path=%old, "removing old backup");
+            fs::remove_file(old)?;
+        }
+    }
+
+    Ok(path)
+}
+
+/// Writes every file of the library to the archive at `out`, by its path
+/// relative to the library.
+fn write(setup: &Setup, config: &BackupConfig, out: &Utf8Path) -> Result<()> {
+    let root = setup.root();
+    let mut skipped = vec![
+        root.join(".git"),
+        root.join(".cache"),
+        backup_dir(setup, config)?,
+        out.to_path_buf(),
+    ];
+    if !config.include_pdfs {
+        skipped.push(setup.pdf_dir());
+    }
+
+    let mut zip = ZipWriter::new(fs::File::create(out)?);
+    let options = SimpleFileOptions::default();
+    let mut buf = Vec::new();
+    for path in bundle::walk(&root)? {
+        if skipped.iter().any(|skipped| path.starts_with(skipped)) {
+            continue;
+        }
+        let Ok(name) = path.strip_prefix(&root) else {
+            continue;
+        };
+        let name = name
+            .components()
+            .map(|c| c.as_str())
+            .collect::<Vec<_>>()
+            .join("/");
+        buf.clear();
+        fs::File::open(&path)?.read_to_end(&mut buf)?;
+        zip.start_file(name.as_str(), options)?;
+        zip.write_all(&buf)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// Takes a snapshot before a destructive operation, if enabled in the config.
+pub fn before_destructive(setup: &Setup, operation: &str) -> Result<()> {
+    let config = setup.config()?.backup;
+    if config.auto && !dry_run::enabled() {
+        debug!(operation, "taking automatic backup");
+        snapshot(setup, &config)?;
+    }
+    Ok(())
+}
+
+/// All snapshots of the library, oldest first.
+pub fn list(setup: &Setup, config: &BackupConfig) -> Result<Vec<Utf8PathBuf>> {
+    let dir = backup_dir(setup, config)?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut snapshots = Vec::new();
+    for entry in dir.read_dir_utf8()? {
+        let path = entry?.into_path();
+        if path
+            .file_name()
+            .is_some_and(|name| name.starts_with("zime-") && name.ends_with(".zip"))
+        {
+            snapshots.push(path);
+        }
+    }
+    snapshots.sort();
+    Ok(snapshots)
+}
@@ -0,0 +1,135 @@
+//! The history of an entry, reconstructed from the library's git history.
+//!
+//! Every commit touching `references.bib` is compared against its parent, so
+//! `zime blame` shows when an entry was added, by whom and with which command,
+//! and each field changed since.
+
+use biblatex::{Bibliography, Entry};
+use color_eyre::{eyre::bail, owo_colors::OwoColorize};
+use duct::cmd;
+use tracing::debug;
+
+use crate::{
+    Result, bundle,
+    config::Setup,
+    diff::{self, FieldChange},
+    i18n::t,
+    provenance,
+};
+
+struct Commit {
+    hash: String,
+    author: String,
+    date: String,
+    subject: String,
+}
+
+enum Event {
+    Added,
+    Changed(Vec<FieldChange>),
+    Removed,
+}
+
+/// The commits touching the bibliography, oldest first.
+fn commits(setup: &Setup) -> Result<Vec<Commit>> {
+    let log = cmd!(
+        "git",
+        "log",
+        "--reverse",
+        "--format=%H%x09%an%x09%as%x09%s",
+        "--",
+        bundle::BIB
+    )
+    .dir(setup.root())
+    .read()?;
+    Ok(log
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(4, '\t');
+            Some(Commit {
+                hash: parts.next()?.to_string(),
+                author: parts.next()?.to_string(),
+                date: parts.next()?.to_string(),
+                subject: parts.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect())
+}
+
+fn entry_at(setup: &Setup, rev: &str, key: &str) -> Option<Entry> {
+    let src = cmd!("git", "show", format!("{rev}:{}", bundle::BIB))
+        .dir(setup.root())
+        .stderr_null()
+        .read()
+        .ok()?;
+    let bib = Bibliography::parse(&src).ok()?;
+    bib.get(key).cloned()
+}
+
+pub fn run(setup: &Setup, entry: &Entry) -> Result<()> {
+    if setup.git().is_none() {
+        bail!(t!("blame-no-git"));
+    }
+
+    let mut history = Vec::new();
+    let mut previous: Option<Entry> = None;
+    for commit in commits(setup)? {
+        let current = entry_at(setup, &commit.hash, &entry.key);
+        let event = match (&previous, &current) {
+            (None, Some(_)) => Event::Added,
+            (Some(_), None) => Event::Removed,
+            (Some(old), Some(new)) => {
+                let fields = diff::diff_entries(old, new);
+                if fields.is_empty() {
+                    continue;
+                }
+                Event::Changed(fields)
+            }
+            (None, None) => continue,
+        };
+        debug!(commit=%commit.hash, "entry changed");
+        history.push((commit, event));
+        previous = current;
+    }
+
+    println!("{}", entry.key.bold());
+    if let Some(provenance) = provenance::get(entry) {
+        let fetched = provenance
+            .fetched
+            .map(|at| at.strftime(" (%Y-%m-%d)").to_string())
+            .unwrap_or_default();
+        println!(
+            "{}",
+            t!(
+                "blame-source",
+                source = provenance.source,
+                id = provenance.id,
+                fetched = fetched
+            )
+            .dimmed()
+        );
+    }
+    if history.is_empty() {
+        println!("{}", t!("blame-uncommitted"));
+        return Ok(());
+    }
+    for (commit, event) in history {
+        let what = match &event {
+            Event::Added => t!("blame-added").green().to_string(),
+            Event::Changed(_) => t!("blame-changed").yellow().to_string(),
+            Event::Removed => t!("blame-removed").red().to_string(),
+        };
+        println!(
+            "{} {} {} {}  {}",
+            (&commit.hash[..8]).dimmed(),
+            commit.date,
+            what,
+            commit.author.cyan(),
+            commit.subject.dimmed()
+        );
+        if let Event::Changed(fields) = &event {
+            diff::print_fields(fields);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,49 @@
+//! Fetching metadata for many entries at once.
+//!
+//! Bulk commands such as `doctor`, `altmetrics` and `pdfs` would otherwise
+//! spend most of their time waiting on one request after another. [`fetch`]
+//! runs the requests on a few worker threads and asks for each identifier only
+//! once per run, even when several entries share it.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Mutex,
+};
+
+use crate::{Result, cancel};
+
+/// Requests in flight at once, low enough to stay within the rate limits of
+/// the public APIs.
+const WORKERS: usize = 8;
+
+/// Calls `fetch` for each distinct id, calling `progress` with the id before
+/// each request. Fails only if the user cancels, the result of each request
+/// is in the returned map.
+pub fn fetch<T: Send>(
+    ids: impl IntoIterator<Item = String>,
+    progress: impl Fn(&str) + Sync,
+    fetch: impl Fn(&str) -> Result<T> + Sync,
+) -> Result<BTreeMap<String, Result<T>>> {
+    let ids: BTreeSet<String> = ids.into_iter().collect();
+    let queue = Mutex::new(ids.into_iter());
+    let results = Mutex::new(BTreeMap::new());
+    std::thread::scope(|scope| {
+        for _ in 0..WORKERS {
+            scope.spawn(|| {
+                loop {
+                    if cancel::cancelled() {
+                        break;
+                    }
+                    let Some(id) = queue.lock().unwrap().next() else {
+                        break;
+                    };
+                    progress(&id);
+                    let result = fetch(&id);
+                    results.lock().unwrap().insert(id, result);
+                }
+            });
+        }
+    });
+    cancel::check()?;
+    Ok(results.into_inner().unwrap())
+}
@@ -0,0 +1,266 @@
+//! Library bundles: a single zip archive containing the bibliography, notes,
+//! metadata, attachments and optionally PDFs, together with a `manifest.json`.
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    io::{Read, Write},
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use color_eyre::eyre::eyre;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+use zip::{ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+use crate::{
+    Result,
+    config::Setup,
+    dry_run, group,
+    merge::{self, MergeReport},
+    meta,
+    note::{self, NotesConfig},
+    provenance,
+};
+
+pub const MANIFEST: &str = "manifest.json";
+pub const BIB: &str = "references.bib";
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Manifest {
+    pub zime_version: String,
+    pub created: jiff::Timestamp,
+    pub includes_pdfs: bool,
+    /// Citation keys of all entries in the bundled bibliography
+    pub keys: Vec<String>,
+    pub files: Vec<ManifestFile>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ManifestFile {
+    /// Path inside the archive, always using `/` as separator
+    pub path: String,
+    pub size: u64,
+}
+
+pub fn export(setup: &Setup, out: &Utf8Path, include_pdfs: bool) -> Result<Manifest> {
+    let bib = setup.bib()?;
+
+    let mut files = vec![(setup.bib_path(), BIB.to_string())];
+    let mut dirs = vec![setup.notes_dir(), setup.meta_dir(), setup.attachments_dir()];
+    if include_pdfs {
+        dirs.push(setup.pdf_dir());
+    }
+    for dir in dirs {
+        for path in walk(&dir)? {
+            let name = path
+                .strip_prefix(setup.root())
+                .unwrap_or(&path)
+                .components()
+                .map(|c| c.as_str())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push((path, name));
+        }
+    }
+
+    let mut zip = ZipWriter::new(fs::File::create(out)?);
+    let options = SimpleFileOptions::default();
+    let mut manifest_files = Vec::with_capacity(files.len());
+    let mut buf = Vec::new();
+    for (path, name) in files {
+        debug!(%path, %name, "adding file to bundle");
+        buf.clear();
+        fs::File::open(&path)?.read_to_end(&mut buf)?;
+        zip.start_file(name.as_str(), options)?;
+        zip.write_all(&buf)?;
+        manifest_files.push(ManifestFile {
+            path: name,
+            size: buf.len() as u64,
+        });
+    }
+
+    let manifest = Manifest {
+        zime_version: env!("CARGO_PKG_VERSION").to_string(),
+        created: jiff::Timestamp::now(),
+        includes_pdfs: include_pdfs,
+        keys: bib.keys().map(|k| k.to_string()).collect(),
+        files: manifest_files,
+    };
+    zip.start_file(MANIFEST, options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+    zip.finish()?;
+
+    info!(%out, entries = manifest.keys.len(), files = manifest.files.len(), "wrote bundle");
+    Ok(manifest)
+}
+
+/// Merges a bundle into the library.
+///
+/// Entries go through the same duplicate detection as any other import. Notes,
+/// metadata and attachments follow their entry if it was renamed, and are attached to
+/// the existing entry if it was a duplicate. Files that already exist locally
+/// with different content are kept as they are. A file that belongs to no
+/// entry of the bundle fails the import before anything is written.
+pub fn import(setup: &Setup, path: &Utf8Path) -> Result<MergeReport> {
+    let mut archive = ZipArchive::new(fs::File::open(path)?)?;
+
+    let manifest: Manifest = serde_json::from_reader(
+        archive
+            .by_name(MANIFEST)
+            .map_err(|_| eyre!("{path} is not a zime bundle, it has no {MANIFEST}"))?,
+    )?;
+    debug!(version=%manifest.zime_version, created=%manifest.created, "reading bundle");
+
+    let mut src = String::new();
+    archive.by_name(BIB)?.read_to_string(&mut src)?;
+    let mut incoming = biblatex::Bibliography::parse(&src)
+        .map_err(|err| eyre!("failed to parse bibliography in bundle: {err}"))?;
+    // entries shared without an owner count as added by the importer
+    if let Some(name) = provenance::identity(setup) {
+        for entry in incoming.iter_mut() {
+            if provenance::added_by(entry).is_none() {
+                provenance::set_added_by(entry, &name);
+            }
+        }
+    }
+    let keys: BTreeSet<String> = incoming.keys().map(str::to_string).collect();
+
+    let mut bib = setup.bib()?;
+    let report = merge::merge(&mut bib, incoming);
+
+    let mut targets = Vec::new();
+    for i in 0..archive.len() {
+        let file = archive.by_index(i)?;
+        if file.is_dir() || [MANIFEST, BIB].contains(&file.name()) {
+            continue;
+        }
+        let name = file
+            .enclosed_name()
+            .and_then(|name| Utf8PathBuf::from_path_buf(name).ok())
+            .ok_or_else(|| eyre!("{} in the bundle has an unsafe path", file.name()))?;
+        let target = target_path(setup, &keys, &report, &name)?;
+        targets.push((i, target));
+    }
+    setup.write_bib(&bib)?;
+
+    for (i, target) in targets {
+        let mut file = archive.by_index(i)?;
+        let mut content = Vec::new();
+        file.read_to_end(&mut content)?;
+        if target.exists() {
+            if fs::read(&target)? != content {
+                warn!(%target, "keeping local file, bundle has a different version");
+            }
+            continue;
+        }
+        if dry_run::enabled() {
+            dry_run::would_write(&target);
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        debug!(%target, "writing file from bundle");
+        fs::write(&target, content)?;
+    }
+
+    Ok(report)
+}
+
+/// Where a file from a bundle should be placed in the library, following
+/// renamed and deduplicated entries.
+///
+/// The file is matched against the layouts of [`note::path`], including the
+/// directories of reading group members, [`meta::path`] and the attachments
+/// directory, for each of the bundle's `keys`, so that keys containing `/`,
+/// such as DBLP keys, are found.
+fn target_path(
+    setup: &Setup,
+    keys: &BTreeSet<String>,
+    report: &MergeReport,
+    name: &Utf8Path,
+) -> Result<Utf8PathBuf> {
+    let name = name
+        .components()
+        .map(|c| c.as_str())
+        .collect::<Vec<_>>()
+        .join("/");
+    let unplaced = || eyre!("{name} in the bundle does not belong to any of its entries");
+    let (dir, rest) = name.split_once('/').ok_or_else(unplaced)?;
+    if dir == "pdfs" {
+        return Ok(setup.pdf_dir().join(rest));
+    }
+    let new_key = |key: &str| {
+        report
+            .new_key(key)
+            .or_else(|| {
+                report
+                    .duplicates
+                    .iter()
+                    .find(|(incoming, _)| incoming == key)
+                    .map(|(_, existing)| existing.as_str())
+            })
+            .map(str::to_string)
+            .ok_or_else(unplaced)
+    };
+
+    match dir {
+        "notes" => {
+            let (stem, extension) = rest.rsplit_once('.').ok_or_else(unplaced)?;
+            let (member, key) = if keys.contains(stem) {
+                (None, stem)
+            } else {
+                match stem.split_once('/') {
+                    Some((member, key)) if keys.contains(key) && group::slug(member) == member => {
+                        (Some(member), key)
+                    }
+                    _ => return Err(unplaced()),
+                }
+            };
+            let config = NotesConfig {
+                extension: extension.to_string(),
+                ..Default::default()
+            };
+            Ok(note::path(setup, &config, member, &new_key(key)?))
+        }
+        "meta" => {
+            let key = rest.strip_suffix(".yaml").ok_or_else(unplaced)?;
+            if !keys.contains(key) {
+                return Err(unplaced());
+            }
+            Ok(meta::path(setup, &new_key(key)?))
+        }
+        "attachments" => {
+            // The longest key, in case one key is a prefix of another
+            let (key, file) = keys
+                .iter()
+                .filter_map(|key| Some((key, rest.strip_prefix(key.as_str())?.strip_prefix('/')?)))
+                .max_by_key(|(key, _)| key.len())
+                .ok_or_else(unplaced)?;
+            Ok(setup.attachments_dir().join(new_key(key)?).join(file))
+        }
+        _ => Err(unplaced()),
+    }
+}
+
+/// Lists all files below `dir` recursively, returning nothing if it does not exist.
+pub fn walk(dir: &Utf8Path) -> Result<Vec<Utf8PathBuf>> {
+    let mut files = Vec::new();
+    if !dir.exists() {
+        return Ok(files);
+    }
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        for entry in dir.read_dir_utf8()? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                stack.push(entry.into_path());
+            } else {
+                files.push(entry.into_path());
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
@@ -0,0 +1,67 @@
+//! Ctrl-C handling.
+//!
+//! The first Ctrl-C asks the running command to stop at the next safe point:
+//! loops over entries call [`check`], which fails once cancelled, so any open
+//! [`Transaction`](crate::transaction::Transaction) rolls the library back on
+//! the way out. Git runs in the same process group, receives the signal itself
+//! and fails, which rolls back the same way, aborting a half-finished rebase.
+//!
+//! A second Ctrl-C exits immediately, after removing files that were being
+//! written with [`write`].
+
+use std::{
+    fs,
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use color_eyre::eyre::bail;
+
+use crate::{Result, i18n::t};
+
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+/// Files being written, removed when exiting on a second Ctrl-C
+static PARTIAL: Mutex<Vec<Utf8PathBuf>> = Mutex::new(Vec::new());
+
+pub fn install() -> Result<()> {
+    ctrlc::set_handler(|| {
+        if CANCELLED.swap(true, Ordering::SeqCst) {
+            if let Ok(partial) = PARTIAL.lock() {
+                for path in partial.iter() {
+                    let _ = fs::remove_file(path);
+                }
+            }
+            std::process::exit(130);
+        }
+        eprintln!("{}", t!("cancelling"));
+    })?;
+    Ok(())
+}
+
+pub fn cancelled() -> bool {
+    CANCELLED.load(Ordering::SeqCst)
+}
+
+/// Fails if the user pressed Ctrl-C.
+pub fn check() -> Result<()> {
+    if cancelled() {
+        bail!(t!("cancelled"));
+    }
+    Ok(())
+}
+
+/// Writes `contents` to `path` through a temporary file, so that an
+/// interrupted write never leaves a truncated file at `path`.
+pub fn write(path: &Utf8Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let part = Utf8PathBuf::from(format!("{path}.part"));
+    PARTIAL.lock().unwrap().push(part.clone());
+    let result = fs::write(&part, contents).and_then(|()| fs::rename(&part, path));
+    PARTIAL.lock().unwrap().retain(|p| p != &part);
+    if result.is_err() {
+        let _ = fs::remove_file(&part);
+    }
+    Ok(result?)
+}
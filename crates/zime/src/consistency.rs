@@ -0,0 +1,186 @@
+//! Keeping the files that belong to entries in line with the bibliography
+//! when a command rewrites entries in bulk.
+//!
+//! Notes, metadata and attachments are named after keys, the read status in
+//! `zime.lock` is kept by key and PDFs are named after DOIs. [`apply`] moves
+//! all of them in one pass when keys or DOIs change, recording each move in
+//! the command's [`Transaction`], so that a failing step puts the library back
+//! as a whole instead of leaving it half-migrated.
+
+use std::collections::BTreeMap;
+
+use biblatex::{Bibliography, Chunk, ChunksExt, Spanned};
+use camino::Utf8PathBuf;
+use color_eyre::eyre::bail;
+use tracing::info;
+
+use crate::{
+    Result, backup, config::Setup, doi, dry_run, export, i18n::t, merge, meta, note,
+    pdfs::Manifest, select, sidecar::Sidecar, transaction::Transaction, versions,
+};
+
+/// The files and directories belonging to the entry `old`, with where each
+/// goes when the entry is renamed to `new`: its notes, including those of each
+/// reading group member, metadata and attachments.
+fn key_files(setup: &Setup, old: &str, new: &str) -> Result<Vec<(Utf8PathBuf, Utf8PathBuf)>> {
+    let notes = setup.config()?.notes;
+    let mut files = vec![(
+        note::path(setup, &notes, None, old),
+        note::path(setup, &notes, None, new),
+    )];
+    for member in note::members(setup)? {
+        files.push((
+            note::path(setup, &notes, Some(&member), old),
+            note::path(setup, &notes, Some(&member), new),
+        ));
+    }
+    files.push((meta::path(setup, old), meta::path(setup, new)));
+    let attachments = setup.attachments_dir();
+    files.push((attachments.join(old), attachments.join(new)));
+    files.retain(|(from, _)| from.exists());
+    Ok(files)
+}
+
+/// Moves the files of the entries whose key or DOI changed between `before`
+/// and `after`, where `renames` maps old keys to new ones, and points
+/// `crossref` and `xdata` fields and version links at the new keys. The
+/// sidecar and PDF manifest are saved, the bibliography is left for the caller
+/// to write.
+pub fn apply(
+    setup: &Setup,
+    tx: &mut Transaction,
+    before: &Bibliography,
+    after: &mut Bibliography,
+    renames: &BTreeMap<String, String>,
+) -> Result<()> {
+    let mut moves = Vec::new();
+    for (old, new) in renames {
+        moves.extend(key_files(setup, old, new)?);
+    }
+
+    let mut manifest = Manifest::load(setup)?;
+    let old_keys: BTreeMap<&str, &str> = renames
+        .iter()
+        .map(|(old, new)| (new.as_str(), old.as_str()))
+        .collect();
+    for entry in after.iter() {
+        let old_key = old_keys.get(entry.key.as_str()).copied();
+        let Some(previous) = before.get(old_key.unwrap_or(&entry.key)) else {
+            continue;
+        };
+        let (Ok(old_doi), Ok(new_doi)) = (previous.doi(), entry.doi()) else {
+            continue;
+        };
+        if doi::eq(&old_doi, &new_doi) {
+            continue;
+        }
+        let from = setup.pdf_path(&old_doi);
+        if from.exists() {
+            let to = setup.pdf_path(&new_doi);
+            if let Some(mut record) = manifest.files.remove(&doi::normalize(&old_doi)) {
+                record.file = to.file_name().unwrap_or_default().to_string();
+                manifest.files.insert(doi::normalize(&new_doi), record);
+            }
+            moves.push((from, to));
+        }
+    }
+
+    // Check every move before making any, so that a clash does not leave
+    // some files moved
+    for (from, to) in &moves {
+        if to.exists() {
+            bail!("cannot move {from} to {to}, which already exists");
+        }
+    }
+    for (from, to) in &moves {
+        info!(%from, %to, "moving");
+        tx.rename(from, to)?;
+    }
+
+    if !renames.is_empty() {
+        let mut sidecar = Sidecar::load(setup)?;
+        for (old, new) in renames {
+            if let Some(meta) = sidecar.entries.remove(old) {
+                sidecar.entries.insert(new.clone(), meta);
+            }
+        }
+        for entry in after.iter_mut() {
+            for field in ["crossref", "xdata"] {
+                let Some(value) = entry.get(field) else {
+                    continue;
+                };
+                let keys = value
+                    .format_verbatim()
+                    .split(',')
+                    .map(str::trim)
+                    .map(|key| renames.get(key).map_or(key, String::as_str))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                entry.set(field, vec![Spanned::detached(Chunk::Verbatim(keys))]);
+            }
+            let mut linked = versions::get(entry);
+            if linked
+                .iter()
+                .any(|version| renames.contains_key(&version.key))
+            {
+                for version in &mut linked {
+                    if let Some(new) = renames.get(&version.key) {
+                        version.key = new.clone();
+                    }
+                }
+                versions::set(entry, &linked);
+            }
+        }
+        if !dry_run::enabled() {
+            sidecar.save(setup)?;
+        }
+    }
+    if !moves.is_empty() && !dry_run::enabled() {
+        manifest.save(setup)?;
+    }
+    Ok(())
+}
+
+/// Renames the entries matching `query` to `to`, or else to keys derived from
+/// their metadata such as `reynolds2002separation`, moving their files along.
+pub fn rekey(setup: &Setup, query: &str, to: Option<&str>, all: bool) -> Result<()> {
+    let before = setup.bib()?;
+    let entries = select::find_many(&before, query, &t!("select-article-to-rekey"), all)?;
+    if to.is_some() && entries.len() > 1 {
+        bail!(t!("rekey-one-only", count = entries.len()));
+    }
+
+    let mut after = before.clone();
+    let mut renames = BTreeMap::new();
+    for entry in &entries {
+        let base = to.map_or_else(|| export::derived_key(entry), str::to_string);
+        if base == entry.key {
+            continue;
+        }
+        let key = match to {
+            Some(_) if after.get(&base).is_some() => bail!(t!("rekey-taken", key = base.as_str())),
+            Some(_) => base,
+            None if after.get(&base).is_some() => merge::free_key(&after, &base),
+            None => base,
+        };
+        let Some(mut renamed) = after.remove(&entry.key) else {
+            continue;
+        };
+        println!("{} -> {key}", entry.key);
+        renamed.key = key.clone();
+        after.insert(renamed);
+        renames.insert(entry.key.clone(), key);
+    }
+    println!("{}", t!("rekeyed", count = renames.len()));
+    if renames.is_empty() {
+        return Ok(());
+    }
+
+    backup::before_destructive(setup, "rekey")?;
+    let mut tx = Transaction::begin(setup)?;
+    apply(setup, &mut tx, &before, &mut after, &renames)?;
+    setup.write_bib(&after)?;
+    setup.sync_git()?;
+    tx.commit();
+    Ok(())
+}
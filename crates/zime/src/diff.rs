@@ -0,0 +1,123 @@
+//! Entry-level differences between two bibliographies.
+
+use std::collections::BTreeSet;
+
+use biblatex::{Bibliography, ChunksExt, Entry};
+use color_eyre::owo_colors::OwoColorize;
+
+#[derive(Debug)]
+pub enum EntryChange<'a> {
+    Added(&'a Entry),
+    Removed(&'a Entry),
+    Changed {
+        key: &'a str,
+        fields: Vec<FieldChange>,
+    },
+}
+
+#[derive(Debug)]
+pub struct FieldChange {
+    pub name: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+pub fn diff<'a>(old: &'a Bibliography, new: &'a Bibliography) -> Vec<EntryChange<'a>> {
+    let mut changes = Vec::new();
+    for entry in old.iter() {
+        match new.get(&entry.key) {
+            None => changes.push(EntryChange::Removed(entry)),
+            Some(other) => {
+                let fields = diff_entries(entry, other);
+                if !fields.is_empty() {
+                    changes.push(EntryChange::Changed {
+                        key: &entry.key,
+                        fields,
+                    });
+                }
+            }
+        }
+    }
+    for entry in new.iter() {
+        if old.get(&entry.key).is_none() {
+            changes.push(EntryChange::Added(entry));
+        }
+    }
+    changes
+}
+
+/// Field-level differences between two versions of an entry.
+pub fn diff_entries(old: &Entry, new: &Entry) -> Vec<FieldChange> {
+    let mut fields = Vec::new();
+    if old.entry_type != new.entry_type {
+        fields.push(FieldChange {
+            name: "type".to_string(),
+            old: Some(old.entry_type.to_string()),
+            new: Some(new.entry_type.to_string()),
+        });
+    }
+    let names: BTreeSet<&String> = old.fields.keys().chain(new.fields.keys()).collect();
+    for name in names {
+        let old = old.fields.get(name).map(|c| c.format_verbatim());
+        let new = new.fields.get(name).map(|c| c.format_verbatim());
+        if old != new {
+            fields.push(FieldChange {
+                name: name.clone(),
+                old,
+                new,
+            });
+        }
+    }
+    fields
+}
+
+pub fn print(changes: &[EntryChange]) {
+    for change in changes {
+        match change {
+            EntryChange::Added(entry) => {
+                println!("{} {}  {}", "+".green(), entry.key.green(), title(entry));
+            }
+            EntryChange::Removed(entry) => {
+                println!("{} {}  {}", "-".red(), entry.key.red(), title(entry));
+            }
+            EntryChange::Changed { key, fields } => {
+                println!("{} {}", "~".yellow(), key.yellow());
+                print_fields(fields);
+            }
+        }
+    }
+}
+
+pub fn print_fields(fields: &[FieldChange]) {
+    for field in fields {
+        println!("    {}:", field.name.bold());
+        if let Some(old) = &field.old {
+            println!("      {} {}", "-".red(), old.red());
+        }
+        if let Some(new) = &field.new {
+            println!("      {} {}", "+".green(), new.green());
+        }
+    }
+}
+
+/// The changes as plain text, one entry per line, for commit messages.
+pub fn manifest(changes: &[EntryChange]) -> String {
+    let mut out = String::new();
+    for change in changes {
+        let line = match change {
+            EntryChange::Added(entry) => format!("+ {}  {}", entry.key, title(entry)),
+            EntryChange::Removed(entry) => format!("- {}  {}", entry.key, title(entry)),
+            EntryChange::Changed { key, fields } => {
+                let names: Vec<&str> = fields.iter().map(|f| f.name.as_str()).collect();
+                format!("~ {key}  {}", names.join(", "))
+            }
+        };
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+fn title(entry: &Entry) -> String {
+    entry.title().unwrap_or_default().format_verbatim()
+}
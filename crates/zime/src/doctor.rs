@@ -0,0 +1,130 @@
+//! Health checks for the entries in the library: titles and abstracts with
+//! mangled math or characters, see [`crate::markup`], and retracted works.
+
+use color_eyre::owo_colors::OwoColorize;
+use itertools::Itertools;
+use tracing::warn;
+
+use crate::{
+    Result, bulk, config::Setup, doi, entry, i18n::t, markup, prompt, remotes,
+    transaction::Transaction,
+};
+
+/// Checks Crossref for retractions and errata of every entry with a DOI.
+pub fn check_updates(setup: &Setup) -> Result<usize> {
+    let bib = setup.bib()?;
+    let mut problems = 0;
+
+    let spinner = prompt::spinner();
+    spinner.start(t!("doctor-checking"));
+    let dois = bib
+        .iter()
+        .filter_map(|entry| entry.doi().ok())
+        .map(|id| doi::normalize(&id));
+    let works = bulk::fetch(
+        dois,
+        |doi| spinner.set_message(doi),
+        remotes::crossref::fetch_work,
+    )?;
+    spinner.stop(t!("doctor-checked"));
+
+    let mut found = Vec::new();
+    for entry in bib.iter() {
+        let Ok(id) = entry.doi() else { continue };
+        match &works[&doi::normalize(&id)] {
+            Ok(work) if !work.updated_by.is_empty() => found.push((entry, work)),
+            Ok(_) => {}
+            Err(err) => warn!(doi = %id, %err, "failed to look up DOI on Crossref"),
+        }
+    }
+
+    for (entry, work) in found {
+        for update in &work.updated_by {
+            let date = update
+                .updated
+                .as_ref()
+                .and_then(|d| d.date_parts.first())
+                .map(|parts| {
+                    parts
+                        .iter()
+                        .map(|p| p.to_string())
+                        .collect::<Vec<_>>()
+                        .join("-")
+                })
+                .unwrap_or_default();
+            let notice = format!(
+                "{} {} (https://doi.org/{})",
+                update.update_type, date, update.doi
+            );
+            if update.is_retraction() {
+                problems += 1;
+                println!("{} {}  {}", "✗".red(), entry::title(entry), notice.red());
+            } else {
+                println!(
+                    "{} {}  {}",
+                    "!".yellow(),
+                    entry::title(entry),
+                    notice.yellow()
+                );
+            }
+        }
+    }
+
+    Ok(problems)
+}
+
+/// Lists the entries whose title or abstract a remote mangled, repairing
+/// them from other remotes if `repair` is set. Returns how many are left.
+pub fn check_markup(setup: &Setup, repair: bool) -> Result<usize> {
+    let mut bib = setup.bib()?;
+    let mut left = 0;
+    let mut repaired = 0;
+    for entry in bib.iter_mut() {
+        let problems = markup::check(entry);
+        if problems.is_empty() {
+            continue;
+        }
+        let described = problems
+            .iter()
+            .map(|(field, problems)| {
+                let problems = problems.iter().map(|p| p.describe()).join(", ");
+                format!("{field}: {problems}")
+            })
+            .join("; ");
+        if repair {
+            if let Some(source) = markup::repair(entry) {
+                repaired += 1;
+                println!(
+                    "{} {}  {}",
+                    "✓".green(),
+                    entry.key,
+                    t!("markup-repaired", source = source).dimmed()
+                );
+                continue;
+            }
+        }
+        left += 1;
+        println!("{} {}  {}", "!".yellow(), entry.key, described.yellow());
+    }
+    if repaired > 0 {
+        let tx = Transaction::begin(setup)?;
+        setup.write_bib(&bib)?;
+        setup.sync_git()?;
+        tx.commit();
+    }
+    Ok(left)
+}
+
+pub fn run(setup: &Setup, repair: bool) -> Result<()> {
+    let mangled = check_markup(setup, repair)?;
+    if mangled > 0 {
+        println!("{}", t!("markup-found", count = mangled));
+    }
+    let retracted = check_updates(setup)?;
+    if retracted == 0 {
+        println!("{}", t!("doctor-none"));
+    } else {
+        println!("{}", t!("doctor-retracted", count = retracted));
+    }
+    Ok(())
+}
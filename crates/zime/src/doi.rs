@@ -0,0 +1,99 @@
+//! Helpers for working with DOIs.
+
+use std::fmt::Write;
+
+/// Normalizes a DOI for comparison and for embedding in URLs and file names.
+///
+/// DOIs are case-insensitive, and are often written with a resolver prefix
+/// such as `https://doi.org/` or `doi:`, which is stripped.
+pub fn normalize(doi: &str) -> String {
+    let doi = doi.trim();
+    let lower = doi.to_lowercase();
+    let prefix = [
+        "https://doi.org/",
+        "http://doi.org/",
+        "https://dx.doi.org/",
+        "http://dx.doi.org/",
+        "doi:",
+    ]
+    .into_iter()
+    .find(|prefix| lower.starts_with(prefix))
+    .map_or(0, str::len);
+    lower[prefix..].trim().to_string()
+}
+
+/// Whether two DOIs refer to the same object.
+pub fn eq(a: &str, b: &str) -> bool {
+    normalize(a) == normalize(b)
+}
+
+/// Encodes a DOI as a file name stem.
+///
+/// Everything except ASCII alphanumerics, `.`, `-` and `_` is percent-encoded,
+/// so the result is valid on all common filesystems and can be decoded back
+/// into the normalized DOI with [`from_file_stem`].
+pub fn to_file_stem(doi: &str) -> String {
+    percent_encode(&normalize(doi), |byte| matches!(byte, b'.' | b'-' | b'_'))
+}
+
+/// Encodes a DOI for use as the path of a URL, such as `https://doi.org/{path}`.
+///
+/// The `/` between prefix and suffix is kept, everything outside the RFC 3986
+/// unreserved set is percent-encoded.
+pub fn url_path(doi: &str) -> String {
+    percent_encode(&normalize(doi), |byte| {
+        matches!(byte, b'.' | b'-' | b'_' | b'~' | b'/')
+    })
+}
+
+fn percent_encode(s: &str, keep: impl Fn(u8) -> bool) -> String {
+    let mut out = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        if byte.is_ascii_alphanumeric() || keep(byte) {
+            out.push(byte as char);
+        } else {
+            write!(out, "%{byte:02X}").unwrap();
+        }
+    }
+    out
+}
+
+/// Decodes a file name stem produced by [`to_file_stem`].
+pub fn from_file_stem(stem: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(stem.len());
+    let mut iter = stem.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            let hex = std::str::from_utf8(&hex).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// The file name stem used before DOIs were percent-encoded, which only
+/// replaced `/` with `--`.
+pub fn legacy_file_stem(doi: &str) -> String {
+    doi.replace("/", "--")
+}
+
+/// The first DOI in `text`, e.g. in the metadata or first page of a PDF.
+pub fn find(text: &str) -> Option<String> {
+    text.match_indices("10.").find_map(|(start, _)| {
+        let rest = &text[start..];
+        let (prefix, suffix) = rest.split_once('/')?;
+        let registrant = &prefix[3..];
+        if !(4..=9).contains(&registrant.len()) || !registrant.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let suffix: String = suffix
+            .chars()
+            .take_while(|c| c.is_ascii_graphic() && !matches!(c, '"' | '<' | '>' | '\\'))
+            .collect();
+        let suffix = suffix.trim_end_matches(['.', ',', ';', ')', ']', '}']);
+        (!suffix.is_empty()).then(|| normalize(&format!("{prefix}/{suffix}")))
+    })
+}
@@ -0,0 +1,27 @@
+//! The global `--dry-run` flag.
+//!
+//! Commands do their usual work, but the places that change the library
+//! (writing the bibliography and sidecar, taking snapshots, placing imported
+//! files and syncing git) print what they would do instead.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use camino::Utf8Path;
+use color_eyre::owo_colors::OwoColorize;
+
+use crate::i18n::t;
+
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+pub fn enable() {
+    DRY_RUN.store(true, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    DRY_RUN.load(Ordering::Relaxed)
+}
+
+/// Reports a file that would have been written.
+pub fn would_write(path: &Utf8Path) {
+    println!("{}", t!("dry-run-write", path = path.as_str()).dimmed());
+}
@@ -0,0 +1,92 @@
+//! Accessors for the fields of bibliography entries that zime cares about.
+
+use biblatex::{Bibliography, Chunk, ChunksExt, DateValue, Entry, PermissiveType, Spanned};
+use itertools::Itertools;
+
+/// The title as it should be displayed.
+pub fn title(entry: &Entry) -> String {
+    entry.title().unwrap_or_default().to_biblatex_string(true)
+}
+
+/// The authors as a comma separated list.
+pub fn authors(entry: &Entry) -> String {
+    entry
+        .author()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|a| a.to_string())
+        .join(", ")
+}
+
+pub fn year(entry: &Entry) -> Option<i32> {
+    let PermissiveType::Typed(date) = entry.date().ok()? else {
+        return None;
+    };
+    match date.value {
+        DateValue::At(date)
+        | DateValue::After(date)
+        | DateValue::Before(date)
+        | DateValue::Between(date, _) => Some(date.year),
+    }
+}
+
+/// The tags of an entry, stored in the comma separated `keywords` field.
+pub fn tags(entry: &Entry) -> Vec<String> {
+    entry
+        .keywords()
+        .map(|keywords| {
+            keywords
+                .format_verbatim()
+                .split(',')
+                .map(|tag| tag.trim().to_string())
+                .filter(|tag| !tag.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Replaces the tags of an entry, removing the `keywords` field if there are
+/// none.
+pub fn set_tags(entry: &mut Entry, tags: &[String]) {
+    if tags.is_empty() {
+        entry.remove("keywords");
+    } else {
+        entry.set(
+            "keywords",
+            vec![Spanned::detached(Chunk::Normal(tags.join(", ")))],
+        );
+    }
+}
+
+/// The journal or proceedings the entry was published in.
+pub fn venue(entry: &Entry) -> Option<String> {
+    entry
+        .journal()
+        .or_else(|_| entry.book_title())
+        .ok()
+        .map(|venue| venue.format_verbatim())
+}
+
+/// The keys of the entries `entry` inherits fields from through its
+/// `crossref` and `xdata` fields.
+pub fn parents(entry: &Entry) -> Vec<String> {
+    ["crossref", "xdata"]
+        .into_iter()
+        .filter_map(|field| entry.get(field))
+        .flat_map(|value| {
+            value
+                .format_verbatim()
+                .split(',')
+                .map(|key| key.trim().to_string())
+                .filter(|key| !key.is_empty())
+                .collect_vec()
+        })
+        .collect()
+}
+
+/// The entries of `bib` that inherit fields from the entry with `key`.
+pub fn dependents<'a>(bib: &'a Bibliography, key: &str) -> Vec<&'a Entry> {
+    bib.iter()
+        .filter(|entry| parents(entry).iter().any(|parent| parent == key))
+        .collect()
+}
@@ -0,0 +1,322 @@
+//! `zime export`: the library as a plain bibliography without zime's own
+//! fields, for sharing or for a submission.
+//!
+//! With `--at` the bibliography is read from a commit or tag in the library's
+//! git history instead, so the exact bibliography a paper was built with can
+//! be reproduced later.
+//!
+//! With `--anonymize` only the fields a reference list needs are kept, which
+//! drops tags, annotations, abstracts and custom fields, and entries get keys
+//! derived from their metadata such as `reynolds2002separation`, so that
+//! neither reveals who wrote the submission.
+//!
+//! What was exported to each file is remembered, so that `--since-last` can
+//! write only the entries that are new or changed since, for keeping a vault,
+//! website or reference manager in sync cheaply. The state is kept in the
+//! cache directory, as it belongs to files on this machine; if it is lost, the
+//! next export is a full one.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+};
+
+use biblatex::{Bibliography, Chunk, ChunksExt, Entry, Spanned};
+use camino::{Utf8Path, Utf8PathBuf};
+use color_eyre::eyre::eyre;
+use duct::cmd;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use crate::{
+    Result, bundle,
+    config::{self, Setup},
+    dry_run, entry, provenance, sidecar, store,
+};
+
+/// The file in the cache directory with what was exported where.
+const STATE: &str = "exports.json";
+
+/// The export state, by library and destination.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct State {
+    destinations: BTreeMap<String, Destination>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct Destination {
+    exported: Option<jiff::Timestamp>,
+    /// The checksum of each exported entry, by key
+    checksums: BTreeMap<String, String>,
+}
+
+impl State {
+    fn path() -> Result<Utf8PathBuf> {
+        Ok(config::cache_dir()?.join(STATE))
+    }
+
+    fn load() -> Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        serde_json::from_str(&fs::read_to_string(&path)?)
+            .map_err(|err| eyre!("failed to parse {path}: {err}"))
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Identifies the destination `out` of exports from the library, with `-`
+/// for standard output.
+fn destination_id(setup: &Setup, out: Option<&Utf8Path>) -> String {
+    let out = out.map_or_else(|| "-".to_string(), |out| absolute(out).to_string());
+    format!("{} -> {out}", setup.root())
+}
+
+/// The absolute path of `path`, with symlinks resolved, whether the file
+/// exists yet or not. Its directory is resolved rather than the file itself,
+/// so the first export to a file is recorded under the same path as the rest.
+fn absolute(path: &Utf8Path) -> Utf8PathBuf {
+    let absolute = std::path::absolute(path)
+        .ok()
+        .and_then(|path| Utf8PathBuf::from_path_buf(path).ok())
+        .unwrap_or_else(|| path.to_path_buf());
+    match (absolute.parent(), absolute.file_name()) {
+        (Some(dir), Some(name)) => dir
+            .canonicalize_utf8()
+            .map_or_else(|_| absolute.clone(), |dir| dir.join(name)),
+        _ => absolute,
+    }
+}
+
+/// Records `bib` as exported to `destination`, and with `since_last` keeps
+/// only the entries that changed since the previous export to it.
+fn record(
+    state: &mut State,
+    destination: &str,
+    bib: Bibliography,
+    since_last: bool,
+) -> Bibliography {
+    let checksums: BTreeMap<String, String> = bib
+        .iter()
+        .map(|entry| (entry.key.clone(), sidecar::checksum(entry)))
+        .collect();
+    let previous = state.destinations.remove(destination).unwrap_or_default();
+    let bib = if since_last {
+        let mut changed = Bibliography::new();
+        for entry in bib.into_iter() {
+            if previous.checksums.get(&entry.key) != checksums.get(&entry.key) {
+                changed.insert(entry);
+            }
+        }
+        let removed = previous
+            .checksums
+            .keys()
+            .filter(|key| !checksums.contains_key(*key))
+            .count();
+        info!(
+            since = ?previous.exported,
+            changed = changed.len(),
+            removed,
+            "exporting changes since the last export"
+        );
+        changed
+    } else {
+        bib
+    };
+    state.destinations.insert(
+        destination.to_string(),
+        Destination {
+            exported: Some(jiff::Timestamp::now()),
+            checksums,
+        },
+    );
+    bib
+}
+
+/// The fields `--anonymize` keeps.
+const CITATION_FIELDS: &[&str] = &[
+    "address",
+    "archiveprefix",
+    "author",
+    "booktitle",
+    "chapter",
+    "crossref",
+    "date",
+    "doi",
+    "edition",
+    "editor",
+    "eprint",
+    "eprintclass",
+    "eprinttype",
+    "eventtitle",
+    "howpublished",
+    "institution",
+    "isbn",
+    "issn",
+    "journal",
+    "journaltitle",
+    "location",
+    "maintitle",
+    "month",
+    "number",
+    "organization",
+    "pages",
+    "primaryclass",
+    "publisher",
+    "school",
+    "series",
+    "subtitle",
+    "title",
+    "type",
+    "url",
+    "venue",
+    "volume",
+    "xdata",
+    "year",
+];
+
+/// Words skipped when picking the title word of a key.
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "the", "on", "of", "for", "in", "to", "and", "with",
+];
+
+/// The bibliography as of the git revision `rev`.
+fn at_revision(setup: &Setup, rev: &str) -> Result<Bibliography> {
+    let src = cmd!("git", "show", format!("{rev}:./{}", bundle::BIB))
+        .dir(setup.root())
+        .stderr_null()
+        .read()
+        .map_err(|_| {
+            eyre!(
+                "{rev} is not a commit with a bibliography in {}",
+                setup.root()
+            )
+        })?;
+    store::parse(&src).map_err(|err| eyre!("failed to parse the bibliography at {rev}: {err}"))
+}
+
+/// A key made of the first author's family name, the year and the first word
+/// of the title, e.g. `reynolds2002separation`.
+pub fn derived_key(entry: &Entry) -> String {
+    let family = entry
+        .author()
+        .or_else(|_| {
+            entry
+                .editors()
+                .map(|editors| editors.into_iter().flat_map(|(e, _)| e).collect())
+        })
+        .ok()
+        .and_then(|people| people.into_iter().next())
+        .map(|person| person.name)
+        .unwrap_or_else(|| "anonymous".to_string());
+    let year = entry::year(entry)
+        .map(|y| y.to_string())
+        .unwrap_or_default();
+    let title = entry::title(entry);
+    let word = title
+        .split_whitespace()
+        .map(|word| {
+            word.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .find(|word| !word.is_empty() && !STOP_WORDS.contains(&word.as_str()))
+        .unwrap_or_default();
+    deunicode::deunicode(&format!("{family}{year}{word}"))
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// Keeps only [`CITATION_FIELDS`] and renames every entry to its
+/// [`derived_key`], updating `crossref` and `xdata` to match.
+fn anonymize(bib: &Bibliography) -> Bibliography {
+    let mut taken = BTreeSet::new();
+    let renames: BTreeMap<String, String> = bib
+        .iter()
+        .map(|entry| {
+            let base = derived_key(entry);
+            let key = std::iter::once(base.clone())
+                .chain(('a'..='z').map(|suffix| format!("{base}{suffix}")))
+                .chain((2..).map(|n| format!("{base}-{n}")))
+                .find(|key| !taken.contains(key))
+                .unwrap();
+            taken.insert(key.clone());
+            (entry.key.clone(), key)
+        })
+        .collect();
+
+    let mut anonymized = Bibliography::new();
+    for entry in bib.iter() {
+        let mut entry = entry.clone();
+        entry.key = renames[&entry.key].clone();
+        entry
+            .fields
+            .retain(|name, _| CITATION_FIELDS.contains(&name.as_str()));
+        for field in ["crossref", "xdata"] {
+            if let Some(value) = entry.get(field) {
+                let keys = value
+                    .format_verbatim()
+                    .split(',')
+                    .map(|key| key.trim())
+                    .map(|key| renames.get(key).map_or(key, String::as_str))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                entry.set(field, vec![Spanned::detached(Chunk::Verbatim(keys))]);
+            }
+        }
+        anonymized.insert(entry);
+    }
+    for (old, new) in &renames {
+        info!(%old, %new, "renamed entry");
+    }
+    anonymized
+}
+
+/// Writes the bibliography to `out`, or to standard output, only the entries
+/// changed since the last export there if `since_last` is set.
+pub fn run(
+    setup: &Setup,
+    at: Option<&str>,
+    out: Option<&Utf8Path>,
+    anonymize: bool,
+    since_last: bool,
+) -> Result<()> {
+    let mut bib = match at {
+        Some(rev) => at_revision(setup, rev)?,
+        None => setup.bib()?,
+    };
+    for entry in bib.iter_mut() {
+        provenance::clear(entry);
+    }
+    if anonymize {
+        bib = self::anonymize(&bib);
+    }
+    let mut state = State::load()?;
+    let destination = destination_id(setup, out);
+    let bib = record(&mut state, &destination, bib, since_last);
+    let content = bib.to_biblatex_string();
+    match out {
+        Some(out) if dry_run::enabled() => dry_run::would_write(out),
+        Some(out) => {
+            fs::write(out, content)?;
+            info!(%out, entries = bib.len(), "wrote bibliography");
+        }
+        None => print!("{content}"),
+    }
+    if dry_run::enabled() {
+        debug!(%destination, "not recording the export in a dry run");
+    } else {
+        state.save()?;
+    }
+    Ok(())
+}
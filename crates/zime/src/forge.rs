@@ -0,0 +1,210 @@
+//! Creation of remote repositories on GitHub and GitLab.
+//!
+//! `zime init --git github:user/papers` resolves the shorthand to an SSH
+//! remote, creating the repository through the forge API when it does not
+//! exist yet.
+
+use color_eyre::eyre::{bail, eyre};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use crate::{Result, http};
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ForgeConfig {
+    /// Personal access token with permission to create repositories on GitHub
+    pub github_token: Option<String>,
+    /// Personal access token with the `api` scope on GitLab
+    pub gitlab_token: Option<String>,
+    /// Base URL of the GitLab instance
+    pub gitlab_url: String,
+    /// Create new repositories as private
+    pub private: bool,
+}
+
+impl Default for ForgeConfig {
+    fn default() -> Self {
+        Self {
+            github_token: None,
+            gitlab_token: None,
+            gitlab_url: "https://gitlab.com".to_string(),
+            private: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Forge {
+    GitHub,
+    GitLab,
+}
+
+/// A repository on a forge, written as `github:owner/name` or `gitlab:group/name`.
+#[derive(Debug, Clone)]
+pub struct ForgeRepo {
+    pub forge: Forge,
+    pub owner: String,
+    pub name: String,
+}
+
+impl ForgeRepo {
+    /// Parses a forge shorthand, returning `None` for anything that is not one.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (forge, path) = if let Some(path) = spec.strip_prefix("github:") {
+            (Forge::GitHub, path)
+        } else if let Some(path) = spec.strip_prefix("gitlab:") {
+            (Forge::GitLab, path)
+        } else {
+            return None;
+        };
+        let (owner, name) = path.trim_end_matches(".git").rsplit_once('/')?;
+        if owner.is_empty() || name.is_empty() {
+            return None;
+        }
+        Some(Self {
+            forge,
+            owner: owner.to_string(),
+            name: name.to_string(),
+        })
+    }
+
+    fn path(&self) -> String {
+        format!("{}/{}", self.owner, self.name)
+    }
+
+    /// The SSH URL used as the `origin` remote.
+    pub fn ssh_url(&self, config: &ForgeConfig) -> String {
+        match self.forge {
+            Forge::GitHub => format!("git@github.com:{}.git", self.path()),
+            Forge::GitLab => {
+                let host = config
+                    .gitlab_url
+                    .trim_start_matches("https://")
+                    .trim_start_matches("http://")
+                    .trim_end_matches('/');
+                format!("git@{host}:{}.git", self.path())
+            }
+        }
+    }
+
+    /// Creates the repository if it does not exist, returning its SSH URL.
+    pub fn ensure_exists(&self, config: &ForgeConfig) -> Result<String> {
+        match self.forge {
+            Forge::GitHub => self.ensure_github(config)?,
+            Forge::GitLab => self.ensure_gitlab(config)?,
+        }
+        Ok(self.ssh_url(config))
+    }
+
+    fn ensure_github(&self, config: &ForgeConfig) -> Result<()> {
+        let token = config.github_token.as_deref().ok_or_else(|| {
+            eyre!(
+                "no `forge.github_token` configured, cannot create {}",
+                self.path()
+            )
+        })?;
+
+        let existing = http::FORGE
+            .get(format!("https://api.github.com/repos/{}", self.path()))
+            .bearer_auth(token)
+            .send()?;
+        if existing.status().is_success() {
+            debug!(repo=%self.path(), "repository already exists on GitHub");
+            return Ok(());
+        }
+        if existing.status() != StatusCode::NOT_FOUND {
+            bail!(
+                "GitHub responded with {} for {}",
+                existing.status(),
+                self.path()
+            );
+        }
+
+        #[derive(Deserialize)]
+        struct User {
+            login: String,
+        }
+        let user: User = http::FORGE
+            .get("https://api.github.com/user")
+            .bearer_auth(token)
+            .send()?
+            .error_for_status()?
+            .json()?;
+
+        let url = if user.login.eq_ignore_ascii_case(&self.owner) {
+            "https://api.github.com/user/repos".to_string()
+        } else {
+            format!("https://api.github.com/orgs/{}/repos", self.owner)
+        };
+        info!(repo=%self.path(), private=config.private, "creating repository on GitHub");
+        http::FORGE
+            .post(url)
+            .bearer_auth(token)
+            .json(&serde_json::json!({
+                "name": self.name,
+                "private": config.private,
+            }))
+            .send()?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    fn ensure_gitlab(&self, config: &ForgeConfig) -> Result<()> {
+        let token = config.gitlab_token.as_deref().ok_or_else(|| {
+            eyre!(
+                "no `forge.gitlab_token` configured, cannot create {}",
+                self.path()
+            )
+        })?;
+        let api = format!("{}/api/v4", config.gitlab_url.trim_end_matches('/'));
+
+        let existing = http::FORGE
+            .get(format!(
+                "{api}/projects/{}",
+                self.path().replace('/', "%2F")
+            ))
+            .header("PRIVATE-TOKEN", token)
+            .send()?;
+        if existing.status().is_success() {
+            debug!(repo=%self.path(), "project already exists on GitLab");
+            return Ok(());
+        }
+        if existing.status() != StatusCode::NOT_FOUND {
+            bail!(
+                "GitLab responded with {} for {}",
+                existing.status(),
+                self.path()
+            );
+        }
+
+        #[derive(Deserialize)]
+        struct Namespace {
+            id: u64,
+        }
+        let namespace: Namespace = http::FORGE
+            .get(format!(
+                "{api}/namespaces/{}",
+                self.owner.replace('/', "%2F")
+            ))
+            .header("PRIVATE-TOKEN", token)
+            .send()?
+            .error_for_status()?
+            .json()?;
+
+        info!(repo=%self.path(), private=config.private, "creating project on GitLab");
+        http::FORGE
+            .post(format!("{api}/projects"))
+            .header("PRIVATE-TOKEN", token)
+            .json(&serde_json::json!({
+                "name": self.name,
+                "path": self.name,
+                "namespace_id": namespace.id,
+                "visibility": if config.private { "private" } else { "public" },
+            }))
+            .send()?
+            .error_for_status()?;
+        Ok(())
+    }
+}
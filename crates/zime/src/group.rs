@@ -0,0 +1,143 @@
+//! Reading-group mode: one shared library whose members each keep their own
+//! read status, ratings and notes.
+//!
+//! With `reading_group = true` in the library's config, members are named by
+//! `git config user.name`. Their status and rating are kept under `members` in
+//! `zime.lock`, and their notes in `notes/<member>/`. Syncing registers a git
+//! merge driver for `zime.lock` that merges it entry by entry and member by
+//! member, so members marking papers at the same time do not conflict.
+
+use std::{collections::BTreeSet, fs};
+
+use camino::Utf8Path;
+use color_eyre::eyre::eyre;
+use duct::cmd;
+use serde_json::Value;
+use tracing::{debug, warn};
+
+use crate::{
+    Result,
+    config::Setup,
+    provenance,
+    sidecar::{self, Sidecar},
+};
+
+/// Name of the merge driver in `.gitattributes` and the git config.
+const DRIVER: &str = "zime-sidecar";
+
+/// The member whose status, ratings and notes commands use, if the library
+/// is in reading-group mode.
+pub fn member(setup: &Setup) -> Result<Option<String>> {
+    if !setup.config()?.reading_group {
+        return Ok(None);
+    }
+    provenance::identity(setup).map(Some).ok_or_else(|| {
+        eyre!("reading groups name members by git config user.name, which is not set")
+    })
+}
+
+/// The directory name of a member's notes, e.g. `ada-lovelace`.
+pub fn slug(member: &str) -> String {
+    member
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Registers the merge driver for `zime.lock`: the attribute in
+/// `.gitattributes`, which is committed, and the command in the library's git
+/// config, which each member's clone needs.
+pub fn install_merge_driver(setup: &Setup) -> Result<()> {
+    let attributes = setup.root().join(".gitattributes");
+    let line = format!("{} merge={DRIVER}", sidecar::FILE);
+    let current = fs::read_to_string(&attributes).unwrap_or_default();
+    if !current.lines().any(|l| l.trim() == line) {
+        let separator = if current.is_empty() || current.ends_with('\n') {
+            ""
+        } else {
+            "\n"
+        };
+        fs::write(&attributes, format!("{current}{separator}{line}\n"))?;
+        debug!(path = %attributes, "added merge driver attribute");
+    }
+    let exe = std::env::current_exe()?;
+    cmd!(
+        "git",
+        "config",
+        format!("merge.{DRIVER}.name"),
+        "zime per-member merge of zime.lock"
+    )
+    .dir(setup.root())
+    .run()?;
+    cmd!(
+        "git",
+        "config",
+        format!("merge.{DRIVER}.driver"),
+        format!("'{}' merge-sidecar %O %A %B", exe.display())
+    )
+    .dir(setup.root())
+    .run()?;
+    Ok(())
+}
+
+/// Merges the versions of `zime.lock` git hands the merge driver, writing the
+/// result to `ours`.
+pub fn merge_files(base: &Utf8Path, ours: &Utf8Path, theirs: &Utf8Path) -> Result<()> {
+    let read = |path: &Utf8Path| -> Result<Option<Value>> {
+        let src = fs::read_to_string(path)?;
+        if src.trim().is_empty() {
+            return Ok(None);
+        }
+        serde_json::from_str(&src)
+            .map(Some)
+            .map_err(|err| eyre!("failed to parse {path}: {err}"))
+    };
+    let merged = merge(
+        read(base)?.as_ref(),
+        read(ours)?.as_ref(),
+        read(theirs)?.as_ref(),
+    )
+    .unwrap_or(Value::Null);
+    let sidecar: Sidecar = if merged.is_null() {
+        Sidecar::default()
+    } else {
+        serde_json::from_value(merged)?
+    };
+    fs::write(ours, serde_json::to_string_pretty(&sidecar)?)?;
+    Ok(())
+}
+
+/// Three-way merge of JSON values, recursing into objects changed on both
+/// sides. Where both sides changed the same value differently, ours is kept.
+fn merge(base: Option<&Value>, ours: Option<&Value>, theirs: Option<&Value>) -> Option<Value> {
+    if ours == theirs || theirs == base {
+        return ours.cloned();
+    }
+    if ours == base {
+        return theirs.cloned();
+    }
+    match (ours, theirs) {
+        (Some(Value::Object(ours)), Some(Value::Object(theirs))) => {
+            let base = base.and_then(Value::as_object);
+            let keys: BTreeSet<&String> = ours.keys().chain(theirs.keys()).collect();
+            Some(Value::Object(
+                keys.into_iter()
+                    .filter_map(|key| {
+                        let value = merge(
+                            base.and_then(|base| base.get(key)),
+                            ours.get(key),
+                            theirs.get(key),
+                        )?;
+                        Some((key.clone(), value))
+                    })
+                    .collect(),
+            ))
+        }
+        _ => {
+            warn!(?ours, ?theirs, "both sides changed zime.lock, keeping ours");
+            ours.or(theirs).cloned()
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! `zime remotes status`: checking every remote up front, so that a remote
+//! being down or a key being rejected shows before `index` or `pdfs` fail
+//! halfway.
+//!
+//! Each remote is asked something small with [`Remote::ping`], bypassing the
+//! HTTP cache, and remotes missing a setting they need are not asked at all.
+
+use std::time::Instant;
+
+use color_eyre::owo_colors::OwoColorize;
+use serde::Serialize;
+
+use crate::{
+    Result, bulk, http,
+    i18n::t,
+    prompt,
+    remotes::{self, REMOTES},
+};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case", tag = "status")]
+enum Status {
+    Reachable { latency_ms: u128 },
+    Failed { error: String },
+    NotConfigured { missing: &'static str },
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    remote: &'static str,
+    #[serde(flatten)]
+    status: Status,
+}
+
+pub fn run(json: bool) -> Result<()> {
+    http::disable_cache();
+    let spinner = prompt::spinner();
+    spinner.start(t!("remotes-checking", count = REMOTES.len()));
+    let results = bulk::fetch(
+        REMOTES
+            .iter()
+            .filter(|remote| remote.missing_config().is_none())
+            .map(|remote| remote.name().to_string()),
+        |_| {},
+        |name| {
+            let remote = remotes::get(name).expect("the remote is listed");
+            let start = Instant::now();
+            remote.ping().map(|()| start.elapsed())
+        },
+    )?;
+    spinner.stop("");
+
+    let reports: Vec<Report> = REMOTES
+        .iter()
+        .map(|remote| Report {
+            remote: remote.name(),
+            status: match (remote.missing_config(), results.get(remote.name())) {
+                (Some(missing), _) => Status::NotConfigured { missing },
+                (None, Some(Ok(latency))) => Status::Reachable {
+                    latency_ms: latency.as_millis(),
+                },
+                (None, Some(Err(err))) => Status::Failed {
+                    error: err.to_string(),
+                },
+                (None, None) => unreachable!("every configured remote is checked"),
+            },
+        })
+        .collect();
+
+    if json {
+        println!("{}", serde_json::to_string(&reports)?);
+        return Ok(());
+    }
+    let width = reports.iter().map(|r| r.remote.len()).max().unwrap_or(0);
+    for report in &reports {
+        let remote = format!("{:width$}", report.remote);
+        match &report.status {
+            Status::Reachable { latency_ms } => println!(
+                "{} {remote}  {}",
+                "✓".green(),
+                format!("{latency_ms} ms").dimmed()
+            ),
+            Status::Failed { error } => println!("{} {remote}  {}", "✗".red(), error.dimmed()),
+            Status::NotConfigured { missing } => println!(
+                "{} {remote}  {}",
+                "-".yellow(),
+                t!("remotes-not-configured", setting = *missing).dimmed()
+            ),
+        }
+    }
+    Ok(())
+}
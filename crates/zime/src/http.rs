@@ -0,0 +1,569 @@
+//! The HTTP client shared by all remotes.
+//!
+//! Requests go through a [`Site`], which sets the headers that site expects,
+//! spaces out requests to sites that ask for it, and logs the rate limit
+//! information they report at debug level.
+//!
+//! Requests answered with `429 Too Many Requests` or `503 Service
+//! Unavailable` are retried after the `Retry-After` the site gives, or else
+//! after a backoff doubling from one second. GET requests are also retried on
+//! other server errors and when the connection fails or times out, as they
+//! are safe to repeat. They are retried `http.retries` times, 3 by default,
+//! and not after the user presses Ctrl-C.
+//!
+//! Requests time out after `http.timeout` seconds, 30 by default, or after
+//! the time set for their site:
+//!
+//! ```toml
+//! [http]
+//! timeout = 30
+//! retries = 5
+//!
+//! [http.timeouts]
+//! scihub = 120
+//!
+//! # Milliseconds between requests, overriding zime's defaults
+//! [http.intervals]
+//! dblp = 1000
+//! ```
+//!
+//! Crossref and OpenAlex serve requests that include a contact address from
+//! their faster "polite" pools, which is enabled by setting `http.mailto` in
+//! the global config. Unpaywall requires it.
+//!
+//! The answers of DBLP, Crossref and arXiv are cached for a while, see
+//! [`cache`].
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use reqwest::{
+    IntoUrl, Method, StatusCode, Url,
+    blocking::{Client, RequestBuilder, Response},
+    cookie::Jar,
+    header::{HeaderMap, RETRY_AFTER, USER_AGENT},
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::{Result, cancel, config::Config};
+
+mod cache;
+
+pub use cache::{disable as disable_cache, set_dir as set_cache_dir};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HttpConfig {
+    /// Contact address sent to APIs that ask for one, such as Crossref
+    pub mailto: Option<String>,
+    /// Seconds before a request is abandoned
+    pub timeout: Option<u64>,
+    /// API key for CORE, see <https://core.ac.uk/services/api>
+    pub core_api_key: Option<String>,
+    /// API key for IEEE Xplore, see <https://developer.ieee.org>
+    pub ieee_api_key: Option<String>,
+    /// Sci-Hub mirrors to try in order, e.g. `https://sci-hub.se`
+    pub scihub_mirrors: Vec<String>,
+    /// Hours the answers of DBLP, Crossref and arXiv are reused for, 24 by
+    /// default and 0 to not cache them
+    pub cache_ttl: Option<u64>,
+    /// How many times a failed request is retried
+    pub retries: Option<usize>,
+    /// Seconds before a request to a site is abandoned, by site name
+    pub timeouts: BTreeMap<String, u64>,
+    /// Milliseconds to leave between requests to a site, by site name
+    pub intervals: BTreeMap<String, u64>,
+}
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How many times a failed request is retried.
+const DEFAULT_RETRIES: usize = 3;
+/// The wait before the first retry, when the site does not say how long.
+const BASE_BACKOFF: Duration = Duration::from_secs(1);
+/// Longer `Retry-After` waits are not honored, the request fails instead.
+const MAX_RETRY_WAIT: Duration = Duration::from_secs(60);
+
+const BROWSER_USER_AGENT: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 11_3_1 like Mac OS X) AppleWebKit/603.1.30 (KHTML, like Gecko) Version/10.0 Mobile/14E304 Safari/602.1";
+const ZIME_USER_AGENT: &str = concat!(
+    "zime/",
+    env!("CARGO_PKG_VERSION"),
+    " (https://github.com/oeb25/zime)"
+);
+
+#[derive(Debug)]
+pub struct Site {
+    pub name: &'static str,
+    /// Send a browser user agent, for sites that block unknown clients
+    browser: bool,
+    /// Include the configured contact address in the user agent
+    polite: bool,
+    /// Keep cookies across requests, for sites that need a login session
+    session: bool,
+    /// Reuse answers to GET requests, see [`cache`]
+    cached: bool,
+    /// Time to leave between the start of requests, as the site asks
+    interval: Duration,
+}
+
+pub const ACM: Site = Site {
+    name: "acm",
+    browser: true,
+    polite: false,
+    session: false,
+    cached: false,
+    interval: Duration::ZERO,
+};
+pub const ARXIV: Site = Site {
+    name: "arxiv",
+    browser: true,
+    polite: false,
+    session: false,
+    cached: true,
+    interval: Duration::from_secs(3),
+};
+pub const BIORXIV: Site = Site {
+    name: "biorxiv",
+    browser: true,
+    polite: false,
+    session: false,
+    cached: false,
+    interval: Duration::ZERO,
+};
+pub const CORE: Site = Site {
+    name: "core",
+    browser: false,
+    polite: false,
+    session: false,
+    cached: false,
+    interval: Duration::ZERO,
+};
+pub const CROSSREF: Site = Site {
+    name: "crossref",
+    browser: false,
+    polite: true,
+    session: false,
+    cached: true,
+    interval: Duration::ZERO,
+};
+pub const DBLP: Site = Site {
+    name: "dblp",
+    browser: false,
+    polite: false,
+    session: false,
+    cached: true,
+    interval: Duration::from_millis(200),
+};
+pub const DOI: Site = Site {
+    name: "doi",
+    browser: false,
+    polite: false,
+    session: false,
+    cached: false,
+    interval: Duration::ZERO,
+};
+pub const EVENT_DATA: Site = Site {
+    name: "eventdata",
+    browser: false,
+    polite: true,
+    session: false,
+    cached: false,
+    interval: Duration::ZERO,
+};
+pub const FORGE: Site = Site {
+    name: "forge",
+    browser: false,
+    polite: false,
+    session: false,
+    cached: false,
+    interval: Duration::ZERO,
+};
+pub const OPENALEX: Site = Site {
+    name: "openalex",
+    browser: false,
+    polite: true,
+    session: false,
+    cached: false,
+    interval: Duration::ZERO,
+};
+pub const GROBID: Site = Site {
+    name: "grobid",
+    browser: false,
+    polite: false,
+    session: false,
+    cached: false,
+    interval: Duration::ZERO,
+};
+pub const HAL: Site = Site {
+    name: "hal",
+    browser: false,
+    polite: false,
+    session: false,
+    cached: false,
+    interval: Duration::ZERO,
+};
+pub const IEEE: Site = Site {
+    name: "ieee",
+    browser: false,
+    polite: false,
+    session: false,
+    cached: false,
+    interval: Duration::ZERO,
+};
+pub const INSPIRE: Site = Site {
+    name: "inspire",
+    browser: false,
+    polite: false,
+    session: false,
+    cached: false,
+    interval: Duration::ZERO,
+};
+pub const OPEN_CITATIONS: Site = Site {
+    name: "opencitations",
+    browser: false,
+    polite: false,
+    session: false,
+    cached: false,
+    interval: Duration::ZERO,
+};
+/// Public works listed on ORCID profiles, see [`crate::remotes::orcid`]
+pub const ORCID: Site = Site {
+    name: "orcid",
+    browser: false,
+    polite: false,
+    session: false,
+    cached: false,
+    interval: Duration::ZERO,
+};
+/// The links of entries, checked by `check-links`
+pub const LINKS: Site = Site {
+    name: "links",
+    browser: true,
+    polite: false,
+    session: false,
+    cached: false,
+    interval: Duration::ZERO,
+};
+/// Publishers and repositories hosting open access PDFs
+pub const OPEN_ACCESS: Site = Site {
+    name: "open-access",
+    browser: true,
+    polite: false,
+    session: false,
+    cached: false,
+    interval: Duration::ZERO,
+};
+/// Publisher pages fetched through the institutional proxy, see
+/// [`crate::remotes::proxy`]
+pub const PROXY: Site = Site {
+    name: "proxy",
+    browser: true,
+    polite: false,
+    session: true,
+    cached: false,
+    interval: Duration::ZERO,
+};
+pub const PUBMED: Site = Site {
+    name: "pubmed",
+    browser: false,
+    polite: false,
+    session: false,
+    cached: false,
+    interval: Duration::from_millis(334),
+};
+pub const SEMANTIC_SCHOLAR: Site = Site {
+    name: "semanticscholar",
+    browser: false,
+    polite: false,
+    session: false,
+    cached: false,
+    interval: Duration::from_secs(1),
+};
+pub const SCIHUB: Site = Site {
+    name: "scihub",
+    browser: true,
+    polite: false,
+    session: false,
+    cached: false,
+    interval: Duration::from_secs(1),
+};
+pub const SSRN: Site = Site {
+    name: "ssrn",
+    browser: true,
+    polite: false,
+    session: false,
+    cached: false,
+    interval: Duration::ZERO,
+};
+pub const UNPAYWALL: Site = Site {
+    name: "unpaywall",
+    browser: false,
+    polite: false,
+    session: false,
+    cached: false,
+    interval: Duration::ZERO,
+};
+pub const ZBMATH: Site = Site {
+    name: "zbmath",
+    browser: false,
+    polite: false,
+    session: false,
+    cached: false,
+    interval: Duration::ZERO,
+};
+
+fn config() -> &'static HttpConfig {
+    static CONFIG: OnceLock<HttpConfig> = OnceLock::new();
+    CONFIG.get_or_init(|| Config::load_global().map(|c| c.http).unwrap_or_default())
+}
+
+fn client(session: bool) -> &'static Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    static SESSION_CLIENT: OnceLock<Client> = OnceLock::new();
+    let build = |session: bool| {
+        let timeout = config()
+            .timeout
+            .map_or(DEFAULT_TIMEOUT, Duration::from_secs);
+        let builder = Client::builder().timeout(timeout);
+        let builder = if session {
+            builder.cookie_provider(cookies().clone())
+        } else {
+            builder
+        };
+        builder
+            .build()
+            .expect("the HTTP client configuration is valid")
+    };
+    if session {
+        SESSION_CLIENT.get_or_init(|| build(true))
+    } else {
+        CLIENT.get_or_init(|| build(false))
+    }
+}
+
+/// The cookies of sites with a login session, kept for the rest of the run.
+fn cookies() -> &'static Arc<Jar> {
+    static JAR: OnceLock<Arc<Jar>> = OnceLock::new();
+    JAR.get_or_init(Default::default)
+}
+
+/// Adds a cookie, given as in a `Set-Cookie` header, for `url` to the
+/// session of sites that keep one.
+pub fn add_cookie(cookie: &str, url: &Url) {
+    cookies().add_cookie_str(cookie, url);
+}
+
+pub fn mailto() -> Option<&'static str> {
+    config().mailto.as_deref()
+}
+
+pub fn core_api_key() -> Option<&'static str> {
+    config().core_api_key.as_deref()
+}
+
+pub fn ieee_api_key() -> Option<&'static str> {
+    config().ieee_api_key.as_deref()
+}
+
+pub fn scihub_mirrors() -> &'static [String] {
+    &config().scihub_mirrors
+}
+
+impl Site {
+    fn user_agent(&self) -> String {
+        if self.browser {
+            return BROWSER_USER_AGENT.to_string();
+        }
+        match mailto() {
+            Some(mailto) if self.polite => format!(
+                "zime/{} (https://github.com/oeb25/zime; mailto:{mailto})",
+                env!("CARGO_PKG_VERSION")
+            ),
+            _ => ZIME_USER_AGENT.to_string(),
+        }
+    }
+
+    pub fn get(&'static self, url: impl IntoUrl) -> Request {
+        self.request(client(self.session).get(url))
+    }
+
+    pub fn post(&'static self, url: impl IntoUrl) -> Request {
+        self.request(client(self.session).post(url))
+    }
+
+    fn request(&'static self, builder: RequestBuilder) -> Request {
+        let builder = match config().timeouts.get(self.name) {
+            Some(&seconds) => builder.timeout(Duration::from_secs(seconds)),
+            None => builder,
+        };
+        Request {
+            site: self,
+            builder: builder.header(USER_AGENT, self.user_agent()),
+        }
+    }
+
+    /// Waits until the site's interval since the last request has passed.
+    fn wait_turn(&self) {
+        static NEXT: Mutex<BTreeMap<&str, Instant>> = Mutex::new(BTreeMap::new());
+        let interval = config()
+            .intervals
+            .get(self.name)
+            .map_or(self.interval, |&ms| Duration::from_millis(ms));
+        if interval.is_zero() {
+            return;
+        }
+        let turn = {
+            let mut next = NEXT.lock().unwrap();
+            let now = Instant::now();
+            let turn = next.get(self.name).map_or(now, |&at| at.max(now));
+            next.insert(self.name, turn + interval);
+            turn
+        };
+        let wait = turn.saturating_duration_since(Instant::now());
+        if !wait.is_zero() {
+            debug!(site = self.name, ?wait, "waiting for the site's interval");
+            std::thread::sleep(wait);
+        }
+    }
+}
+
+/// A request to a [`Site`], mirroring the parts of [`RequestBuilder`] zime
+/// uses.
+pub struct Request {
+    site: &'static Site,
+    builder: RequestBuilder,
+}
+
+impl Request {
+    pub fn query<T: Serialize + ?Sized>(self, query: &T) -> Self {
+        self.map(|b| b.query(query))
+    }
+
+    pub fn header(self, name: &'static str, value: &str) -> Self {
+        self.map(|b| b.header(name, value))
+    }
+
+    pub fn bearer_auth(self, token: &str) -> Self {
+        self.map(|b| b.bearer_auth(token))
+    }
+
+    pub fn json<T: Serialize + ?Sized>(self, json: &T) -> Self {
+        self.map(|b| b.json(json))
+    }
+
+    pub fn body(self, body: Vec<u8>) -> Self {
+        self.map(|b| b.body(body))
+    }
+
+    fn map(self, f: impl FnOnce(RequestBuilder) -> RequestBuilder) -> Self {
+        Self {
+            site: self.site,
+            builder: f(self.builder),
+        }
+    }
+
+    pub fn send(self) -> Result<Response> {
+        let site = self.site.name;
+        let cache_url = self.cache_url();
+        if let Some(response) = cache_url.as_deref().and_then(cache::get) {
+            return Ok(response);
+        }
+        let idempotent = self.method() == Some(Method::GET);
+        let retries = config().retries.unwrap_or(DEFAULT_RETRIES);
+        let mut attempt = 0;
+        loop {
+            cancel::check()?;
+            self.site.wait_turn();
+            let builder = self
+                .builder
+                .try_clone()
+                .expect("zime does not send streaming bodies");
+            let wait = match builder.send() {
+                Ok(response) => {
+                    debug!(site, url=%response.url(), status=%response.status(), "response");
+                    log_rate_limit(site, response.headers());
+                    let status = response.status();
+                    let retry = matches!(
+                        status,
+                        StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+                    ) || (idempotent && status.is_server_error());
+                    let wait = retry_after(response.headers()).unwrap_or(backoff(attempt));
+                    if !retry || attempt >= retries || wait > MAX_RETRY_WAIT {
+                        return match &cache_url {
+                            Some(url) => cache::put(url, response),
+                            None => Ok(response),
+                        };
+                    }
+                    warn!(site, %status, ?wait, "waiting before retrying");
+                    wait
+                }
+                Err(err)
+                    if idempotent
+                        && attempt < retries
+                        && (err.is_connect() || err.is_timeout() || err.is_request()) =>
+                {
+                    let wait = backoff(attempt);
+                    warn!(site, %err, ?wait, "request failed, waiting before retrying");
+                    wait
+                }
+                Err(err) => return Err(err.into()),
+            };
+            std::thread::sleep(wait);
+            attempt += 1;
+        }
+    }
+
+    fn method(&self) -> Option<Method> {
+        let request = self.builder.try_clone()?.build().ok()?;
+        Some(request.method().clone())
+    }
+
+    /// The URL the answer is cached by, for GET requests to cached sites.
+    fn cache_url(&self) -> Option<String> {
+        if !self.site.cached {
+            return None;
+        }
+        let request = self.builder.try_clone()?.build().ok()?;
+        (request.method() == Method::GET).then(|| request.url().to_string())
+    }
+}
+
+/// The wait before retry number `attempt + 1`, doubling with each attempt.
+fn backoff(attempt: usize) -> Duration {
+    BASE_BACKOFF * 2u32.saturating_pow(attempt as u32)
+}
+
+/// Parses a `Retry-After` header, given either in seconds or as an HTTP date.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = jiff::fmt::rfc2822::parse(value).ok()?.timestamp();
+    let wait = at.duration_since(jiff::Timestamp::now());
+    Some(Duration::try_from(wait).unwrap_or(Duration::ZERO))
+}
+
+/// Logs the rate limit headers used by the APIs zime talks to.
+fn log_rate_limit(site: &str, headers: &HeaderMap) {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    // Crossref
+    if let (Some(limit), Some(interval)) = (
+        header("x-rate-limit-limit"),
+        header("x-rate-limit-interval"),
+    ) {
+        debug!(site, limit, interval, "rate limit");
+    }
+    // GitHub and GitLab
+    let remaining = header("x-ratelimit-remaining").or_else(|| header("ratelimit-remaining"));
+    if let Some(remaining) = remaining {
+        let limit = header("x-ratelimit-limit")
+            .or_else(|| header("ratelimit-limit"))
+            .unwrap_or("?");
+        debug!(site, remaining, limit, "rate limit");
+    }
+}
@@ -0,0 +1,141 @@
+//! Responses of the sites marked `cached`, kept in `.cache/http` in the
+//! library for `http.cache_ttl` hours, 24 by default.
+//!
+//! Only successful text responses to GET requests are kept, so that PDFs are
+//! not duplicated and errors are retried. `--no-cache` skips the cache for one
+//! command, and a `cache_ttl` of 0 turns it off.
+
+use std::{
+    fs,
+    sync::{
+        OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use jiff::{Timestamp, ToSpan};
+use reqwest::{
+    StatusCode,
+    blocking::Response,
+    header::{CONTENT_TYPE, HeaderValue},
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
+
+use crate::Result;
+
+const DEFAULT_TTL_HOURS: u64 = 24;
+
+static DIR: OnceLock<Utf8PathBuf> = OnceLock::new();
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Keeps responses in the `.cache` directory of the library at `root`.
+pub fn set_dir(root: &Utf8Path) {
+    let _ = DIR.set(root.join(".cache"));
+}
+
+pub fn disable() {
+    DISABLED.store(true, Ordering::Relaxed);
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Cached {
+    url: String,
+    fetched: Timestamp,
+    status: u16,
+    content_type: Option<String>,
+    body: String,
+}
+
+fn ttl_hours() -> u64 {
+    super::config().cache_ttl.unwrap_or(DEFAULT_TTL_HOURS)
+}
+
+/// Where the response for `url` is kept, if the cache is in use.
+fn path(url: &str) -> Option<Utf8PathBuf> {
+    if DISABLED.load(Ordering::Relaxed) || ttl_hours() == 0 {
+        return None;
+    }
+    let digest = Sha256::digest(url);
+    let name: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+    Some(DIR.get()?.join("http").join(format!("{name}.json")))
+}
+
+fn rebuild(cached: Cached) -> Option<Response> {
+    let mut builder = ::http::Response::builder().status(StatusCode::from_u16(cached.status).ok()?);
+    if let Some(content_type) = &cached.content_type {
+        builder = builder.header(CONTENT_TYPE, HeaderValue::from_str(content_type).ok()?);
+    }
+    Some(builder.body(cached.body).ok()?.into())
+}
+
+/// The kept response for `url`, if it is younger than the TTL.
+pub fn get(url: &str) -> Option<Response> {
+    let path = path(url)?;
+    let cached: Cached = serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+    let hours = i64::try_from(ttl_hours()).unwrap_or(i64::MAX);
+    let expires = cached.fetched.checked_add(hours.hours()).ok()?;
+    if cached.url != url || expires < Timestamp::now() {
+        return None;
+    }
+    debug!(url, fetched = %cached.fetched, "using cached response");
+    rebuild(cached)
+}
+
+/// Keeps `response` for `url` if it is cacheable, handing back an equivalent
+/// response as its body has been read.
+pub fn put(url: &str, response: Response) -> Result<Response> {
+    let Some(path) = path(url) else {
+        return Ok(response);
+    };
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    if !response.status().is_success()
+        || content_type
+            .as_deref()
+            .is_some_and(|content_type| content_type.starts_with("application/pdf"))
+    {
+        return Ok(response);
+    }
+    let status = response.status();
+    let headers = response.headers().clone();
+    let bytes = response.bytes()?;
+    let Ok(body) = String::from_utf8(bytes.to_vec()) else {
+        let mut response = ::http::Response::builder().status(status).body(bytes)?;
+        *response.headers_mut() = headers;
+        return Ok(response.into());
+    };
+    let cached = Cached {
+        url: url.to_string(),
+        fetched: Timestamp::now(),
+        status: status.as_u16(),
+        content_type,
+        body,
+    };
+    if let Err(err) = write(&path, &cached) {
+        warn!(%path, %err, "failed to cache response");
+    }
+    Ok(rebuild(cached).expect("the response was valid when received"))
+}
+
+fn write(path: &Utf8Path, cached: &Cached) -> Result<()> {
+    let Some(dir) = path.parent() else {
+        return Ok(());
+    };
+    fs::create_dir_all(dir)?;
+    // Keep the cache out of the library's repository, whatever its
+    // `.gitignore` says
+    if let Some(root) = dir.parent() {
+        let ignore = root.join(".gitignore");
+        if !ignore.exists() {
+            fs::write(ignore, "*\n")?;
+        }
+    }
+    fs::write(path, serde_json::to_string(cached)?)?;
+    Ok(())
+}
@@ -0,0 +1,92 @@
+//! Translations of user-facing messages.
+//!
+//! Messages are [Fluent](https://projectfluent.org) files in `locales/`,
+//! embedded in the binary. The language is taken from `language` in the global
+//! config, or else from `LC_ALL`, `LC_MESSAGES` or `LANG`. Messages missing
+//! from a translation fall back to English.
+
+use std::sync::OnceLock;
+
+use fluent_bundle::{FluentArgs, FluentResource, concurrent::FluentBundle};
+use unic_langid::LanguageIdentifier;
+
+use crate::config::Config;
+
+/// Available locales, English first.
+const LOCALES: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.ftl")),
+    ("da", include_str!("../locales/da.ftl")),
+];
+
+/// Bundles to look messages up in, most preferred first.
+static BUNDLES: OnceLock<Vec<FluentBundle<FluentResource>>> = OnceLock::new();
+
+fn bundle(lang: &str, src: &str) -> FluentBundle<FluentResource> {
+    let id: LanguageIdentifier = lang.parse().expect("locale names are valid");
+    let mut bundle = FluentBundle::new_concurrent(vec![id]);
+    // the isolation marks are meant for bidirectional text in GUIs and show up
+    // as garbage in some terminals
+    bundle.set_use_isolating(false);
+    let resource = FluentResource::try_new(src.to_string()).expect("locales are valid Fluent");
+    bundle
+        .add_resource(resource)
+        .expect("locales have no duplicate messages");
+    bundle
+}
+
+/// The requested language, e.g. `da` for `LANG=da_DK.UTF-8`.
+fn requested_language() -> Option<String> {
+    let requested = Config::load_global()
+        .ok()
+        .and_then(|config| config.language)
+        .or_else(|| {
+            ["LC_ALL", "LC_MESSAGES", "LANG"]
+                .into_iter()
+                .find_map(|var| std::env::var(var).ok().filter(|value| !value.is_empty()))
+        })?;
+    requested
+        .split(['_', '-', '.', '@'])
+        .next()
+        .map(str::to_lowercase)
+}
+
+fn bundles() -> &'static [FluentBundle<FluentResource>] {
+    BUNDLES.get_or_init(|| {
+        let requested = requested_language();
+        let mut bundles: Vec<_> = LOCALES
+            .iter()
+            .skip(1)
+            .filter(|(lang, _)| requested.as_deref() == Some(lang))
+            .map(|(lang, src)| bundle(lang, src))
+            .collect();
+        bundles.push(bundle(LOCALES[0].0, LOCALES[0].1));
+        bundles
+    })
+}
+
+/// Looks up and formats a message, use [`t!`] instead.
+pub fn message(id: &str, args: Option<&FluentArgs>) -> String {
+    for bundle in bundles() {
+        let Some(pattern) = bundle.get_message(id).and_then(|message| message.value()) else {
+            continue;
+        };
+        let mut errors = Vec::new();
+        return bundle
+            .format_pattern(pattern, args, &mut errors)
+            .into_owned();
+    }
+    id.to_string()
+}
+
+/// Translates a message, as in `t!("confirm-remove", title = title)`.
+macro_rules! t {
+    ($id:literal) => {
+        $crate::i18n::message($id, None)
+    };
+    ($id:literal, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::message($id, Some(&args))
+    }};
+}
+pub(crate) use t;
@@ -0,0 +1,452 @@
+//! `zime import`: adding many works at once from a publication list or the
+//! citations of an existing paper, merged into the library without
+//! duplicates, and `zime refs` and `zime cited-by`: adding works cited by an
+//! entry or citing it.
+
+use std::fs;
+
+use biblatex::{Bibliography, Entry};
+use camino::Utf8Path;
+use color_eyre::eyre::{bail, eyre};
+use itertools::Itertools;
+use tracing::warn;
+
+use crate::{
+    Result, bulk,
+    config::Setup,
+    doi,
+    i18n::t,
+    index, language,
+    latex::{self, BblItem},
+    merge::{self, MergeReport},
+    prompt,
+    provenance::{self, Provenance},
+    remotes::{self, Candidate},
+    select,
+    tag_rules::Rules,
+    title_case,
+    transaction::Transaction,
+};
+
+/// Imports the works on an ORCID profile. Works with a DOI are taken from
+/// Crossref, the others from ORCID itself.
+pub fn orcid(setup: &Setup, id: &str) -> Result<MergeReport> {
+    let orcid = remotes::orcid::parse_id(id)?;
+    let spinner = prompt::spinner();
+    spinner.start(t!("import-fetching", source = "ORCID"));
+    let works = remotes::orcid::works(&orcid)?;
+    let (with_doi, without_doi): (Vec<_>, Vec<_>) =
+        works.into_iter().partition(|work| work.doi().is_some());
+
+    let crossref = bulk::fetch(
+        with_doi.iter().filter_map(|work| work.doi()),
+        |doi| spinner.set_message(doi),
+        remotes::crossref::fetch_work,
+    )?;
+    let orcid_works = bulk::fetch(
+        without_doi.iter().map(|work| work.id()),
+        |id| spinner.set_message(id),
+        remotes::orcid::fetch_work,
+    )?;
+    spinner.stop(t!(
+        "import-fetched",
+        count = crossref.len() + orcid_works.len()
+    ));
+
+    let mut entries = Vec::new();
+    for (doi, work) in crossref {
+        match work {
+            Ok(work) => entries.push((
+                work.record().into_entry(),
+                Provenance::new("crossref", &doi),
+            )),
+            Err(err) => warn!(%doi, %err, "failed to look up DOI on Crossref"),
+        }
+    }
+    for (id, work) in orcid_works {
+        match work {
+            Ok(work) => entries.push((work.record().into_entry(), Provenance::new("orcid", &id))),
+            Err(err) => warn!(%id, %err, "failed to fetch work from ORCID"),
+        }
+    }
+    insert_all(setup, entries)
+}
+
+/// Imports the publications of a DBLP author, given by PID, author page URL
+/// or name, letting the user deselect works unless `yes` is set.
+pub fn dblp_author(setup: &Setup, author: &str, yes: bool) -> Result<MergeReport> {
+    let pid = if author.contains('/') {
+        remotes::dblp::pid(author).to_string()
+    } else {
+        let spinner = prompt::spinner();
+        spinner.start(t!("looking-up"));
+        let authors = remotes::dblp::search_author(author)?;
+        spinner.stop("");
+        let author = match authors.as_slice() {
+            [] => return Err(eyre!(t!("no-entry-found", query = author))),
+            [author] => author,
+            _ => {
+                let i = prompt::select(
+                    t!("select-author"),
+                    authors.iter().map(|author| (&author.author, &author.url)),
+                    0,
+                )?;
+                &authors[i]
+            }
+        };
+        author.pid().to_string()
+    };
+    dblp(setup, &pid, remotes::dblp::fetch_author_bib, yes)
+}
+
+/// Imports the papers of a conference edition or journal volume on DBLP, by
+/// its key or page URL, letting the user deselect papers unless `yes` is set.
+pub fn dblp_venue(setup: &Setup, venue: &str, yes: bool) -> Result<MergeReport> {
+    let toc = remotes::dblp::toc(venue);
+    dblp(setup, &toc, remotes::dblp::fetch_toc_bib, yes)
+}
+
+/// Imports the publications fetched as a .bib from DBLP by `fetch`.
+fn dblp(
+    setup: &Setup,
+    id: &str,
+    fetch: impl Fn(&str) -> Result<String>,
+    yes: bool,
+) -> Result<MergeReport> {
+    let spinner = prompt::spinner();
+    spinner.start(t!("import-fetching", source = "DBLP"));
+    let src = fetch(id)?;
+    let bib = Bibliography::parse(&src)
+        .map_err(|err| eyre!("failed to parse the publications of {id}: {err}"))?;
+    spinner.stop(t!("import-fetched", count = bib.len()));
+    let entries = choose(&bib, yes)?;
+    insert_all(
+        setup,
+        entries
+            .into_iter()
+            .map(|entry| {
+                let key = entry.key.strip_prefix("DBLP:").unwrap_or(&entry.key);
+                let provenance = Provenance::new("dblp", key);
+                (entry.clone(), provenance)
+            })
+            .collect(),
+    )
+}
+
+/// Imports the works cited in a paper, by the keys in its `.aux` file and
+/// what the `.bbl` file next to it says about them. Works with a DOI are taken
+/// from Crossref, keys of DBLP's `DBLP:` form from DBLP and the others from
+/// Crossref when a work has the same title. Entries keep the keys they are
+/// cited by, so the paper builds against the library.
+pub fn aux(setup: &Setup, path: &Utf8Path) -> Result<MergeReport> {
+    let mut keys = latex::aux_citations(path)?;
+    keys.remove("*");
+    let bbl = path.with_extension("bbl");
+    let items = if bbl.exists() {
+        let src = fs::read_to_string(&bbl).map_err(|err| eyre!("failed to read {bbl}: {err}"))?;
+        latex::bbl_items(&src)
+    } else {
+        warn!(%bbl, "no .bbl file, only DBLP keys can be resolved");
+        Default::default()
+    };
+
+    let spinner = prompt::spinner();
+    spinner.start(t!("import-fetching", source = "Crossref, DBLP"));
+    let resolved = bulk::fetch(
+        keys,
+        |key| spinner.set_message(key),
+        |key| resolve(key, items.get(key)),
+    )?;
+    let (entries, unresolved): (Vec<_>, Vec<_>) =
+        resolved.into_iter().partition(|(_, result)| result.is_ok());
+    spinner.stop(t!("import-fetched", count = entries.len()));
+    for (key, result) in &unresolved {
+        if let Err(err) = result {
+            warn!(%key, %err, "failed to resolve citation");
+        }
+    }
+    if !unresolved.is_empty() {
+        println!(
+            "{}",
+            t!(
+                "aux-unresolved",
+                count = unresolved.len(),
+                keys = unresolved.iter().map(|(key, _)| key).join(", ")
+            )
+        );
+    }
+    let entries = entries
+        .into_iter()
+        .filter_map(|(key, result)| {
+            let (mut entry, provenance) = result.ok()?;
+            entry.key = key;
+            Some((entry, provenance))
+        })
+        .collect_vec();
+    insert_all(setup, entries)
+}
+
+/// Looks up the work cited by `key`.
+fn resolve(key: &str, item: Option<&BblItem>) -> Result<(Entry, Provenance)> {
+    if let Some(dblp_key) = key.strip_prefix("DBLP:") {
+        let src = remotes::dblp::fetch_bib(dblp_key)?;
+        let entry = remotes::parse_entry(&src, "dblp", dblp_key)?;
+        return Ok((entry, Provenance::new("dblp", dblp_key)));
+    }
+    let item = item.ok_or_else(|| eyre!("not in the .bbl file"))?;
+    if let Some(doi) = &item.doi {
+        let work = remotes::crossref::fetch_work(doi)?;
+        return Ok((work.record().into_entry(), Provenance::new("crossref", doi)));
+    }
+    let title = item
+        .title
+        .as_deref()
+        .ok_or_else(|| eyre!("no DOI or title in the .bbl file"))?;
+    let wanted = merge::normalize_title(title);
+    let work = remotes::crossref::search(title, 0)?
+        .items
+        .into_iter()
+        .find(|work| merge::normalize_title(&work.record().title) == wanted)
+        .ok_or_else(|| eyre!("no work titled {title:?} on Crossref"))?;
+    Ok((
+        work.record().into_entry(),
+        Provenance::new("crossref", &work.doi),
+    ))
+}
+
+/// Imports works cited by `entry`, from the reference list its publisher
+/// deposited with Crossref, letting the user select which unless `yes` is
+/// set. Only references with a DOI that are not in the library are offered.
+pub fn references(setup: &Setup, entry: &Entry, yes: bool) -> Result<MergeReport> {
+    let id = entry
+        .doi()
+        .map_err(|_| eyre!(t!("refs-no-doi", key = entry.key.as_str())))?;
+    let bib = setup.bib()?;
+    let spinner = prompt::spinner();
+    spinner.start(t!("import-fetching", source = "Crossref"));
+    let work = remotes::crossref::fetch_work(&id)?;
+    spinner.stop(t!("refs-fetched", count = work.reference.len()));
+
+    let in_library = |doi: &str| {
+        bib.iter()
+            .any(|entry| entry.doi().is_ok_and(|other| doi::eq(&other, doi)))
+    };
+    let offered = work
+        .reference
+        .iter()
+        .filter(|reference| reference.doi.as_deref().is_some_and(|d| !in_library(d)))
+        .collect_vec();
+    let without_doi = work.reference.iter().filter(|r| r.doi.is_none()).count();
+    println!(
+        "{}",
+        t!(
+            "refs-skipped",
+            present = work.reference.len() - offered.len() - without_doi,
+            missing = without_doi
+        )
+    );
+    if offered.is_empty() {
+        return Ok(MergeReport::default());
+    }
+    let labels = offered
+        .iter()
+        .map(|reference| (reference.label(), reference.doi.clone().unwrap_or_default()))
+        .collect_vec();
+    let chosen = choose_offered(&labels, yes)?
+        .into_iter()
+        .map(|i| offered[i])
+        .collect_vec();
+
+    let spinner = prompt::spinner();
+    spinner.start(t!("import-fetching", source = "Crossref"));
+    let works = bulk::fetch(
+        chosen.iter().filter_map(|reference| reference.doi.clone()),
+        |doi| spinner.set_message(doi),
+        remotes::crossref::fetch_work,
+    )?;
+    spinner.stop(t!("import-fetched", count = works.len()));
+    let entries = works
+        .into_iter()
+        .filter_map(|(doi, work)| match work {
+            Ok(work) => Some((
+                work.record().into_entry(),
+                Provenance::new("crossref", &doi),
+            )),
+            Err(err) => {
+                warn!(%doi, %err, "failed to look up DOI on Crossref");
+                None
+            }
+        })
+        .collect_vec();
+    insert_all(setup, entries)
+}
+
+/// Imports works citing `entry`, found on Semantic Scholar and by its DOI on
+/// OpenCitations, letting the user select which unless `yes` is set. Only
+/// works not in the library are offered, at most `limit` of them.
+pub fn cited_by(setup: &Setup, entry: &Entry, limit: usize, yes: bool) -> Result<MergeReport> {
+    let s2_id = remotes::semanticscholar::paper_id(entry);
+    let doi = entry.doi().ok();
+    if s2_id.is_none() && doi.is_none() {
+        bail!(t!("cited-by-no-id", key = entry.key.as_str()));
+    }
+    let spinner = prompt::spinner();
+    spinner.start(t!("looking-up"));
+    let mut candidates: Vec<Candidate> = Vec::new();
+    let mut failure = None;
+    if let Some(id) = &s2_id {
+        match remotes::semanticscholar::citations(id, limit) {
+            Ok(papers) => candidates.extend(papers.into_iter().map(|paper| paper.candidate())),
+            Err(err) => {
+                warn!(%id, %err, "failed to look up citations on Semantic Scholar");
+                failure = Some(err);
+            }
+        }
+    }
+    if let Some(id) = &doi {
+        match remotes::opencitations::citing(id) {
+            Ok(dois) => {
+                for citing in dois {
+                    let known = candidates.iter().any(|candidate| {
+                        candidate
+                            .doi
+                            .as_deref()
+                            .is_some_and(|other| doi::eq(other, &citing))
+                    });
+                    if !known {
+                        candidates.push(Candidate {
+                            title: citing.clone(),
+                            authors: String::new(),
+                            provenance: Provenance::new("crossref", &citing),
+                            doi: Some(citing),
+                        });
+                    }
+                }
+            }
+            Err(err) => {
+                warn!(doi = %id, %err, "failed to look up citations on OpenCitations");
+                failure = Some(err);
+            }
+        }
+    }
+    if let Some(err) = failure.filter(|_| candidates.is_empty()) {
+        return Err(err);
+    }
+    spinner.stop(t!("cited-by-found", count = candidates.len()));
+
+    let bib = setup.bib()?;
+    let found = candidates.len();
+    candidates.retain(|candidate| !index::in_library(&bib, candidate));
+    candidates.truncate(limit);
+    println!(
+        "{}",
+        t!("cited-by-present", count = found - candidates.len())
+    );
+    if candidates.is_empty() {
+        return Ok(MergeReport::default());
+    }
+    let labels = candidates
+        .iter()
+        .map(|candidate| {
+            let label = if candidate.authors.is_empty() {
+                candidate.title.clone()
+            } else {
+                format!("{} ({})", candidate.title, candidate.authors)
+            };
+            (label, candidate.doi.clone().unwrap_or_default())
+        })
+        .collect_vec();
+    let chosen = choose_offered(&labels, yes)?
+        .into_iter()
+        .map(|i| &candidates[i])
+        .collect_vec();
+    let (with_doi, without_doi): (Vec<_>, Vec<_>) = chosen
+        .into_iter()
+        .partition(|candidate| candidate.doi.is_some());
+
+    let spinner = prompt::spinner();
+    spinner.start(t!("import-fetching", source = "Crossref, Semantic Scholar"));
+    let crossref = bulk::fetch(
+        with_doi
+            .iter()
+            .filter_map(|candidate| candidate.doi.clone()),
+        |doi| spinner.set_message(doi),
+        remotes::crossref::fetch_work,
+    )?;
+    let papers = bulk::fetch(
+        without_doi
+            .iter()
+            .map(|candidate| candidate.provenance.id.clone()),
+        |id| spinner.set_message(id),
+        remotes::semanticscholar::fetch_paper,
+    )?;
+    spinner.stop(t!("import-fetched", count = crossref.len() + papers.len()));
+
+    let mut entries = Vec::new();
+    for (doi, work) in crossref {
+        match work {
+            Ok(work) => entries.push((
+                work.record().into_entry(),
+                Provenance::new("crossref", &doi),
+            )),
+            Err(err) => warn!(%doi, %err, "failed to look up DOI on Crossref"),
+        }
+    }
+    for (id, paper) in papers {
+        match paper {
+            Ok(paper) => entries.push((
+                paper.record().into_entry(),
+                Provenance::new("semanticscholar", &id),
+            )),
+            Err(err) => warn!(%id, %err, "failed to fetch paper from Semantic Scholar"),
+        }
+    }
+    insert_all(setup, entries)
+}
+
+/// The indices of the works offered as `(label, hint)` that the user wants to
+/// import, all of them if `yes` is set.
+fn choose_offered(offered: &[(String, String)], yes: bool) -> Result<Vec<usize>> {
+    if yes {
+        return Ok((0..offered.len()).collect());
+    }
+    Ok(prompt::multiselect(
+        t!("select-works-to-import"),
+        offered.iter().map(|(label, hint)| (label, hint)),
+        &[],
+    )?)
+}
+
+/// The entries of `bib` the user wants to import, all of them if `yes` is set.
+fn choose(bib: &Bibliography, yes: bool) -> Result<Vec<&Entry>> {
+    let entries = bib.iter().collect_vec();
+    if yes || entries.is_empty() {
+        return Ok(entries);
+    }
+    select::select_many(&t!("select-works-to-import"), &entries)
+}
+
+/// Adds the fetched entries to the library, like [`crate::add::insert`] does
+/// for a single one, and syncs once.
+fn insert_all(setup: &Setup, entries: Vec<(Entry, Provenance)>) -> Result<MergeReport> {
+    let identity = provenance::identity(setup);
+    let rules = Rules::load(setup)?;
+    let style = setup.config()?.title_case.style;
+    let entries = entries.into_iter().map(|(mut entry, provenance)| {
+        provenance::set(&mut entry, &provenance);
+        language::fill(&mut entry);
+        rules.apply(&mut entry);
+        title_case::normalize(&mut entry, style);
+        if let Some(name) = &identity {
+            provenance::set_added_by(&mut entry, name);
+        }
+        entry
+    });
+    let mut bib = setup.bib()?;
+    let tx = Transaction::begin(setup)?;
+    let report = merge::merge(&mut bib, entries);
+    setup.write_bib(&bib)?;
+    setup.sync_git()?;
+    tx.commit();
+    Ok(report)
+}
@@ -0,0 +1,209 @@
+//! Searching remotes for a work and adding it to the library.
+//!
+//! Several remotes are searched at once, and their results are merged where
+//! they agree on the DOI or title, labelled with the remotes that found them.
+
+use biblatex::Bibliography;
+use color_eyre::{eyre::eyre, owo_colors::OwoColorize};
+use itertools::Itertools;
+use tracing::warn;
+
+use crate::{
+    Result, add,
+    config::Setup,
+    doi, entry,
+    i18n::t,
+    merge, prompt, provenance, refetch,
+    remotes::{self, Candidate, Remote},
+    venues::{Venue, Venues},
+};
+
+/// The remotes that can be searched, for `--source`.
+pub fn sources() -> clap::builder::PossibleValuesParser {
+    remotes::with(|capabilities| capabilities.search)
+        .map(|remote| {
+            clap::builder::PossibleValue::new(remote.name())
+                .aliases(remote.aliases().iter().copied())
+                .help(remote.description())
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+/// A search result from one or more remotes, which agree on its DOI or title.
+struct Hit {
+    candidate: Candidate,
+    sources: Vec<&'static str>,
+}
+
+/// A remote being searched, and where its next page starts.
+struct Search {
+    remote: &'static dyn Remote,
+    first: usize,
+    more: bool,
+}
+
+/// Whether `candidate` is already in `bib`, by its DOI or title.
+pub fn in_library(bib: &Bibliography, candidate: &Candidate) -> bool {
+    let title = merge::normalize_title(&candidate.title);
+    bib.iter().any(|entry| {
+        let same_doi = candidate
+            .doi
+            .as_deref()
+            .zip(entry.doi().ok())
+            .is_some_and(|(a, b)| doi::eq(a, &b));
+        same_doi || merge::normalize_title(&entry::title(entry)) == title
+    })
+}
+
+/// Adds `candidate` to `hits`, or the source to the hit it duplicates.
+fn add_hit(hits: &mut Vec<Hit>, source: &'static str, candidate: Candidate) {
+    let doi = candidate.doi.as_deref().map(doi::normalize);
+    let title = merge::normalize_title(&candidate.title);
+    let duplicate = hits.iter_mut().find(|hit| {
+        let same_doi = doi.is_some() && hit.candidate.doi.as_deref().map(doi::normalize) == doi;
+        same_doi || (!title.is_empty() && merge::normalize_title(&hit.candidate.title) == title)
+    });
+    match duplicate {
+        Some(hit) => {
+            if !hit.sources.contains(&source) {
+                hit.sources.push(source);
+            }
+            if hit.candidate.doi.is_none() {
+                hit.candidate.doi = candidate.doi;
+            }
+        }
+        None => hits.push(Hit {
+            candidate,
+            sources: vec![source],
+        }),
+    }
+}
+
+/// Searches the remotes with more results concurrently, adding their next
+/// page to `hits`. A remote that fails is skipped, unless all of them do.
+fn search_all(
+    searches: &mut [Search],
+    query: &str,
+    venue: Option<&Venue>,
+    hits: &mut Vec<Hit>,
+) -> Result<()> {
+    let pages = std::thread::scope(|scope| {
+        let handles = searches
+            .iter()
+            .filter(|search| search.more)
+            .map(|search| {
+                let remote = search.remote;
+                let first = search.first;
+                scope.spawn(move || (remote.name(), remote.search(query, venue, first)))
+            })
+            .collect_vec();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("search thread panicked"))
+            .collect_vec()
+    });
+    let mut failure = None;
+    let mut succeeded = false;
+    for (name, page) in pages {
+        let search = searches
+            .iter_mut()
+            .find(|search| search.remote.name() == name)
+            .unwrap();
+        match page {
+            Ok(page) => {
+                succeeded = true;
+                search.first += page.candidates.len();
+                search.more = page.more;
+                for candidate in page.candidates {
+                    add_hit(hits, name, candidate);
+                }
+            }
+            Err(err) => {
+                warn!(source = name, %err, "search failed");
+                search.more = false;
+                failure = Some(err);
+            }
+        }
+    }
+    match failure {
+        Some(err) if !succeeded => Err(err),
+        _ => Ok(()),
+    }
+}
+
+pub fn run(setup: &Setup, query: &str, sources: &[String], venue: Option<&str>) -> Result<()> {
+    let mut searches = sources
+        .iter()
+        .map(|source| remotes::get(source).ok_or_else(|| eyre!("unknown source {source}")))
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .unique_by(|remote| remote.name())
+        .map(|remote| Search {
+            remote,
+            first: 0,
+            more: true,
+        })
+        .collect_vec();
+    let venue = venue
+        .map(|venue| Venues::load(setup).and_then(|venues| venues.get(venue).cloned()))
+        .transpose()?;
+    let mut hits = Vec::new();
+    let selection = loop {
+        let spinner = prompt::spinner();
+        spinner.start(t!("looking-up"));
+        let first = hits.len();
+        search_all(&mut searches, query, venue.as_ref(), &mut hits)?;
+        spinner.stop("");
+        if hits.is_empty() {
+            return Err(eyre!(t!("no-entry-found", query = query)));
+        }
+
+        let mut items = hits
+            .iter()
+            .map(|hit| {
+                let candidate = &hit.candidate;
+                let mut hint = hit.sources.join(", ");
+                if let Some(doi) = &candidate.doi {
+                    hint.push_str(&format!(" · DOI: {doi}"));
+                }
+                (
+                    format!(
+                        "{} ({})",
+                        candidate.title.bold(),
+                        candidate.authors.italic()
+                    ),
+                    hint,
+                )
+            })
+            .collect_vec();
+        if searches.iter().any(|search| search.more) {
+            items.push((t!("load-more").dimmed().to_string(), String::new()));
+        }
+        let selection = prompt::select(t!("select-article"), items, first.min(hits.len() - 1))?;
+        if selection < hits.len() {
+            break selection;
+        }
+    };
+    prompt::outro(t!("added"))?;
+    add_candidate(setup, &hits[selection].candidate)?;
+    Ok(())
+}
+
+/// Adds a search result to the library, with the metadata of its publisher's
+/// remote where there is one. Returns its key in the library.
+pub fn add_candidate(setup: &Setup, candidate: &Candidate) -> Result<String> {
+    let provenance = match &candidate.doi {
+        Some(doi) => add::publisher_provenance(setup, doi)?,
+        None => None,
+    }
+    .unwrap_or_else(|| candidate.provenance.clone());
+
+    let spinner = prompt::spinner();
+    spinner.start(t!("downloading-bibliography"));
+    let mut entry = refetch::fetch_record(&provenance)?;
+    spinner.stop("");
+
+    provenance::set(&mut entry, &provenance);
+    add::insert(setup, entry)
+}
@@ -0,0 +1,92 @@
+//! Detecting the language of entries and translating their abstracts.
+//!
+//! The detected language is stored in the `language` field, named as BibLaTeX
+//! expects, e.g. `german`. If a translation command is configured, `show`
+//! prints abstracts in other languages translated:
+//!
+//! ```toml
+//! [translation]
+//! command = "trans -b :en"
+//! language = "english"
+//! ```
+
+use biblatex::{Bibliography, Chunk, ChunksExt, Entry, Spanned};
+use color_eyre::eyre::eyre;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::{Result, config::Config, entry};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TranslationConfig {
+    /// Command reading text on stdin and printing its translation
+    pub command: Option<String>,
+    /// Language the command translates into, entries already in it are shown
+    /// as they are
+    pub language: Option<String>,
+}
+
+/// The language of `entry` as given by its `language` field.
+pub fn get(entry: &Entry) -> Option<String> {
+    entry
+        .get("language")
+        .map(|chunks| chunks.format_verbatim().to_lowercase())
+}
+
+/// Detects the language of `entry` from its title and abstract, if the
+/// detection is reliable.
+pub fn detect(entry: &Entry) -> Option<String> {
+    let mut text = entry::title(entry);
+    if let Ok(abstract_) = entry.abstract_() {
+        text.push('\n');
+        text.push_str(&abstract_.format_verbatim());
+    }
+    let info = whatlang::detect(&text)?;
+    debug!(key=%entry.key, lang=?info.lang(), confidence=info.confidence(), "detected language");
+    info.is_reliable()
+        .then(|| info.lang().eng_name().to_lowercase())
+}
+
+/// Sets the `language` field of `entry` if it has none and its language can be
+/// detected. Returns whether the entry changed.
+pub fn fill(entry: &mut Entry) -> bool {
+    if get(entry).is_some() {
+        return false;
+    }
+    let Some(language) = detect(entry) else {
+        return false;
+    };
+    entry.set("language", vec![Spanned::detached(Chunk::Normal(language))]);
+    true
+}
+
+/// Fills in the language of all entries of `bib`, returning how many changed.
+pub fn fill_all(bib: &mut Bibliography) -> usize {
+    bib.iter_mut().map(fill).filter(|&changed| changed).count()
+}
+
+/// The abstract of `entry` translated with the configured command, if one is
+/// configured and the entry is not already in the target language.
+pub fn translated_abstract(entry: &Entry) -> Result<Option<String>> {
+    let config = Config::load_global()?.translation;
+    let Some(command) = config.command else {
+        return Ok(None);
+    };
+    let Ok(abstract_) = entry.abstract_() else {
+        return Ok(None);
+    };
+    let language = get(entry).or_else(|| detect(entry));
+    if language.is_some() && language == config.language.map(|l| l.to_lowercase()) {
+        return Ok(None);
+    }
+
+    let mut args = command.split_whitespace();
+    let program = args
+        .next()
+        .ok_or_else(|| eyre!("translation command is empty"))?;
+    let translation = duct::cmd(program, args)
+        .stdin_bytes(abstract_.format_verbatim())
+        .read()?;
+    Ok(Some(translation))
+}
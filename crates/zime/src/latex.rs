@@ -0,0 +1,257 @@
+//! Integration with LaTeX projects.
+//!
+//! `zime latexmk` is meant to run before each build, e.g. from a `Makefile` or
+//! as a latexmk hook, and writes the entries cited in the project's `.aux`
+//! files to a project-local bibliography, so paper repositories stay
+//! self-contained without exporting by hand.
+//!
+//! `zime import --aux` goes the other way, adding the works cited by an
+//! existing paper to the library, identified by what its `.bbl` says about
+//! them.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+};
+
+use biblatex::{Bibliography, Entry};
+use camino::{Utf8Path, Utf8PathBuf};
+use color_eyre::eyre::eyre;
+use tracing::{debug, info, warn};
+
+use crate::{Result, config::Setup, doi, entry, provenance, versions};
+
+/// The citation keys of an `.aux` file and the files it includes.
+///
+/// BibTeX writes `\citation{a,b}`, biblatex `\abx@aux@cite{0}{a}`. A `*` key
+/// stands for `\nocite{*}`.
+pub fn aux_citations(path: &Utf8Path) -> Result<BTreeSet<String>> {
+    let mut keys = BTreeSet::new();
+    let mut stack = vec![path.to_path_buf()];
+    let mut seen = BTreeSet::new();
+    while let Some(path) = stack.pop() {
+        if !seen.insert(path.clone()) {
+            continue;
+        }
+        let src = fs::read_to_string(&path).map_err(|err| eyre!("failed to read {path}: {err}"))?;
+        for line in src.lines() {
+            if let Some(rest) = line.strip_prefix("\\citation{") {
+                let list = rest.split('}').next().unwrap_or_default();
+                keys.extend(list.split(',').map(|key| key.trim().to_string()));
+            } else if let Some(rest) = line.strip_prefix("\\abx@aux@cite{") {
+                // `\abx@aux@cite{<refsection>}{<key>}`
+                if let Some(key) = rest.split('{').nth(1).and_then(|k| k.split('}').next()) {
+                    keys.insert(key.trim().to_string());
+                }
+            } else if let Some(rest) = line.strip_prefix("\\@input{") {
+                let file = rest.split('}').next().unwrap_or_default();
+                let dir = path.parent().unwrap_or(Utf8Path::new("."));
+                let included = dir.join(file);
+                if included.exists() {
+                    stack.push(included);
+                }
+            }
+        }
+    }
+    keys.retain(|key| !key.is_empty());
+    Ok(keys)
+}
+
+/// What a `.bbl` file says about a cited work.
+#[derive(Debug, Default)]
+pub struct BblItem {
+    pub doi: Option<String>,
+    pub title: Option<String>,
+}
+
+/// The text of the brace group `s` starts with, and what follows it.
+fn group(s: &str) -> Option<(&str, &str)> {
+    let s = s.strip_prefix('{')?;
+    let mut depth = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some((&s[..i], &s[i + 1..])),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `text` without TeX commands and braces, e.g. `\emph{Foo} {B}ar.` as `Foo
+/// Bar`.
+fn plain(text: &str) -> String {
+    let mut out = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                while chars.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+                    chars.next();
+                }
+            }
+            '{' | '}' => {}
+            '~' => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    let out = out.split_whitespace().collect::<Vec<_>>().join(" ");
+    out.trim_end_matches(['.', ',']).to_string()
+}
+
+/// The DOI and title of each cited work in a `.bbl` file, by key.
+///
+/// biblatex writes an `\entry{key}` block per work with its fields, BibTeX a
+/// `\bibitem{key}` formatted by the style, where the title is usually the
+/// first `\newblock`.
+pub fn bbl_items(src: &str) -> BTreeMap<String, BblItem> {
+    let mut items = BTreeMap::new();
+    for block in src.split("\\entry").skip(1) {
+        let Some((key, body)) = group(block) else {
+            continue;
+        };
+        let body = body.split("\\endentry").next().unwrap_or_default();
+        let title = body
+            .split_once("\\field{title}")
+            .and_then(|(_, rest)| group(rest.trim_start()))
+            .map(|(title, _)| plain(title));
+        items.insert(
+            key.to_string(),
+            BblItem {
+                doi: bbl_doi(body),
+                title,
+            },
+        );
+    }
+    for block in src.split("\\bibitem").skip(1) {
+        let mut rest = block.trim_start();
+        // The optional argument, e.g. `[Reynolds(2002)]`, with the label
+        // natbib shows
+        if rest.starts_with('[') {
+            let mut depth = 0;
+            let end = rest.char_indices().find_map(|(i, c)| {
+                match c {
+                    '[' => depth += 1,
+                    ']' => depth -= 1,
+                    _ => {}
+                }
+                (depth == 0).then_some(i)
+            });
+            rest = end.map_or("", |end| rest[end + 1..].trim_start());
+        }
+        let Some((key, body)) = group(rest) else {
+            continue;
+        };
+        let body = body
+            .split("\\end{thebibliography}")
+            .next()
+            .unwrap_or_default();
+        let title = body
+            .split("\\newblock")
+            .nth(1)
+            .map(plain)
+            .filter(|title| !title.is_empty());
+        items.insert(
+            key.trim().to_string(),
+            BblItem {
+                doi: bbl_doi(body),
+                title,
+            },
+        );
+    }
+    items
+}
+
+fn bbl_doi(body: &str) -> Option<String> {
+    // A DOI in a link is followed by the link text
+    doi::find(body).map(|doi| doi.split('}').next().unwrap_or_default().to_string())
+}
+
+/// The `.aux` files in `dir`.
+fn aux_files(dir: &Utf8Path) -> Result<Vec<Utf8PathBuf>> {
+    let mut files = Vec::new();
+    for entry in dir.read_dir_utf8()? {
+        let path = entry?.into_path();
+        if path.extension() == Some("aux") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// The entries of `bib` cited by `keys` without zime's own fields, and the
+/// keys not in the library. Entries they inherit from through `crossref` or
+/// `xdata` are included too, so BibLaTeX can resolve them.
+pub fn subset(bib: &Bibliography, keys: &BTreeSet<String>) -> (Bibliography, Vec<String>) {
+    let mut out = Bibliography::new();
+    let mut missing = Vec::new();
+    let mut add = |entry: &Entry| {
+        let mut entry = entry.clone();
+        provenance::clear(&mut entry);
+        out.insert(entry);
+    };
+    if keys.contains("*") {
+        bib.iter().for_each(&mut add);
+        return (out, missing);
+    }
+    let mut stack: Vec<String> = keys.iter().cloned().collect();
+    let mut seen = BTreeSet::new();
+    while let Some(key) = stack.pop() {
+        if !seen.insert(key.clone()) {
+            continue;
+        }
+        match bib.get(&key) {
+            Some(entry) => {
+                stack.extend(entry::parents(entry));
+                add(entry);
+            }
+            None => missing.push(key),
+        }
+    }
+    missing.sort();
+    (out, missing)
+}
+
+/// Writes the entries cited in `aux` (or all `.aux` files in the current
+/// directory) to `out`. The file is only touched when its content changes, so
+/// that build tools watching it do not rebuild needlessly.
+pub fn latexmk(setup: &Setup, aux: &[Utf8PathBuf], out: &Utf8Path) -> Result<()> {
+    let aux = if aux.is_empty() {
+        aux_files(Utf8Path::new("."))?
+    } else {
+        aux.to_vec()
+    };
+    if aux.is_empty() {
+        warn!("no .aux files found, run LaTeX once first");
+    }
+
+    let mut keys = BTreeSet::new();
+    for file in &aux {
+        keys.extend(aux_citations(file)?);
+    }
+    debug!(?keys, "found citations");
+
+    let bib = setup.bib()?;
+    let (subset, missing) = subset(&bib, &keys);
+    for key in &missing {
+        warn!(%key, "citation not found in the library");
+    }
+    for entry in subset.iter() {
+        if let Some(extended) = versions::extended(&bib, entry) {
+            if !keys.contains(&extended.key) {
+                warn!(key=%entry.key, extended=%extended.key, "citing the short version of a paper with an extended version");
+            }
+        }
+    }
+
+    let content = subset.to_biblatex_string();
+    if fs::read_to_string(out).ok().as_deref() == Some(content.as_str()) {
+        debug!(%out, "bibliography is up to date");
+        return Ok(());
+    }
+    fs::write(out, content)?;
+    info!(%out, entries = subset.len(), "wrote bibliography");
+    Ok(())
+}
@@ -0,0 +1,256 @@
+//! The URLs of an entry: where its publisher, arXiv and its authors host it.
+//!
+//! The DOI, the arXiv eprint and the `url` field each give one. Further links,
+//! such as an author's copy, are kept in the reserved `zime-urls` field as
+//! space separated `kind=url` pairs, e.g. `author=https://example.org/p.pdf`.
+
+use std::collections::BTreeSet;
+
+use biblatex::{Chunk, ChunksExt, Entry, Spanned};
+use color_eyre::{
+    eyre::{bail, eyre},
+    owo_colors::OwoColorize,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Result, bulk, config::Setup, doi, entry, http, i18n::t, prompt, remotes::arxiv,
+    summary::Summary,
+};
+
+pub const FIELD: &str = "zime-urls";
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Deserialize,
+    Serialize,
+    clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    /// The publisher's page, usually behind the DOI
+    Publisher,
+    /// The arXiv abstract page
+    Arxiv,
+    /// A copy on an author's or institution's website
+    #[default]
+    Author,
+    /// Any other mirror
+    Mirror,
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Publisher => "publisher",
+            Kind::Arxiv => "arxiv",
+            Kind::Author => "author",
+            Kind::Mirror => "mirror",
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        [Kind::Publisher, Kind::Arxiv, Kind::Author, Kind::Mirror]
+            .into_iter()
+            .find(|kind| kind.name() == name)
+    }
+}
+
+impl std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Link {
+    pub kind: Kind,
+    pub url: String,
+}
+
+/// The links kept in [`FIELD`].
+pub fn extra(entry: &Entry) -> Vec<Link> {
+    let Some(value) = entry.get(FIELD) else {
+        return Vec::new();
+    };
+    value
+        .format_verbatim()
+        .split_whitespace()
+        .filter_map(|pair| {
+            let (kind, url) = pair.split_once('=')?;
+            Some(Link {
+                kind: Kind::parse(kind)?,
+                url: url.to_string(),
+            })
+        })
+        .collect()
+}
+
+pub fn set_extra(entry: &mut Entry, links: &[Link]) {
+    if links.is_empty() {
+        entry.remove(FIELD);
+        return;
+    }
+    let value = links
+        .iter()
+        .map(|link| format!("{}={}", link.kind, link.url))
+        .collect::<Vec<_>>()
+        .join(" ");
+    entry.set(FIELD, vec![Spanned::detached(Chunk::Verbatim(value))]);
+}
+
+/// Adds `link` to the entry, unless it already has that URL.
+pub fn add(entry: &mut Entry, link: Link) -> bool {
+    if all(entry).iter().any(|l| l.url == link.url) {
+        return false;
+    }
+    let mut links = extra(entry);
+    links.push(link);
+    set_extra(entry, &links);
+    true
+}
+
+/// All links of the entry, the canonical one first.
+pub fn all(entry: &Entry) -> Vec<Link> {
+    let mut links = Vec::new();
+    if let Ok(id) = entry.doi() {
+        links.push(match arxiv::id(&id) {
+            Some(id) => Link {
+                kind: Kind::Arxiv,
+                url: format!("https://arxiv.org/abs/{id}"),
+            },
+            None => Link {
+                kind: Kind::Publisher,
+                url: format!("https://doi.org/{}", doi::url_path(&id)),
+            },
+        });
+    }
+    let eprint = entry
+        .get("eprint")
+        .map(|id| id.format_verbatim())
+        .filter(|_| {
+            entry
+                .get("eprinttype")
+                .is_some_and(|t| t.format_verbatim().eq_ignore_ascii_case("arxiv"))
+        });
+    if let Some(id) = eprint {
+        links.push(Link {
+            kind: Kind::Arxiv,
+            url: format!("https://arxiv.org/abs/{id}"),
+        });
+    }
+    if let Some(url) = entry.get("url").map(|url| url.format_verbatim()) {
+        let kind = if url.contains("arxiv.org/") {
+            Kind::Arxiv
+        } else {
+            Kind::Publisher
+        };
+        links.push(Link { kind, url });
+    }
+    links.extend(extra(entry));
+
+    let mut seen = BTreeSet::new();
+    links.retain(|link| !link.url.is_empty() && seen.insert(link.url.clone()));
+    links
+}
+
+/// Opens a file or URL with the desktop's default application.
+pub fn open(target: &str) -> Result<()> {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    duct::cmd!(opener, target).run()?;
+    Ok(())
+}
+
+/// Opens the downloaded PDF of `entry`.
+pub fn open_pdf(setup: &Setup, entry: &Entry) -> Result<()> {
+    let path = entry
+        .doi()
+        .ok()
+        .map(|doi| setup.pdf_path(&doi))
+        .filter(|path| path.exists())
+        .ok_or_else(|| eyre!("{} has no downloaded PDF", entry::title(entry)))?;
+    open(path.as_str())
+}
+
+/// Lets the user choose one of the links of `entry` and opens it.
+pub fn open_link(entry: &Entry) -> Result<()> {
+    let links = all(entry);
+    let link = match links.as_slice() {
+        [] => bail!("{} has no links", entry::title(entry)),
+        [link] => link,
+        _ => {
+            let i = prompt::select(
+                t!("select-link"),
+                links.iter().map(|link| (&link.url, link.kind.name())),
+                0,
+            )?;
+            &links[i]
+        }
+    };
+    open(&link.url)
+}
+
+/// Checks that `url` resolves, following redirects.
+pub fn check(url: &str) -> Result<()> {
+    http::LINKS.get(url).send()?.error_for_status()?;
+    Ok(())
+}
+
+/// Why a link is broken, coarse enough to count in a summary.
+fn reason(err: &color_eyre::eyre::Error) -> String {
+    match err
+        .downcast_ref::<reqwest::Error>()
+        .and_then(|err| err.status())
+    {
+        Some(status) => format!("HTTP {}", status.as_u16()),
+        None => "unreachable".to_string(),
+    }
+}
+
+/// Checks every link of `entries`, printing the broken ones unless `quiet`.
+pub fn check_all(entries: &[&Entry], quiet: bool) -> Result<Summary> {
+    let spinner = prompt::spinner();
+    spinner.start(t!("links-checking"));
+    let results = bulk::fetch(
+        entries
+            .iter()
+            .flat_map(|entry| all(entry))
+            .map(|link| link.url),
+        |url| spinner.set_message(url),
+        check,
+    )?;
+    spinner.stop(t!("links-checked", count = results.len()));
+
+    let mut summary = Summary::default();
+    for entry in entries {
+        for link in all(entry) {
+            match &results[&link.url] {
+                Ok(()) => summary.succeed(&entry.key),
+                Err(err) => {
+                    if !quiet {
+                        println!(
+                            "{} {}  {} {}",
+                            "✗".red(),
+                            entry.key,
+                            link.url,
+                            reason(err).dimmed()
+                        );
+                    }
+                    summary.fail(&entry.key, reason(err));
+                }
+            }
+        }
+    }
+    Ok(summary)
+}
@@ -0,0 +1,244 @@
+//! Listing the entries of the library.
+
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use biblatex::{Bibliography, Entry};
+use color_eyre::owo_colors::OwoColorize;
+
+use crate::{
+    Result, altmetrics, archive,
+    config::Setup,
+    entry, provenance,
+    query::Query,
+    rankings::{self, Ranking},
+    sidecar::Sidecar,
+    versions,
+};
+
+#[derive(Debug, Default, clap::Args)]
+pub struct Filters {
+    /// Only show entries matching this query, e.g. `"separation logic" AND NOT concurrency`
+    pub query: Vec<String>,
+    #[clap(long)]
+    /// Show archived entries instead
+    pub archived: bool,
+    #[clap(long)]
+    /// Only show entries without a downloaded PDF
+    pub missing_pdf: bool,
+    #[clap(long)]
+    /// Only show entries without a DOI
+    pub no_doi: bool,
+    #[clap(long)]
+    /// Only show entries without an abstract
+    pub no_abstract: bool,
+    #[clap(long)]
+    /// Only show entries without tags
+    pub untagged: bool,
+    #[clap(long, value_name = "PERSON")]
+    /// Only show entries added by this person
+    pub added_by: Option<String>,
+}
+
+/// How to lay out the listed entries.
+#[derive(Debug, Default, Clone, Copy)]
+pub enum Layout {
+    #[default]
+    Flat,
+    /// As a tree grouped by a field
+    Groups(GroupBy),
+    /// Each entry followed by its other versions
+    Versions,
+}
+
+impl Filters {
+    pub fn query(&self) -> Result<Query> {
+        Query::parse(&self.query.join(" "))
+    }
+
+    pub fn matches(&self, setup: &Setup, entry: &Entry) -> bool {
+        let doi = entry.doi().ok();
+        if self.no_doi && doi.is_some() {
+            return false;
+        }
+        if self.missing_pdf && doi.is_some_and(|doi| setup.pdf_path(&doi).exists()) {
+            return false;
+        }
+        if self.no_abstract && entry.abstract_().is_ok() {
+            return false;
+        }
+        if self.untagged && !entry::tags(entry).is_empty() {
+            return false;
+        }
+        if let Some(person) = &self.added_by {
+            let person = person.to_lowercase();
+            if !provenance::added_by(entry)
+                .is_some_and(|name| name.to_lowercase().contains(&person))
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, clap::ValueEnum)]
+pub enum Sort {
+    /// Keep the order of the bibliography file
+    #[default]
+    File,
+    Key,
+    Title,
+    Year,
+    /// Most mentioned first, see `zime altmetrics`
+    Attention,
+}
+
+pub fn sort(entries: &mut [&Entry], sort: Sort, sidecar: &Sidecar) {
+    match sort {
+        Sort::File => {}
+        Sort::Key => entries.sort_by(|a, b| a.key.cmp(&b.key)),
+        Sort::Title => entries.sort_by_cached_key(|e| entry::title(e).to_lowercase()),
+        Sort::Year => entries.sort_by_key(|e| entry::year(e)),
+        Sort::Attention => entries.sort_by_key(|e| Reverse(altmetrics::total(sidecar.get(&e.key)))),
+    }
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum GroupBy {
+    Year,
+    Venue,
+    Tag,
+    Author,
+}
+
+impl GroupBy {
+    /// The groups an entry belongs to. Entries can be in several groups when
+    /// grouping by tag or author.
+    fn groups(self, entry: &Entry) -> Vec<String> {
+        let groups = match self {
+            GroupBy::Year => entry::year(entry)
+                .map(|y| y.to_string())
+                .into_iter()
+                .collect(),
+            GroupBy::Venue => entry::venue(entry).into_iter().collect(),
+            GroupBy::Tag => entry::tags(entry),
+            GroupBy::Author => entry
+                .author()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|a| a.to_string())
+                .collect(),
+        };
+        if groups.is_empty() {
+            let missing = match self {
+                GroupBy::Year => "no year",
+                GroupBy::Venue => "no venue",
+                GroupBy::Tag => "untagged",
+                GroupBy::Author => "no author",
+            };
+            vec![format!("({missing})")]
+        } else {
+            groups
+        }
+    }
+}
+
+pub fn run(setup: &Setup, filters: &Filters, order: Sort, layout: Layout) -> Result<()> {
+    let query = filters.query()?;
+    let bib = if filters.archived {
+        archive::load(setup)?
+    } else {
+        setup.bib()?
+    };
+    let mut entries = query.filter(setup, &bib)?;
+    entries.retain(|entry| filters.matches(setup, entry));
+    sort(&mut entries, order, &Sidecar::load(setup)?);
+    let rankings = rankings::load_all(setup)?;
+
+    let group_by = match layout {
+        Layout::Flat => {
+            for entry in entries {
+                print_entry(entry, &rankings);
+            }
+            return Ok(());
+        }
+        Layout::Versions => {
+            print_versions(&bib, &entries, &rankings);
+            return Ok(());
+        }
+        Layout::Groups(group_by) => group_by,
+    };
+
+    let mut groups: BTreeMap<String, Vec<&Entry>> = BTreeMap::new();
+    for entry in entries {
+        for group in group_by.groups(entry) {
+            groups.entry(group).or_default().push(entry);
+        }
+    }
+    for (group, entries) in groups {
+        println!(
+            "{} {}",
+            group.bold(),
+            format!("({})", entries.len()).dimmed()
+        );
+        for (i, entry) in entries.iter().enumerate() {
+            let branch = if i + 1 == entries.len() {
+                "└─"
+            } else {
+                "├─"
+            };
+            println!(
+                "  {} {} {}",
+                branch.dimmed(),
+                entry::title(entry),
+                entry::authors(entry).italic().dimmed()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Prints the entries that are not another entry's longer version, each
+/// followed by its other versions.
+fn print_versions(bib: &Bibliography, entries: &[&Entry], rankings: &[Ranking]) {
+    let nested: BTreeSet<&str> = entries
+        .iter()
+        .flat_map(|entry| versions::get(entry))
+        .filter_map(|version| bib.get(&version.key))
+        .map(|entry| entry.key.as_str())
+        .collect();
+    for entry in entries {
+        if nested.contains(entry.key.as_str()) {
+            continue;
+        }
+        print_entry(entry, rankings);
+        let related = versions::related(bib, entry);
+        for (i, (relation, other)) in related.iter().enumerate() {
+            let branch = if i + 1 == related.len() {
+                "└─"
+            } else {
+                "├─"
+            };
+            println!(
+                "  {} {} {} {}",
+                branch.dimmed(),
+                format!("{relation}:").dimmed(),
+                entry::title(other),
+                format!("({})", other.key).dimmed()
+            );
+        }
+    }
+}
+
+pub fn print_entry(entry: &Entry, rankings: &[Ranking]) {
+    let title = entry::title(entry);
+    let authors = entry::authors(entry);
+    let doi = entry.doi().unwrap_or_default();
+    println!("{} ({})\n  {}", title.bold(), doi, authors.italic());
+    if let Some(ranks) = rankings::describe(rankings, entry) {
+        println!("  {}", ranks.dimmed());
+    }
+}
@@ -0,0 +1,195 @@
+//! Local-only entries, which are never committed or pushed, for confidential
+//! drafts and papers under review kept alongside a shared reading library.
+//!
+//! An entry is local-only if `zime local` marked it, in the reserved
+//! `zime-local` field, or if it has one of the tags in `local_tags`:
+//!
+//! ```toml
+//! local_tags = ["confidential", "under-review"]
+//! ```
+//!
+//! Local-only entries are kept in `local.bib` instead of the bibliography, and
+//! their notes, metadata and attachments are listed in the repository's
+//! `.git/info/exclude`, which is itself not committed. Their read status and
+//! the rest of their data in the sidecar go in `local.lock` instead of
+//! `zime.lock`, archived ones go in `local-archive.bib` instead of
+//! `archive.bib`, and sync commits do not list them.
+
+use std::{collections::HashSet, fs};
+
+use biblatex::{Bibliography, Chunk, Entry, Spanned};
+use camino::Utf8PathBuf;
+use color_eyre::eyre::eyre;
+use duct::cmd;
+use tracing::debug;
+
+use crate::{Result, archive, config::Setup, dry_run, entry, sidecar};
+
+pub const FIELD: &str = "zime-local";
+pub const FILE: &str = "local.bib";
+
+/// Marks the start and end of the lines zime manages in the exclude file.
+const BEGIN: &str = "# zime local-only entries, managed by zime";
+const END: &str = "# end of zime local-only entries";
+
+pub fn path(setup: &Setup) -> Utf8PathBuf {
+    setup.root().join(FILE)
+}
+
+/// Whether the entry was marked local-only, not counting its tags.
+pub fn is_marked(entry: &Entry) -> bool {
+    entry.get(FIELD).is_some()
+}
+
+/// Whether the entry is local-only, marked or by one of `local_tags`.
+pub fn is_local(entry: &Entry, local_tags: &[String]) -> bool {
+    is_marked(entry)
+        || entry::tags(entry)
+            .iter()
+            .any(|tag| local_tags.contains(tag))
+}
+
+pub fn set(entry: &mut Entry, local: bool) {
+    if local {
+        entry.set(
+            FIELD,
+            vec![Spanned::detached(Chunk::Verbatim("true".to_string()))],
+        );
+    } else {
+        entry.remove(FIELD);
+    }
+}
+
+pub fn load(setup: &Setup) -> Result<Bibliography> {
+    let path = path(setup);
+    if !path.exists() {
+        return Ok(Bibliography::new());
+    }
+    let src = fs::read_to_string(&path)?;
+    Bibliography::parse(&src).map_err(|err| eyre!("failed to parse {path}: {err}"))
+}
+
+/// Splits `bib` into the entries that are shared and those that are
+/// local-only.
+pub fn split(setup: &Setup, bib: &Bibliography) -> Result<(Bibliography, Bibliography)> {
+    let local_tags = setup.config()?.local_tags;
+    let mut shared = Bibliography::new();
+    let mut local = Bibliography::new();
+    for entry in bib.iter() {
+        if is_local(entry, &local_tags) {
+            local.insert(entry.clone());
+        } else {
+            shared.insert(entry.clone());
+        }
+    }
+    Ok((shared, local))
+}
+
+/// The keys of the local-only entries, in `local.bib` and archived.
+pub fn keys(setup: &Setup) -> Result<HashSet<String>> {
+    Ok(load(setup)?
+        .iter()
+        .chain(archive::load_local(setup)?.iter())
+        .map(|entry| entry.key.clone())
+        .collect())
+}
+
+/// Writes the local-only entries to `local.bib`, and keeps them, the archived
+/// ones and their files out of git.
+pub fn save(setup: &Setup, local: &Bibliography) -> Result<()> {
+    let path = path(setup);
+    let archived = archive::load_local(setup)?;
+    if local.is_empty() && archived.is_empty() && !path.exists() {
+        return Ok(());
+    }
+    fs::write(&path, local.to_biblatex_string())?;
+    if setup.git().is_some() {
+        let keys: Vec<&str> = local
+            .iter()
+            .chain(archived.iter())
+            .map(|entry| entry.key.as_str())
+            .collect();
+        update_exclude(setup, &keys)?;
+    }
+    Ok(())
+}
+
+/// The patterns of the files belonging to the entries with `keys`, relative
+/// to the library.
+fn patterns(keys: &[&str]) -> Vec<String> {
+    let mut patterns = vec![
+        FILE.to_string(),
+        sidecar::LOCAL_FILE.to_string(),
+        archive::LOCAL_FILE.to_string(),
+    ];
+    for key in keys {
+        patterns.push(format!("notes/{key}.*"));
+        patterns.push(format!("notes/*/{key}.*"));
+        patterns.push(format!("meta/{key}.*"));
+        patterns.push(format!("attachments/{key}/"));
+    }
+    patterns
+}
+
+/// Replaces zime's block in `.git/info/exclude` with the files of the
+/// local-only entries, leaving the rest of the file as it is.
+fn update_exclude(setup: &Setup, keys: &[&str]) -> Result<()> {
+    let exclude = cmd!("git", "rev-parse", "--git-path", "info/exclude")
+        .dir(setup.root())
+        .read()?;
+    let exclude = setup.root().join(exclude.trim());
+    // The patterns in the exclude file are relative to the top of the
+    // repository, which the library may be inside of
+    let prefix = cmd!("git", "rev-parse", "--show-prefix")
+        .dir(setup.root())
+        .read()?;
+    let current = fs::read_to_string(&exclude).unwrap_or_default();
+    let mut lines = Vec::new();
+    let mut in_block = false;
+    for line in current.lines() {
+        match line {
+            BEGIN => in_block = true,
+            END => in_block = false,
+            _ if !in_block => lines.push(line.to_string()),
+            _ => {}
+        }
+    }
+    lines.push(BEGIN.to_string());
+    lines.extend(
+        patterns(keys)
+            .into_iter()
+            .map(|pattern| format!("/{}{pattern}", prefix.trim())),
+    );
+    lines.push(END.to_string());
+    if let Some(dir) = exclude.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&exclude, lines.join("\n") + "\n")?;
+    debug!(%exclude, count = keys.len(), "updated excluded files");
+    Ok(())
+}
+
+/// Stops tracking the files of entries that were shared before they became
+/// local-only, so that the next sync removes them from the repository.
+pub fn untrack(setup: &Setup, keys: &[&str]) -> Result<()> {
+    if setup.git().is_none() || dry_run::enabled() {
+        return Ok(());
+    }
+    for pattern in patterns(keys) {
+        let pattern = format!(":(glob){}", pattern.trim_matches('/'));
+        cmd!(
+            "git",
+            "rm",
+            "-r",
+            "-q",
+            "--cached",
+            "--ignore-unmatch",
+            "--",
+            pattern
+        )
+        .dir(setup.root())
+        .stderr_null()
+        .run()?;
+    }
+    Ok(())
+}
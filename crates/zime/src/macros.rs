@@ -0,0 +1,110 @@
+//! BibTeX `@string` abbreviations.
+//!
+//! The parser expands abbreviations, so on every write the definitions in the
+//! previous `references.bib` are carried over, and fields whose value is
+//! exactly that of one of them are written as the abbreviation again.
+//!
+//! Abbreviations can also be defined in `strings.define` in the config. With
+//! `strings.abbreviate_venues` they are used for the venue fields of entries,
+//! e.g. to write `journaltitle = pacmpl`.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+use biblatex::Bibliography;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct StringsConfig {
+    /// Abbreviations by name, e.g. `pacmpl = "Proc. ACM Program. Lang."`
+    pub define: BTreeMap<String, String>,
+    /// Write venue fields as an abbreviation from `define` where one matches
+    pub abbreviate_venues: bool,
+}
+
+/// Venue fields as the serializer names them.
+const VENUE_FIELDS: [&str; 3] = ["journaltitle", "booktitle", "eventtitle"];
+
+/// The `@string` definitions in BibTeX source, in order.
+pub fn parse(src: &str) -> Vec<(String, String)> {
+    let mut strings = Vec::new();
+    // ASCII lowercasing keeps byte offsets
+    let lower = src.to_ascii_lowercase();
+    let mut from = 0;
+    while let Some(i) = lower[from..].find("@string") {
+        from += i + "@string".len();
+        let Some(body) = src[from..].trim_start().strip_prefix('{') else {
+            continue;
+        };
+        let Some((name, value)) = body.split_once('=') else {
+            continue;
+        };
+        if let Some(value) = delimited(value.trim_start()) {
+            strings.push((name.trim().to_string(), value.to_string()));
+        }
+    }
+    strings
+}
+
+/// The content of a `{...}` or `"..."` delimited value at the start of `s`.
+fn delimited(s: &str) -> Option<&str> {
+    if let Some(rest) = s.strip_prefix('"') {
+        return rest.find('"').map(|end| &rest[..end]);
+    }
+    s.strip_prefix('{')?;
+    let mut depth = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&s[1..i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Serializes `bib` with the abbreviations defined in `previous`, the current
+/// content of the file, and in the config.
+pub fn to_biblatex_string(bib: &Bibliography, previous: &str, config: &StringsConfig) -> String {
+    let mut strings = parse(previous);
+    let from_file: BTreeSet<String> = strings.iter().map(|(name, _)| name.clone()).collect();
+    for (name, value) in &config.define {
+        if !strings.iter().any(|(n, _)| n.eq_ignore_ascii_case(name)) {
+            strings.push((name.clone(), value.clone()));
+        }
+    }
+    if strings.is_empty() {
+        return bib.to_biblatex_string();
+    }
+
+    let mut out = String::new();
+    for (name, value) in &strings {
+        writeln!(out, "@string{{{name} = {{{value}}}}}").unwrap();
+    }
+    for entry in bib.iter() {
+        out.push('\n');
+        for line in entry.to_biblatex_string().lines() {
+            let abbreviation = line.split_once(" = ").and_then(|(field, value)| {
+                let value = value.strip_suffix(',')?;
+                let (name, _) = strings.iter().find(|(name, expansion)| {
+                    value.strip_prefix('{').and_then(|v| v.strip_suffix('}'))
+                        == Some(expansion.as_str())
+                        && (from_file.contains(name)
+                            || config.abbreviate_venues && VENUE_FIELDS.contains(&field))
+                })?;
+                Some(format!("{field} = {name},"))
+            });
+            out.push_str(abbreviation.as_deref().unwrap_or(line));
+            out.push('\n');
+        }
+    }
+    out
+}
@@ -1,16 +1,77 @@
+mod add;
+mod altmetrics;
+mod archive;
+mod backup;
+mod blame;
+mod bulk;
+mod bundle;
+mod cancel;
+mod consistency;
+mod diff;
+mod doctor;
+mod doi;
+mod dry_run;
+mod entry;
+mod export;
+mod forge;
+mod group;
+mod health;
+mod http;
+mod i18n;
+mod import;
+mod index;
+mod language;
+mod latex;
+mod links;
+mod list;
+mod local;
+mod macros;
+mod markup;
+mod merge;
+mod meta;
+mod note;
+mod notify;
+mod pdf_steps;
+mod pdf_text;
+mod pdfs;
+mod pick;
+mod prompt;
+mod provenance;
+mod query;
+mod queue;
+mod random;
+mod rankings;
+mod recommend;
+mod refetch;
 mod remotes;
+mod restore;
+mod select;
+mod share;
+mod show;
+mod sidecar;
+mod stats;
+mod store;
+mod submodule;
+mod summary;
+mod tag_rules;
+mod title_case;
+mod transaction;
+mod typst;
+mod updates;
+mod venues;
+mod versions;
+mod which;
 
 use std::fs;
 
-use biblatex::ChunksExt;
 use camino::Utf8PathBuf;
 use clap::Parser as _;
 use color_eyre::{eyre::eyre, owo_colors::OwoColorize};
 use config::Setup;
 use duct::cmd;
+use i18n::t;
 use itertools::Itertools;
-use remotes::arxiv::is_arxiv;
-use tracing::{debug, info, warn};
+use tracing::{debug, info};
 
 type Result<T, E = color_eyre::eyre::Error> = std::result::Result<T, E>;
 
@@ -20,6 +81,22 @@ struct Cli {
     cmd: Command,
     #[command(flatten)]
     verbose: clap_verbosity_flag::Verbosity,
+    #[clap(long, global = true)]
+    /// Print what would change instead of writing to the library
+    dry_run: bool,
+    #[clap(long, global = true)]
+    /// Library to use, a `.zime` directory or a directory containing one
+    root: Option<Utf8PathBuf>,
+    #[clap(long, global = true)]
+    /// Sync with the git remote even if `sync_interval` has not passed
+    force_sync: bool,
+    #[clap(long, global = true, value_enum)]
+    /// How to ask questions, instead of the `[prompt]` backend in the global
+    /// config
+    prompt: Option<prompt::Kind>,
+    #[clap(long, global = true)]
+    /// Ask remotes again instead of reusing their cached answers
+    no_cache: bool,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -29,20 +106,460 @@ enum Command {
         /// Setup local zine repository
         local: bool,
         #[clap(long)]
-        /// Location of git repository
+        /// Location of git repository, or `github:owner/name`/`gitlab:group/name`
+        /// to create it through the forge API
         git: Option<String>,
+        #[clap(long, requires_all = ["local", "git"])]
+        /// Add the local library to the enclosing git repository as a
+        /// submodule
+        submodule: bool,
     },
     Sync {},
     Index {
         query: Vec<String>,
+        #[clap(
+            long = "source",
+            value_delimiter = ',',
+            default_values = ["dblp", "crossref", "arxiv", "semanticscholar"],
+            value_parser = index::sources()
+        )]
+        /// Where to search, can be repeated or comma separated
+        sources: Vec<String>,
+        #[clap(long)]
+        /// Only search entries from this tracked venue
+        venue: Option<String>,
+    },
+    #[clap(group(clap::ArgGroup::new("from").required(true)))]
+    /// Add many works at once, skipping those already in the library
+    Import {
+        #[clap(long, group = "from")]
+        /// All works on an ORCID profile, by iD or profile URL
+        orcid: Option<String>,
+        #[clap(long, group = "from")]
+        /// All publications of a DBLP author, by PID, author page URL or name
+        dblp_author: Option<String>,
+        #[clap(long, group = "from")]
+        /// All papers of a DBLP conference edition or journal volume, by key or
+        /// URL, e.g. `conf/popl/popl2020`
+        dblp_venue: Option<String>,
+        #[clap(long, group = "from")]
+        /// All works cited in a paper, by its `.aux` file and the `.bbl` file
+        /// next to it
+        aux: Option<Utf8PathBuf>,
+        #[clap(short, long)]
+        /// Import all works without asking which
+        yes: bool,
+        #[clap(long)]
+        /// Print the summary as JSON
+        json: bool,
+    },
+    /// Add works cited by an entry, from its reference list on Crossref
+    Refs {
+        query: String,
+        #[clap(short, long)]
+        /// Add all cited works without asking which
+        yes: bool,
+        #[clap(long)]
+        /// Print the summary as JSON
+        json: bool,
+    },
+    /// Add works citing an entry, from Semantic Scholar and OpenCitations
+    CitedBy {
+        query: String,
+        #[clap(long, default_value_t = 100)]
+        /// How many citing works to offer at most
+        limit: usize,
+        #[clap(short, long)]
+        /// Add all citing works without asking which
+        yes: bool,
+        #[clap(long)]
+        /// Print the summary as JSON
+        json: bool,
+    },
+    /// List papers related to an entry, from Semantic Scholar, and add the
+    /// ones picked
+    Recommend {
+        query: String,
+        #[clap(long, default_value_t = 20)]
+        /// How many papers to ask for, before those in the library are left out
+        limit: usize,
+    },
+    /// Add an entry by its DOI, with metadata from Crossref
+    Add {
+        #[clap(long, required_unless_present = "pdf", conflicts_with = "pdf")]
+        doi: Option<String>,
+        #[clap(long)]
+        /// Add the work in a PDF, by its embedded DOI or with GROBID
+        pdf: Option<Utf8PathBuf>,
+    },
+    /// Show the details of an entry
+    Show {
+        query: String,
+    },
+    /// Open the downloaded PDF of an entry
+    Open {
+        query: String,
+        #[clap(long)]
+        /// Choose one of the entry's links to open in the browser instead
+        url: bool,
+    },
+    /// Add a link to an entry, such as a copy on an author's website
+    Link {
+        query: String,
+        url: String,
+        #[clap(long, value_enum, default_value_t)]
+        /// What the link points to
+        kind: links::Kind,
+    },
+    #[clap(group(clap::ArgGroup::new("version").required(true).multiple(true)))]
+    /// Link a paper to its extended version or its preprint
+    Relate {
+        query: String,
+        #[clap(long, group = "version")]
+        /// The journal or otherwise extended version of the paper
+        extended: Option<String>,
+        #[clap(long, group = "version")]
+        /// The preprint of the paper
+        preprint: Option<String>,
+    },
+    /// Check which Sci-Hub mirrors respond, fastest first
+    Mirrors,
+    /// Inspect the remote sources of metadata and PDFs
+    Remotes {
+        #[clap(subcommand)]
+        cmd: RemotesCommand,
+    },
+    /// Check that the links of entries still resolve
+    CheckLinks {
+        #[clap(long)]
+        /// Only check entries matching this query
+        only: Option<String>,
+        #[clap(long)]
+        /// Print the summary as JSON
+        json: bool,
+    },
+    /// Show when an entry was added and every change to it since
+    Blame {
+        query: String,
+    },
+    /// Open the note of an entry, creating it from a template if needed
+    Note {
+        query: String,
+        #[clap(long)]
+        /// Name of the template in the `templates/` directory to use
+        template: Option<String>,
+    },
+    /// Print the DOI or arXiv link of an entry
+    Share {
+        query: String,
+        #[clap(long)]
+        /// Also render the link as a QR code
+        qr: bool,
+    },
+    /// Pick an entry and print its key, or do something else with it
+    Pick {
+        query: Vec<String>,
+        #[clap(long)]
+        /// Print one line per entry for rofi, dmenu or fzf, or with `--then`
+        /// read the chosen line from stdin
+        dmenu: bool,
+        #[clap(long, value_enum)]
+        /// What to do with the picked entry, defaults to printing its key
+        then: Option<pick::Action>,
+    },
+    /// Pick an entry by typing and print its key, for editor integrations
+    PickKey {
+        query: Vec<String>,
+    },
+    /// Show a random entry, optionally one matching a query
+    Random {
+        query: Vec<String>,
+    },
+    /// Track venues of interest and their deadlines
+    Venues {
+        #[clap(subcommand)]
+        cmd: VenuesCommand,
+    },
+    /// Manage the reading queue
+    Queue {
+        #[clap(subcommand)]
+        cmd: QueueCommand,
+    },
+    /// Move entries out of the bibliography into the archive
+    Archive {
+        query: String,
+        #[clap(long)]
+        /// Archive all matching entries without asking which
+        all: bool,
+    },
+    /// Set the read status of entries
+    Mark {
+        query: String,
+        #[clap(value_enum)]
+        status: sidecar::ReadStatus,
+        #[clap(long)]
+        /// Mark all matching entries without asking which
+        all: bool,
+    },
+    /// Rate entries from 1 to 5
+    Rate {
+        query: String,
+        #[clap(value_parser = clap::value_parser!(u8).range(1..=5))]
+        rating: u8,
+        #[clap(long)]
+        /// Rate all matching entries without asking which
+        all: bool,
+    },
+    #[clap(hide = true)]
+    /// Merge driver for zime.lock in reading-group mode, run by git
+    MergeSidecar {
+        base: Utf8PathBuf,
+        ours: Utf8PathBuf,
+        theirs: Utf8PathBuf,
+    },
+    /// Add or remove tags of entries
+    Tag {
+        query: String,
+        #[clap(long)]
+        /// Tag to add, can be repeated
+        add: Vec<String>,
+        #[clap(long)]
+        /// Tag to remove, can be repeated
+        remove: Vec<String>,
+        #[clap(long)]
+        /// Tag all matching entries without asking which
+        all: bool,
+    },
+    /// Add the tags of the configured tag rules to every matching entry
+    Retag,
+    /// Normalize the casing of titles as configured in `[title_case]`
+    Fmt {},
+    /// Re-download the record an entry was fetched from and apply changes
+    Refetch {
+        query: String,
+        #[clap(short, long)]
+        /// Apply all changes without asking
+        yes: bool,
+    },
+    /// Rename entries, moving their notes, metadata, attachments and read
+    /// status along
+    Rekey {
+        query: String,
+        #[clap(long)]
+        /// The new key, by default one derived from the metadata such as
+        /// `reynolds2002separation`
+        to: Option<String>,
+        #[clap(long, conflicts_with = "to")]
+        /// Rename all entries matching the query without asking which
+        all: bool,
+    },
+    #[clap(visible_alias = "search")]
+    List {
+        #[command(flatten)]
+        filters: list::Filters,
+        #[clap(long, value_enum, default_value_t)]
+        /// Order in which to print entries
+        sort: list::Sort,
+        #[clap(long, value_enum, conflicts_with = "tree")]
+        /// Print entries as a tree grouped by this field
+        group_by: Option<list::GroupBy>,
+        #[clap(long)]
+        /// Print each entry with its extended version and preprint
+        tree: bool,
     },
-    List {},
     Rm {
         #[clap(short, long)]
         force: bool,
+        #[clap(long)]
+        /// Remove all matching entries without asking which
+        all: bool,
+        query: String,
+    },
+    /// Keep entries out of git, or share them again with `--undo`
+    Local {
         query: String,
+        #[clap(long)]
+        /// Share the entries again
+        undo: bool,
+        #[clap(long)]
+        /// Change all matching entries without asking which
+        all: bool,
+    },
+    /// Detect the language of entries that do not have one
+    DetectLanguage {},
+    /// Show statistics about the library
+    Stats {
+        #[clap(long)]
+        /// Show entries added, PDFs downloaded and notes written per month
+        growth: bool,
+        #[clap(long, value_enum, default_value_t, requires = "growth")]
+        /// How to print the monthly counts
+        format: stats::GrowthFormat,
+    },
+    /// Print the paths of the library in use and why it was chosen
+    Which {},
+    /// Check entries for mangled math and characters, retractions and errata
+    Doctor {
+        #[clap(long)]
+        /// Replace mangled titles and abstracts with those of other remotes
+        repair: bool,
+    },
+    /// Check arXiv for newer versions of preprints, and update those picked
+    CheckUpdates {
+        #[clap(short, long)]
+        /// Update all preprints with a newer version without asking
+        yes: bool,
+    },
+    /// Fetch how often entries are mentioned outside the literature
+    Altmetrics {
+        #[clap(long)]
+        /// Only consider entries matching this query
+        only: Option<String>,
+        #[clap(long)]
+        /// Fetch again even if the cached counts are recent
+        force: bool,
+    },
+    Pdfs {
+        #[clap(long)]
+        /// Show which entries have a PDF instead of downloading
+        status: bool,
+        #[clap(long)]
+        /// Only consider entries matching this query
+        only: Option<String>,
+        #[clap(long)]
+        /// Print the summary as JSON
+        json: bool,
+    },
+    Bundle {
+        #[clap(subcommand)]
+        cmd: BundleCommand,
+    },
+    /// Take a snapshot of the library
+    Backup {
+        #[clap(long)]
+        /// List existing snapshots instead
+        list: bool,
+    },
+    /// Rename PDFs stored under an older naming scheme
+    Migrate {},
+    /// Write the bibliography without zime's own fields
+    Export {
+        #[clap(short, long)]
+        /// File to write, defaults to standard output
+        out: Option<Utf8PathBuf>,
+        #[clap(long)]
+        /// Export the bibliography as of this git commit or tag
+        at: Option<String>,
+        #[clap(long)]
+        /// Keep only the fields a reference list needs and derive keys from
+        /// the metadata, for double-blind submissions
+        anonymize: bool,
+        #[clap(long)]
+        /// Export only the entries new or changed since the last export to
+        /// the same file
+        since_last: bool,
+    },
+    /// Write the entries cited by a LaTeX project to a local bibliography
+    Latexmk {
+        /// `.aux` files to read citations from, defaults to all in the current directory
+        aux: Vec<Utf8PathBuf>,
+        #[clap(short, long, default_value = "zime.bib")]
+        /// Bibliography to write
+        out: Utf8PathBuf,
+    },
+    /// Write the entries cited by a Typst project to a local Hayagriva file
+    Typst {
+        #[clap(default_value = ".")]
+        /// Project directory to scan for `.typ` files
+        dir: Utf8PathBuf,
+        #[clap(short, long, default_value = "zime.yml")]
+        /// Hayagriva file to write
+        out: Utf8PathBuf,
+    },
+    /// Restore the bibliography, with read status and archived entries, from a
+    /// backup snapshot or a git commit
+    Restore {
+        #[clap(long)]
+        /// Snapshot file, snapshot name or git revision
+        from: String,
+        #[clap(long)]
+        /// Also restore notes
+        notes: bool,
+        #[clap(short, long)]
+        /// Restore without asking for confirmation
+        yes: bool,
     },
-    Pdfs {},
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum BundleCommand {
+    /// Write the library to a zip archive
+    Export {
+        out: Utf8PathBuf,
+        #[clap(long)]
+        /// Include downloaded PDFs in the archive
+        pdfs: bool,
+    },
+    /// Merge a bundle into the current library
+    Import {
+        file: Utf8PathBuf,
+        #[clap(long)]
+        /// Print the summary as JSON
+        json: bool,
+    },
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum RemotesCommand {
+    /// Check that each remote answers and accepts the configured credentials
+    Status {
+        #[clap(long)]
+        /// Print the results as JSON
+        json: bool,
+    },
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum VenuesCommand {
+    /// Track a venue, or update a tracked one
+    Add {
+        name: String,
+        #[clap(long = "alias")]
+        /// Other name the venue appears under in entries, can be repeated
+        aliases: Vec<String>,
+        #[clap(long)]
+        /// Name of the venue in DBLP, if it differs
+        dblp: Option<String>,
+        #[clap(long)]
+        /// Submission deadline, e.g. 2025-07-10
+        deadline: Option<jiff::civil::Date>,
+    },
+    /// Stop tracking a venue
+    Rm { name: String },
+    /// List tracked venues, upcoming deadlines first
+    List,
+    /// Show the entries and frequent authors of a venue
+    Show { name: String },
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum QueueCommand {
+    /// Add an entry to the reading queue, or update its priority and due date
+    Add {
+        query: String,
+        #[clap(short, long, default_value_t = 0)]
+        /// Entries with higher priority are read first
+        priority: i32,
+        #[clap(long)]
+        /// Date by which to have read the entry, e.g. 2025-06-01
+        due: Option<jiff::civil::Date>,
+    },
+    /// Show what to read next
+    Next,
+    /// Remove an entry from the queue and mark it as read
+    Done { query: String },
+    /// List the queue in reading order
+    List,
 }
 
 fn main() -> Result<()> {
@@ -56,8 +573,29 @@ fn main() -> Result<()> {
         .with_max_level(args.verbose)
         .init();
 
+    if args.dry_run {
+        dry_run::enable();
+    }
+    cancel::install()?;
+    if let Some(root) = args.root {
+        config::set_root(root);
+    }
+    if args.force_sync {
+        config::force_sync();
+    }
+    if args.no_cache {
+        http::disable_cache();
+    }
+    if let Some(kind) = args.prompt {
+        prompt::set(kind);
+    }
+
     match args.cmd {
-        Command::Init { local, git } => {
+        Command::Init {
+            local,
+            git,
+            submodule,
+        } => {
             let root = if local {
                 Some(
                     Utf8PathBuf::from_path_buf(fs::canonicalize(".")?)
@@ -80,7 +618,18 @@ fn main() -> Result<()> {
                     debug!("git repository already exists");
                 } else {
                     debug!("creating new git repository");
-                    cmd!("git", "init").dir(setup.root()).run()?;
+                    cmd!("git", "init", "-b", "main").dir(setup.root()).run()?;
+                };
+
+                let git = if let Some(repo) = forge::ForgeRepo::parse(git) {
+                    let config = if setup.config_file().exists() {
+                        config::Config::load(&setup.config_file())?
+                    } else {
+                        config::Config::load_global()?
+                    };
+                    repo.ensure_exists(&config.forge)?
+                } else {
+                    git.to_string()
                 };
 
                 // ignore the result of this command
@@ -136,217 +685,581 @@ pdfs/
             }
 
             setup.sync_git()?;
+            if submodule {
+                if let Some(git) = setup.git() {
+                    submodule::add(&setup.root(), git)?;
+                }
+            }
         }
         Command::Sync {} => {
             let setup = Setup::determine_from_cwd()?;
+            config::force_sync();
             setup.sync_git()?;
         }
-        Command::Index { query } => {
+        Command::Index {
+            query,
+            sources,
+            venue,
+        } => {
             let setup = Setup::determine_from_cwd()?;
+            index::run(&setup, &query.join(" "), &sources, venue.as_deref())?;
+        }
+        Command::Import {
+            orcid,
+            dblp_author,
+            dblp_venue,
+            aux,
+            yes,
+            json,
+        } => {
+            let setup = Setup::determine_from_cwd()?;
+            let report = match (orcid, dblp_author, dblp_venue, aux) {
+                (Some(id), _, _, _) => import::orcid(&setup, &id)?,
+                (_, Some(author), _, _) => import::dblp_author(&setup, &author, yes)?,
+                (_, _, Some(venue), _) => import::dblp_venue(&setup, &venue, yes)?,
+                (_, _, _, Some(aux)) => import::aux(&setup, &aux)?,
+                (None, None, None, None) => unreachable!("clap requires a source"),
+            };
+            report.summary().print(json)?;
+        }
+        Command::Refs { query, yes, json } => {
+            let setup = Setup::determine_from_cwd()?;
+            let bib = setup.bib()?;
+            let entry = select::find_one(&bib, &query, &t!("select-article-for-refs"))?;
+            let report = import::references(&setup, entry, yes)?;
+            report.summary().print(json)?;
+        }
+        Command::CitedBy {
+            query,
+            limit,
+            yes,
+            json,
+        } => {
+            let setup = Setup::determine_from_cwd()?;
+            let bib = setup.bib()?;
+            let entry = select::find_one(&bib, &query, &t!("select-article-for-cited-by"))?;
+            let report = import::cited_by(&setup, entry, limit, yes)?;
+            report.summary().print(json)?;
+        }
+        Command::Recommend { query, limit } => {
+            let setup = Setup::determine_from_cwd()?;
+            let bib = setup.bib()?;
+            let entry = select::find_one(&bib, &query, &t!("select-article-to-recommend"))?;
+            recommend::run(&setup, entry, limit)?;
+        }
+        Command::Add { doi, pdf } => {
+            let setup = Setup::determine_from_cwd()?;
+            match (doi, pdf) {
+                (Some(doi), _) => add::by_doi(&setup, &doi)?,
+                (None, Some(pdf)) => add::by_pdf(&setup, &pdf)?,
+                (None, None) => unreachable!("clap requires --doi or --pdf"),
+            };
+        }
+        Command::Rm { force, all, query } => {
+            let setup = Setup::determine_from_cwd()?;
+            let mut bib = setup.bib()?;
 
-            let spinner = cliclack::spinner();
-            spinner.start("Looking up articles...");
-            let res = remotes::dblp::search(&query.join(" "))?;
-            spinner.stop("");
-
-            let selection = cliclack::select("Select article")
-                .items(
-                    &res.result
-                        .hits
-                        .hit
-                        .iter()
-                        .map(|hit| {
-                            (
-                                hit,
-                                format!(
-                                    "{} ({})",
-                                    hit.info.title.bold(),
-                                    hit.info
-                                        .authors
-                                        .author
-                                        .iter()
-                                        .map(|a| a.text.italic())
-                                        .format(", ")
-                                ),
-                                if let Some(doi) = hit.info.doi.as_ref() {
-                                    format!("DOI: {}", doi)
-                                } else {
-                                    "".to_string()
-                                },
-                            )
-                        })
-                        .collect_vec(),
-                )
-                .interact()?;
-            cliclack::outro("Added!")?;
+            let selection = select::find_many(&bib, &query, &t!("select-article-to-remove"), all)?;
+            let prompt = match selection.as_slice() {
+                [entry] => t!("confirm-remove", title = entry::title(entry)),
+                _ => t!("confirm-remove-many", count = selection.len()),
+            };
+            let keys = selection
+                .iter()
+                .map(|entry| entry.key.clone())
+                .collect_vec();
+            let dependents = keys
+                .iter()
+                .flat_map(|key| entry::dependents(&bib, key))
+                .filter(|entry| !keys.contains(&entry.key))
+                .map(|entry| entry.key.clone())
+                .unique()
+                .collect_vec();
+            if !dependents.is_empty() {
+                println!(
+                    "{}",
+                    t!("remove-dependents", keys = dependents.join(", ")).yellow()
+                );
+            }
 
-            let spinner = cliclack::spinner();
-            spinner.start("Downloading bibliography...");
-            let bib_entry = selection.bib()?;
+            if force || prompt::confirm(prompt)? {
+                backup::before_destructive(&setup, "rm")?;
+                let tx = transaction::Transaction::begin(&setup)?;
+                for key in &keys {
+                    if bib.remove(key).is_none() {
+                        return Err(eyre!(t!("remove-failed")));
+                    }
+                }
+                setup.write_bib(&bib)?;
+                setup.sync_git()?;
+                tx.commit();
+            }
+        }
+        Command::Show { query } => {
+            let setup = Setup::determine_from_cwd()?;
+            let bib = setup.bib()?;
+            let entry = select::find_one(&bib, &query, &t!("select-article"))?;
+            let sidecar = sidecar::Sidecar::load(&setup)?;
+            let meta = meta::load(&setup, &entry.key)?;
+            show::print(&setup, &bib, entry, sidecar.get(&entry.key), meta.as_ref());
+        }
+        Command::Open { query, url } => {
+            let setup = Setup::determine_from_cwd()?;
+            let bib = setup.bib()?;
+            let entry = select::find_one(&bib, &query, &t!("select-article"))?;
+            if url {
+                links::open_link(entry)?;
+            } else {
+                links::open_pdf(&setup, entry)?;
+            }
+        }
+        Command::Link { query, url, kind } => {
+            let setup = Setup::determine_from_cwd()?;
+            let mut bib = setup.bib()?;
+            let key = select::find_one(&bib, &query, &t!("select-article"))?
+                .key
+                .clone();
+            let entry = bib.get_mut(&key).unwrap();
+            if !links::add(entry, links::Link { kind, url }) {
+                println!("{}", t!("link-exists", key = key));
+                return Ok(());
+            }
+            let tx = transaction::Transaction::begin(&setup)?;
+            setup.write_bib(&bib)?;
+            setup.sync_git()?;
+            tx.commit();
+        }
+        Command::Relate {
+            query,
+            extended,
+            preprint,
+        } => {
+            let setup = Setup::determine_from_cwd()?;
+            let mut bib = setup.bib()?;
+            let key = select::find_one(&bib, &query, &t!("select-article"))?
+                .key
+                .clone();
+            let mut links = Vec::new();
+            for (kind, query) in [
+                (versions::Kind::Extended, extended),
+                (versions::Kind::Preprint, preprint),
+            ] {
+                if let Some(query) = query {
+                    let other = select::find_one(&bib, &query, &t!("select-version"))?;
+                    if other.key == key {
+                        return Err(eyre!(t!("relate-self")));
+                    }
+                    links.push((kind, other.key.clone()));
+                }
+            }
+            let entry = bib.get_mut(&key).unwrap();
+            for (kind, other) in links {
+                versions::link(entry, kind, &other);
+            }
+            let tx = transaction::Transaction::begin(&setup)?;
+            setup.write_bib(&bib)?;
+            setup.sync_git()?;
+            tx.commit();
+        }
+        Command::Mirrors => {
+            let mirrors = remotes::scihub::mirrors();
+            let spinner = prompt::spinner();
+            spinner.start(t!("mirrors-probing"));
+            let results = bulk::fetch(mirrors.iter().cloned(), |_| {}, remotes::scihub::probe)?;
             spinner.stop("");
-
+            let results = results
+                .into_iter()
+                .sorted_by_key(|(_, result)| result.as_ref().map_or(u128::MAX, |l| l.as_millis()));
+            for (mirror, result) in results {
+                match result {
+                    Ok(latency) => println!(
+                        "{} {mirror}  {}",
+                        "✓".green(),
+                        format!("{} ms", latency.as_millis()).dimmed()
+                    ),
+                    Err(err) => println!("{} {mirror}  {}", "✗".red(), err.to_string().dimmed()),
+                }
+            }
+        }
+        Command::Remotes { cmd } => match cmd {
+            RemotesCommand::Status { json } => health::run(json)?,
+        },
+        Command::CheckLinks { only, json } => {
+            let setup = Setup::determine_from_cwd()?;
+            let only = query::Query::parse(only.as_deref().unwrap_or_default())?;
+            let bib = setup.bib()?;
+            let entries = only.filter(&setup, &bib)?;
+            links::check_all(&entries, json)?.print(json)?;
+        }
+        Command::Blame { query } => {
+            let setup = Setup::determine_from_cwd()?;
+            let bib = setup.bib()?;
+            let entry = select::find_one(&bib, &query, &t!("select-article"))?;
+            blame::run(&setup, entry)?;
+        }
+        Command::Note { query, template } => {
+            let setup = Setup::determine_from_cwd()?;
+            let bib = setup.bib()?;
+            let entry = select::find_one(&bib, &query, &t!("select-article"))?;
+            let mut tx = transaction::Transaction::begin(&setup)?;
+            note::open(&setup, &mut tx, entry, template.as_deref())?;
+            setup.sync_git()?;
+            tx.commit();
+        }
+        Command::Share { query, qr } => {
+            let setup = Setup::determine_from_cwd()?;
+            let bib = setup.bib()?;
+            let entry = select::find_one(&bib, &query, &t!("select-article-to-share"))?;
+            share::run(entry, qr)?;
+        }
+        Command::Pick { query, dmenu, then } => {
+            let setup = Setup::determine_from_cwd()?;
+            pick::run(&setup, &query::Query::parse(&query.join(" "))?, dmenu, then)?;
+        }
+        Command::PickKey { query } => {
+            let setup = Setup::determine_from_cwd()?;
+            pick::pick_key(&setup, &query::Query::parse(&query.join(" "))?)?;
+        }
+        Command::Random { query } => {
+            let setup = Setup::determine_from_cwd()?;
+            random::run(&setup, &query::Query::parse(&query.join(" "))?)?;
+        }
+        Command::Venues { cmd } => {
+            let setup = Setup::determine_from_cwd()?;
+            match cmd {
+                VenuesCommand::Add {
+                    name,
+                    aliases,
+                    dblp,
+                    deadline,
+                } => {
+                    let mut venues = venues::Venues::load(&setup)?;
+                    venues.track(venues::Venue {
+                        name,
+                        aliases,
+                        dblp,
+                        deadline,
+                    });
+                    let tx = transaction::Transaction::begin(&setup)?;
+                    venues.save(&setup)?;
+                    setup.sync_git()?;
+                    tx.commit();
+                }
+                VenuesCommand::Rm { name } => {
+                    let mut venues = venues::Venues::load(&setup)?;
+                    venues.untrack(&name)?;
+                    let tx = transaction::Transaction::begin(&setup)?;
+                    venues.save(&setup)?;
+                    setup.sync_git()?;
+                    tx.commit();
+                }
+                VenuesCommand::List => venues::list(&setup)?,
+                VenuesCommand::Show { name } => venues::show(&setup, &name)?,
+            }
+        }
+        Command::Queue { cmd } => {
+            let setup = Setup::determine_from_cwd()?;
+            match cmd {
+                QueueCommand::Add {
+                    query,
+                    priority,
+                    due,
+                } => {
+                    let bib = setup.bib()?;
+                    let entry = select::find_one(&bib, &query, &t!("select-article-to-queue"))?;
+                    let tx = transaction::Transaction::begin(&setup)?;
+                    queue::add(&setup, entry, priority, due)?;
+                    setup.sync_git()?;
+                    tx.commit();
+                }
+                QueueCommand::Next => queue::next(&setup)?,
+                QueueCommand::Done { query } => {
+                    let bib = setup.bib()?;
+                    let entry = select::find_one(&bib, &query, &t!("select-article"))?;
+                    let tx = transaction::Transaction::begin(&setup)?;
+                    queue::done(&setup, entry)?;
+                    setup.sync_git()?;
+                    tx.commit();
+                }
+                QueueCommand::List => queue::print(&setup)?,
+            }
+        }
+        Command::Archive { query, all } => {
+            let setup = Setup::determine_from_cwd()?;
+            let bib = setup.bib()?;
+            let entries = select::find_many(&bib, &query, &t!("select-article-to-archive"), all)?;
+            let entries = entries.into_iter().cloned().collect_vec();
+            let tx = transaction::Transaction::begin(&setup)?;
+            archive::archive(&setup, &entries)?;
+            setup.sync_git()?;
+            tx.commit();
+        }
+        Command::Mark { query, status, all } => {
+            let setup = Setup::determine_from_cwd()?;
+            let bib = setup.bib()?;
+            let entries = select::find_many(&bib, &query, &t!("select-article-to-mark"), all)?;
+            let tx = transaction::Transaction::begin(&setup)?;
+            for entry in entries {
+                sidecar::mark(&setup, &entry.key, status)?;
+            }
+            setup.sync_git()?;
+            tx.commit();
+        }
+        Command::Rate { query, rating, all } => {
+            let setup = Setup::determine_from_cwd()?;
+            let bib = setup.bib()?;
+            let entries = select::find_many(&bib, &query, &t!("select-article-to-rate"), all)?;
+            let tx = transaction::Transaction::begin(&setup)?;
+            for entry in entries {
+                sidecar::rate(&setup, &entry.key, rating)?;
+            }
+            setup.sync_git()?;
+            tx.commit();
+        }
+        Command::MergeSidecar { base, ours, theirs } => {
+            group::merge_files(&base, &ours, &theirs)?;
+        }
+        Command::Tag {
+            query,
+            add,
+            remove,
+            all,
+        } => {
+            let setup = Setup::determine_from_cwd()?;
             let mut bib = setup.bib()?;
-
-            bib.insert(
-                biblatex::Bibliography::parse(&bib_entry)
-                    .map_err(|err| eyre!("failed to parse bibliography entry: {err}"))?
-                    .into_iter()
-                    .next()
-                    .unwrap(),
-            );
-
-            debug!("writing bibliography to file");
-            fs::write(setup.bib_path(), bib.to_biblatex_string())?;
-
+            let keys = select::find_many(&bib, &query, &t!("select-article-to-tag"), all)?
+                .into_iter()
+                .map(|entry| entry.key.clone())
+                .collect_vec();
+            let tx = transaction::Transaction::begin(&setup)?;
+            for key in &keys {
+                let entry = bib.get_mut(key).unwrap();
+                let mut tags = entry::tags(entry);
+                tags.retain(|tag| !remove.contains(tag));
+                for tag in &add {
+                    if !tags.contains(tag) {
+                        tags.push(tag.clone());
+                    }
+                }
+                entry::set_tags(entry, &tags);
+            }
+            setup.write_bib(&bib)?;
             setup.sync_git()?;
+            tx.commit();
         }
-        Command::Rm { force, query } => {
+        Command::Fmt {} => {
             let setup = Setup::determine_from_cwd()?;
+            let before = setup.bib()?;
+            let mut bib = before.clone();
+            let changed = title_case::normalize_all(&setup, &mut bib)?;
+            for key in &changed {
+                if let Some(entry) = bib.get(key) {
+                    println!("{key}  {}", entry::title(entry).green());
+                }
+            }
+            println!("{}", t!("titles-formatted", count = changed.len()));
+            if !changed.is_empty() {
+                backup::before_destructive(&setup, "fmt")?;
+                let mut tx = transaction::Transaction::begin(&setup)?;
+                consistency::apply(&setup, &mut tx, &before, &mut bib, &Default::default())?;
+                setup.write_bib(&bib)?;
+                setup.sync_git()?;
+                tx.commit();
+            }
+        }
+        Command::Retag => {
+            let setup = Setup::determine_from_cwd()?;
+            let rules = tag_rules::Rules::load(&setup)?;
             let mut bib = setup.bib()?;
-
-            // find possible entries
-
-            let entries = bib
+            let added = rules.apply_all(&mut bib);
+            for (key, tags) in &added {
+                let tags = tags.iter().map(|tag| format!("+{tag}")).join(" ");
+                println!("{key}  {}", tags.green());
+            }
+            println!("{}", t!("retagged", count = added.len()));
+            if !added.is_empty() {
+                let tx = transaction::Transaction::begin(&setup)?;
+                setup.write_bib(&bib)?;
+                setup.sync_git()?;
+                tx.commit();
+            }
+        }
+        Command::Refetch { query, yes } => {
+            let setup = Setup::determine_from_cwd()?;
+            refetch::run(&setup, &query, yes)?;
+        }
+        Command::Rekey { query, to, all } => {
+            let setup = Setup::determine_from_cwd()?;
+            consistency::rekey(&setup, &query, to.as_deref(), all)?;
+        }
+        Command::List {
+            filters,
+            sort,
+            group_by,
+            tree,
+        } => {
+            let setup = Setup::determine_from_cwd()?;
+            let layout = match (group_by, tree) {
+                (Some(group_by), _) => list::Layout::Groups(group_by),
+                (None, true) => list::Layout::Versions,
+                (None, false) => list::Layout::Flat,
+            };
+            list::run(&setup, &filters, sort, layout)?;
+        }
+        Command::Local { query, undo, all } => {
+            let setup = Setup::determine_from_cwd()?;
+            let mut bib = setup.bib()?;
+            let keys = select::find_many(&bib, &query, &t!("select-article-to-localize"), all)?
                 .iter()
-                .filter(|entry| {
-                    entry.doi().map(|doi| doi == query).unwrap_or_default()
-                        || entry
-                            .title()
-                            .map(|title| {
-                                title
-                                    .to_biblatex_string(false)
-                                    .to_lowercase()
-                                    .contains(&query.to_lowercase())
-                            })
-                            .unwrap_or_default()
-                })
+                .map(|entry| entry.key.clone())
                 .collect_vec();
-
-            if entries.is_empty() {
-                return Err(eyre!("No entry found with DOI or title: {}", query));
-            }
-
-            let selection = cliclack::select("Select article to remove")
-                .items(
-                    &entries
-                        .iter()
-                        .map(|entry| {
-                            (
-                                entry,
-                                format!(
-                                    "{} ({})",
-                                    entry
-                                        .title()
-                                        .unwrap_or_default()
-                                        .to_biblatex_string(true)
-                                        .bold(),
-                                    entry
-                                        .author()
-                                        .unwrap_or_default()
-                                        .into_iter()
-                                        .map(|a| a.to_string())
-                                        .format(", ")
-                                ),
-                                if let Ok(doi) = entry.doi() {
-                                    format!("DOI: {}", doi)
-                                } else {
-                                    "".to_string()
-                                },
-                            )
-                        })
-                        .collect_vec(),
-                )
-                .interact()?;
-
-            let title = selection
-                .title()
-                .unwrap_or_default()
-                .to_biblatex_string(true);
-            if force || cliclack::confirm(format!("Remove {}?", title)).interact()? {
-                let key = selection.key.clone();
-                let removed = bib.remove(&key);
-                if removed.is_none() {
-                    return Err(eyre!("Failed to remove entry"));
+            for key in &keys {
+                if let Some(entry) = bib.get_mut(key) {
+                    local::set(entry, !undo);
                 }
-                fs::write(setup.bib_path(), bib.to_biblatex_string())?;
+            }
+            let tx = transaction::Transaction::begin(&setup)?;
+            setup.write_bib(&bib)?;
+            if !undo {
+                local::untrack(&setup, &keys.iter().map(String::as_str).collect_vec())?;
+            }
+            setup.sync_git()?;
+            tx.commit();
+            if undo {
+                println!("{}", t!("shared", count = keys.len()));
+            } else {
+                println!("{}", t!("localized", count = keys.len()));
+            }
+        }
+        Command::DetectLanguage {} => {
+            let setup = Setup::determine_from_cwd()?;
+            let mut bib = setup.bib()?;
+            let count = language::fill_all(&mut bib);
+            if count > 0 {
+                let tx = transaction::Transaction::begin(&setup)?;
+                setup.write_bib(&bib)?;
                 setup.sync_git()?;
+                tx.commit();
             }
+            println!("{}", t!("languages-detected", count = count));
         }
-        Command::List {} => {
+        Command::Stats { growth, format } => {
             let setup = Setup::determine_from_cwd()?;
-            let bib = setup.bib()?;
-            for entry in bib {
-                let title = entry.title().unwrap_or_default().to_biblatex_string(true);
-                let authors = entry
-                    .author()
-                    .unwrap_or_default()
-                    .into_iter()
-                    .map(|a| a.to_string())
-                    .format(", ");
-                let doi = entry.doi().unwrap_or_default();
-                println!("{} ({})\n  {}", title.bold(), doi, authors.italic());
+            if growth {
+                stats::growth(&setup, format)?;
+            } else {
+                stats::run(&setup)?;
             }
         }
-        Command::Pdfs {} => {
+        Command::Which {} => {
+            let setup = Setup::determine_from_cwd()?;
+            which::run(&setup)?;
+        }
+        Command::Altmetrics { only, force } => {
             let setup = Setup::determine_from_cwd()?;
+            let only = query::Query::parse(only.as_deref().unwrap_or_default())?;
             let bib = setup.bib()?;
-            for entry in bib {
-                let doi = match entry.doi() {
-                    Ok(doi) => doi,
-                    Err(err) => {
-                        let title = entry.title().unwrap_or_default().to_biblatex_string(true);
-                        warn!(title=%title, %err, "failed to extract DOI");
-                        continue;
-                    }
-                };
-                let path = setup.pdf_dir().join(format!("{}.pdf", path_safe_doi(&doi)));
-
-                if path.exists() {
-                    debug!(%path, "skipping PDF, already exists");
-                    continue;
+            let entries = only.filter(&setup, &bib)?;
+            let tx = transaction::Transaction::begin(&setup)?;
+            altmetrics::refresh(&setup, &entries, force)?;
+            setup.sync_git()?;
+            tx.commit();
+        }
+        Command::Doctor { repair } => {
+            let setup = Setup::determine_from_cwd()?;
+            doctor::run(&setup, repair)?;
+        }
+        Command::CheckUpdates { yes } => {
+            let setup = Setup::determine_from_cwd()?;
+            updates::run(&setup, yes)?;
+        }
+        Command::Pdfs { status, only, json } => {
+            let setup = Setup::determine_from_cwd()?;
+            let only = query::Query::parse(only.as_deref().unwrap_or_default())?;
+            if status {
+                pdfs::print_status(&setup, &only)?;
+            } else {
+                pdfs::download_all(&setup, &only)?.print(json)?;
+            }
+        }
+        Command::Bundle { cmd } => match cmd {
+            BundleCommand::Export { out, pdfs } => {
+                let setup = Setup::determine_from_cwd()?;
+                bundle::export(&setup, &out, pdfs)?;
+            }
+            BundleCommand::Import { file, json } => {
+                let setup = Setup::determine_from_cwd()?;
+                backup::before_destructive(&setup, "bundle import")?;
+                let tx = transaction::Transaction::begin(&setup)?;
+                let report = bundle::import(&setup, &file)?;
+                setup.sync_git()?;
+                tx.commit();
+                report.summary().print(json)?;
+            }
+        },
+        Command::Backup { list } => {
+            let setup = Setup::determine_from_cwd()?;
+            let config = setup.config()?.backup;
+            if list {
+                for snapshot in backup::list(&setup, &config)? {
+                    println!("{snapshot}");
                 }
-
-                let pdf = if is_arxiv(&doi) {
-                    match remotes::arxiv::fetch_pdf(&doi) {
-                        Ok(pdf) => pdf,
-                        Err(err) => {
-                            let title = entry.title().unwrap_or_default().to_biblatex_string(true);
-                            warn!(title=%title, %doi, %err, "failed to download PDF");
-                            continue;
-                        }
-                    }
-                } else {
-                    match remotes::scihub::fetch_pdf(&doi) {
-                        Ok(pdf) => pdf,
-                        Err(err) => {
-                            let title = entry.title().unwrap_or_default().to_biblatex_string(true);
-                            warn!(title=%title, %doi, %err, "failed to download PDF");
-                            continue;
-                        }
-                    }
-                };
-                fs::create_dir_all(setup.pdf_dir())?;
-                debug!(path=%path, "writing PDF to file");
-                fs::write(&path, pdf)?;
-                info!(path=%path, "downloaded PDF");
+            } else {
+                let path = backup::snapshot(&setup, &config)?;
+                println!("{path}");
             }
         }
+        Command::Migrate {} => {
+            let setup = Setup::determine_from_cwd()?;
+            let dry_run = dry_run::enabled();
+            let mut tx = transaction::Transaction::begin(&setup)?;
+            let renames = pdfs::migrate(&setup, &mut tx, dry_run)?;
+            for rename in &renames {
+                println!("{} -> {}", rename.old, rename.new);
+            }
+            if renames.is_empty() {
+                println!("{}", t!("pdfs-naming-ok"));
+            } else if !dry_run {
+                setup.sync_git()?;
+            }
+            tx.commit();
+        }
+        Command::Export {
+            out,
+            at,
+            anonymize,
+            since_last,
+        } => {
+            let setup = Setup::determine_from_cwd()?;
+            export::run(&setup, at.as_deref(), out.as_deref(), anonymize, since_last)?;
+        }
+        Command::Latexmk { aux, out } => {
+            let setup = Setup::determine_from_cwd()?;
+            latex::latexmk(&setup, &aux, &out)?;
+        }
+        Command::Typst { dir, out } => {
+            let setup = Setup::determine_from_cwd()?;
+            typst::run(&setup, &dir, &out)?;
+        }
+        Command::Restore { from, notes, yes } => {
+            let setup = Setup::determine_from_cwd()?;
+            restore::run(&setup, &from, notes, yes)?;
+        }
     }
 
     Ok(())
 }
 
-fn path_safe_doi(doi: &str) -> String {
-    doi.replace("/", "--")
-}
-
 mod config {
-    use std::fs;
-
     use crate::Result;
 
+    use std::sync::{
+        OnceLock,
+        atomic::{AtomicBool, Ordering},
+    };
+
     use camino::{Utf8Path, Utf8PathBuf};
-    use color_eyre::eyre::eyre;
+    use color_eyre::{eyre::eyre, owo_colors::OwoColorize};
     use duct::cmd;
     use serde::{Deserialize, Serialize};
     use tracing::{debug, info, warn};
@@ -363,6 +1276,7 @@ mod config {
             } else {
                 global_config_dir()?
             };
+            crate::http::set_cache_dir(&config_base);
             // check if the directory is a git repository
             let git = if cmd!("git", "rev-parse", "--is-inside-work-tree")
                 .dir(&config_base)
@@ -396,19 +1310,37 @@ mod config {
         }
 
         pub fn determine_from(path: &Utf8Path) -> Result<Self> {
-            // walk up the directory tree until we find a zime.toml file
+            if let Some(root) = ROOT.get() {
+                let local = root.join(".zime");
+                let root = if local.is_dir() { local } else { root.clone() };
+                debug!(root=%root, "using library given with --root");
+                return Self::new(None, Some(root));
+            }
+            let walk_up = Config::load_global()?.walk_up;
+            // walk up the directory tree until we find a .zime directory
             let mut current = path;
             loop {
+                if walk_up == WalkUp::Never {
+                    break;
+                }
                 let config_dir = current.join(".zime");
                 if config_dir.exists() {
                     debug!(config_dir=%config_dir, "found config dir");
+                    crate::submodule::init_if_empty(&config_dir)?;
                     return Self::new(None, Some(config_dir));
                 }
-                if let Some(parent) = current.parent() {
-                    current = parent;
-                } else {
+                if walk_up == WalkUp::Git && current.join(".git").exists() {
+                    debug!(repository=%current, "stopping at git repository root");
                     break;
                 }
+                let Some(parent) = current.parent() else {
+                    break;
+                };
+                if walk_up == WalkUp::Filesystem && !same_filesystem(current, parent) {
+                    debug!(dir=%current, "stopping at filesystem boundary");
+                    break;
+                }
+                current = parent;
             }
             // use global config directory
             debug!("using global config directory");
@@ -419,6 +1351,16 @@ mod config {
             self.config_base.clone()
         }
 
+        /// Whether this is the library in the global config directory, rather
+        /// than a local `.zime` directory.
+        pub fn is_global(&self) -> bool {
+            global_config_dir().is_ok_and(|dir| dir == self.config_base)
+        }
+
+        pub fn archive_path(&self) -> Utf8PathBuf {
+            self.config_base.join("archive.bib")
+        }
+
         pub fn config_file(&self) -> Utf8PathBuf {
             self.config_base.join("zime.toml")
         }
@@ -431,12 +1373,137 @@ mod config {
             self.config_base.join("pdfs")
         }
 
+        /// Where the PDF of the entry with the given DOI is stored.
+        pub fn pdf_path(&self, doi: &str) -> Utf8PathBuf {
+            self.pdf_dir()
+                .join(format!("{}.pdf", crate::doi::to_file_stem(doi)))
+        }
+
+        pub fn notes_dir(&self) -> Utf8PathBuf {
+            self.config_base.join("notes")
+        }
+
+        pub fn attachments_dir(&self) -> Utf8PathBuf {
+            self.config_base.join("attachments")
+        }
+
+        pub fn meta_dir(&self) -> Utf8PathBuf {
+            self.config_base.join("meta")
+        }
+
+        pub fn templates_dir(&self) -> Utf8PathBuf {
+            self.config_base.join("templates")
+        }
+
+        /// Loads the library config, falling back to the defaults if there is
+        /// no config file.
+        pub fn config(&self) -> Result<Config> {
+            if self.config_file().exists() {
+                Config::load(&self.config_file())
+            } else {
+                Ok(Config::default())
+            }
+        }
+
         pub fn git(&self) -> Option<&str> {
             self.git.as_deref()
         }
 
+        /// Where the time of the last sync is kept, inside the git directory
+        /// so that it is not committed.
+        fn last_sync_path(&self) -> Result<Utf8PathBuf> {
+            let git_dir = cmd!("git", "rev-parse", "--absolute-git-dir")
+                .dir(self.root())
+                .read()?;
+            Ok(Utf8PathBuf::from(git_dir).join("zime-last-sync"))
+        }
+
+        /// Whether the library was synced less than `sync_interval` minutes
+        /// ago, and the sync was not forced.
+        fn sync_throttled(&self) -> Result<bool> {
+            let Some(minutes) = self.config()?.sync_interval else {
+                return Ok(false);
+            };
+            if FORCE_SYNC.load(Ordering::Relaxed) {
+                return Ok(false);
+            }
+            let last = std::fs::read_to_string(self.last_sync_path()?)
+                .ok()
+                .and_then(|last| last.trim().parse::<jiff::Timestamp>().ok());
+            Ok(last.is_some_and(|last| {
+                jiff::Timestamp::now().duration_since(last)
+                    < jiff::SignedDuration::from_mins(minutes as i64)
+            }))
+        }
+
+        fn record_sync(&self) -> Result<()> {
+            std::fs::write(self.last_sync_path()?, jiff::Timestamp::now().to_string())?;
+            Ok(())
+        }
+
+        /// The command that made the changes, which `zime blame` shows, and
+        /// the entries it changed, so that a single commit of a bulk command
+        /// still says what happened.
+        ///
+        /// Only the subcommands and flag names are kept from the command line,
+        /// as queries and values may name local-only entries.
+        fn commit_message(&self) -> Result<String> {
+            use clap::CommandFactory;
+
+            let mut command = crate::Cli::command();
+            let mut args = Vec::new();
+            for arg in std::env::args().skip(1) {
+                if arg.starts_with('-') {
+                    let flag = arg.split_once('=').map_or(arg.as_str(), |(flag, _)| flag);
+                    args.push(flag.to_string());
+                } else if let Some(sub) = command.find_subcommand(&arg).cloned() {
+                    args.push(sub.get_name().to_string());
+                    command = sub;
+                }
+            }
+            let mut message = format!("zime: {}", args.join(" "));
+            let committed = cmd!("git", "show", "HEAD:./references.bib")
+                .dir(self.root())
+                .stderr_null()
+                .read()
+                .unwrap_or_default();
+            let mut old = biblatex::Bibliography::parse(&committed).unwrap_or_default();
+            // Local-only entries are not committed, and must not be named in
+            // the commit either, including those that just became local-only
+            let (new, local) = crate::local::split(self, &self.bib()?)?;
+            for entry in local.iter() {
+                old.remove(&entry.key);
+            }
+            let changes = crate::diff::diff(&old, &new);
+            if changes.len() > 1 {
+                message.push_str(&format!(" ({} entries)", changes.len()));
+            }
+            if !changes.is_empty() {
+                message.push_str("\n\n");
+                message.push_str(&crate::diff::manifest(&changes));
+            }
+            Ok(message)
+        }
+
         pub fn sync_git(&self) -> Result<()> {
+            if crate::dry_run::enabled() {
+                if self.git().is_some() {
+                    println!("{}", crate::i18n::t!("dry-run-sync").dimmed());
+                }
+                return Ok(());
+            }
             if let Some(_git) = self.git() {
+                if self.sync_throttled()? {
+                    info!("synced recently, skipping sync");
+                    return Ok(());
+                }
+                let superproject = crate::submodule::superproject(&self.root());
+                if superproject.is_some() {
+                    crate::submodule::attach_head(&self.root())?;
+                }
+                if self.config()?.reading_group {
+                    crate::group::install_merge_driver(self)?;
+                }
                 // check for changes
                 let status = duct::cmd!("git", "status", "--porcelain")
                     .dir(self.root())
@@ -445,44 +1512,199 @@ mod config {
                 if !status.is_empty() {
                     info!("committing changes");
                     duct::cmd!("git", "add", ".").dir(self.root()).run()?;
-                    duct::cmd!("git", "commit", "-m", "zime: auto commit")
+                    duct::cmd!("git", "commit", "-m", self.commit_message()?)
                         .dir(self.root())
                         .run()?;
                 }
 
-                // pull from upstream
-                duct::cmd!("git", "pull", "origin", "main", "--rebase")
-                    .dir(self.root())
-                    .run()?;
+                // pull from upstream, unless the remote is still empty
+                let has_upstream = duct::cmd!(
+                    "git",
+                    "ls-remote",
+                    "--exit-code",
+                    "--heads",
+                    "origin",
+                    "main"
+                )
+                .dir(self.root())
+                .stdout_null()
+                .run()
+                .is_ok();
+                if has_upstream {
+                    let pulled = duct::cmd!("git", "pull", "origin", "main", "--rebase")
+                        .dir(self.root())
+                        .run();
+                    if let Err(err) = pulled {
+                        crate::notify::send(
+                            &crate::i18n::t!("notify-sync-failed"),
+                            &err.to_string(),
+                        );
+                        return Err(err.into());
+                    }
+                } else {
+                    debug!("remote has no main branch yet, skipping pull");
+                }
 
                 // push changes
-                if !status.is_empty() {
+                if !status.is_empty() || !has_upstream {
                     duct::cmd!("git", "push", "origin", "main")
                         .dir(self.root())
                         .run()?;
                 }
+                if let Some(parent) = &superproject {
+                    crate::submodule::commit_in_superproject(&self.root(), parent)?;
+                }
+                self.record_sync()?;
             }
             Ok(())
         }
 
         pub fn bib(&self) -> Result<biblatex::Bibliography> {
-            if !self.bib_path().exists() {
-                fs::write(&self.bib_path(), "")?;
+            crate::store::load(self)
+        }
+
+        pub fn write_bib(&self, bib: &biblatex::Bibliography) -> Result<()> {
+            if crate::dry_run::enabled() {
+                let old = self.bib()?;
+                crate::diff::print(&crate::diff::diff(&old, bib));
+                crate::dry_run::would_write(&self.bib_path());
+                return Ok(());
+            }
+            if self.config()?.confirm_writes {
+                let old = self.bib()?;
+                let changes = crate::diff::diff(&old, bib);
+                if !changes.is_empty() {
+                    crate::diff::print(&changes);
+                    if !crate::prompt::confirm(crate::i18n::t!("confirm-write"))? {
+                        return Err(eyre!(crate::i18n::t!("write-declined")));
+                    }
+                }
             }
-            let src = fs::read_to_string(&self.bib_path())?;
-            let bib = biblatex::Bibliography::parse(&src)
-                .map_err(|err| eyre!("failed to parse {}: {err}", self.bib_path()))?;
-            Ok(bib)
+            crate::store::save(self, bib)
         }
     }
 
+    static ROOT: OnceLock<Utf8PathBuf> = OnceLock::new();
+
+    /// Uses the library at `root` instead of looking for one.
+    pub fn set_root(root: Utf8PathBuf) {
+        let _ = ROOT.set(root);
+    }
+
+    static FORCE_SYNC: AtomicBool = AtomicBool::new(false);
+
+    /// Syncs regardless of `sync_interval`.
+    pub fn force_sync() {
+        FORCE_SYNC.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the library was given with `--root`.
+    pub fn has_explicit_root() -> bool {
+        ROOT.get().is_some()
+    }
+
+    /// How far up from the current directory to look for a `.zime` directory
+    /// before falling back to the global library.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize, Serialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum WalkUp {
+        /// Up to the root of the filesystem
+        #[default]
+        Always,
+        /// Up to the root of the enclosing git repository
+        Git,
+        /// Up to the root of the filesystem the current directory is on
+        Filesystem,
+        /// Always use the global library
+        Never,
+    }
+
+    #[cfg(unix)]
+    fn same_filesystem(a: &Utf8Path, b: &Utf8Path) -> bool {
+        use std::os::unix::fs::MetadataExt;
+        match (std::fs::metadata(a), std::fs::metadata(b)) {
+            (Ok(a), Ok(b)) => a.dev() == b.dev(),
+            _ => false,
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn same_filesystem(_: &Utf8Path, _: &Utf8Path) -> bool {
+        true
+    }
+
     #[derive(Debug, Deserialize, Serialize)]
-    pub struct Config {}
+    #[serde(default)]
+    pub struct Config {
+        /// Language of messages, e.g. `da`, defaults to the system language
+        pub language: Option<String>,
+        /// Show the changes and ask before every write of the bibliography
+        pub confirm_writes: bool,
+        /// Where to stop looking for a local library, only read from the
+        /// global config
+        pub walk_up: WalkUp,
+        /// Minutes after a sync during which further syncs are skipped
+        pub sync_interval: Option<u64>,
+        /// Fetch works from their publisher where zime can, e.g. the ACM
+        /// Digital Library for ACM DOIs, instead of Crossref or DBLP
+        pub prefer_publisher: bool,
+        /// Keep read status, ratings and notes per member, named by `git
+        /// config user.name`, see [`crate::group`]
+        pub reading_group: bool,
+        pub forge: crate::forge::ForgeConfig,
+        pub http: crate::http::HttpConfig,
+        /// Institutional proxy for PDFs, see [`crate::remotes::proxy`]
+        pub proxy: crate::remotes::proxy::ProxyConfig,
+        /// GROBID server for `add --pdf`, see [`crate::remotes::grobid`]
+        pub grobid: crate::remotes::grobid::GrobidConfig,
+        pub backup: crate::backup::BackupConfig,
+        pub notes: crate::note::NotesConfig,
+        pub notifications: crate::notify::NotifyConfig,
+        pub store: crate::store::StoreConfig,
+        /// `@string` abbreviations
+        pub strings: crate::macros::StringsConfig,
+        pub translation: crate::language::TranslationConfig,
+        /// Venue ranking datasets shown in `list` and `stats`
+        pub rankings: Vec<crate::rankings::RankingConfig>,
+        /// Tags added to entries matching a query, see [`crate::tag_rules`]
+        pub tag_rules: Vec<crate::tag_rules::TagRule>,
+        /// Entries with these tags are never committed, see [`crate::local`]
+        pub local_tags: Vec<String>,
+        /// Casing of titles, see [`crate::title_case`]
+        pub title_case: crate::title_case::TitleCaseConfig,
+        /// How to ask questions, see [`crate::prompt`]
+        pub prompt: crate::prompt::PromptConfig,
+        /// Commands run on downloaded PDFs, see [`crate::pdf_steps`]
+        pub pdf_steps: Vec<crate::pdf_steps::Step>,
+    }
 
     #[allow(clippy::derivable_impls)]
     impl Default for Config {
         fn default() -> Self {
-            Self {}
+            Self {
+                language: None,
+                confirm_writes: false,
+                walk_up: Default::default(),
+                sync_interval: None,
+                prefer_publisher: false,
+                reading_group: false,
+                forge: Default::default(),
+                http: Default::default(),
+                proxy: Default::default(),
+                grobid: Default::default(),
+                backup: Default::default(),
+                notes: Default::default(),
+                notifications: Default::default(),
+                store: Default::default(),
+                strings: Default::default(),
+                translation: Default::default(),
+                rankings: Default::default(),
+                tag_rules: Default::default(),
+                local_tags: Default::default(),
+                title_case: Default::default(),
+                prompt: Default::default(),
+                pdf_steps: Default::default(),
+            }
         }
     }
 
@@ -491,6 +1713,16 @@ mod config {
             let content = std::fs::read_to_string(path)?;
             toml::from_str(&content).map_err(|e| eyre!(e))
         }
+        /// Loads the config from the global config directory, falling back to
+        /// the defaults if it has not been created.
+        pub fn load_global() -> Result<Self> {
+            let path = global_config_file()?;
+            if path.exists() {
+                Self::load(&path)
+            } else {
+                Ok(Self::default())
+            }
+        }
         pub fn write(&self, path: &Utf8Path) -> Result<()> {
             let content = toml::to_string(self)?;
             std::fs::write(path, content)?;
@@ -498,10 +1730,23 @@ mod config {
         }
     }
 
+    fn project_dirs() -> Result<directories::ProjectDirs> {
+        directories::ProjectDirs::from("com", "zime", "zime")
+            .ok_or_else(|| eyre!("Could not determine configuration directory"))
+    }
+
     fn global_config_dir() -> Result<Utf8PathBuf> {
-        let dirs = directories::ProjectDirs::from("com", "zime", "zime")
-            .ok_or_else(|| eyre!("Could not determine configuration directory"))?;
-        Utf8PathBuf::from_path_buf(dirs.config_dir().to_path_buf())
+        Utf8PathBuf::from_path_buf(project_dirs()?.config_dir().to_path_buf())
             .map_err(|_| eyre!("Config path is not valid UTF-8"))
     }
+
+    pub fn global_config_file() -> Result<Utf8PathBuf> {
+        Ok(global_config_dir()?.join("zime.toml"))
+    }
+
+    /// Where data that can be fetched again is kept, shared by all libraries.
+    pub fn cache_dir() -> Result<Utf8PathBuf> {
+        Utf8PathBuf::from_path_buf(project_dirs()?.cache_dir().to_path_buf())
+            .map_err(|_| eyre!("Cache path is not valid UTF-8"))
+    }
 }
@@ -0,0 +1,206 @@
+//! Finding titles and abstracts whose math or special characters a remote
+//! mangled, e.g. MathML left in by Crossref, `$` escaped into text or TeX
+//! commands that lost their math delimiters.
+//!
+//! `zime doctor` lists them, and with `--repair` fetches the entry from other
+//! remotes that know its DOI and takes the fields from the first one whose
+//! version is clean.
+
+use biblatex::{Chunk, Entry, Spanned};
+use tracing::debug;
+
+use crate::{
+    provenance::{self, Provenance},
+    refetch,
+};
+
+/// The fields checked, which remotes most often mangle.
+pub const FIELDS: &[&str] = &["title", "abstract"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Problem {
+    /// HTML or MathML tags, e.g. `<mml:math>` or `<sub>`
+    Markup,
+    /// A `$` kept as text, where the math it delimited was escaped
+    EscapedDollar,
+    /// A TeX math command such as `\alpha` outside of math
+    BareMath,
+    /// Backslashes doubled by escaping them twice
+    DoubledBackslash,
+    /// Braces that do not match up
+    UnbalancedBraces,
+    /// UTF-8 decoded as Latin-1, e.g. `Ã©` for `é`, or lost characters
+    Mojibake,
+}
+
+impl Problem {
+    pub fn describe(self) -> &'static str {
+        match self {
+            Problem::Markup => "HTML or MathML tags",
+            Problem::EscapedDollar => "escaped $",
+            Problem::BareMath => "math command outside math",
+            Problem::DoubledBackslash => "doubled backslashes",
+            Problem::UnbalancedBraces => "unbalanced braces",
+            Problem::Mojibake => "garbled characters",
+        }
+    }
+}
+
+/// TeX commands only valid in math mode.
+const MATH_COMMANDS: &[&str] = &[
+    "alpha",
+    "beta",
+    "gamma",
+    "delta",
+    "epsilon",
+    "lambda",
+    "mu",
+    "pi",
+    "sigma",
+    "omega",
+    "mathcal",
+    "mathbb",
+    "mathbf",
+    "mathrm",
+    "frac",
+    "sqrt",
+    "leq",
+    "geq",
+    "neq",
+    "sum",
+    "prod",
+    "infty",
+    "subseteq",
+    "rightarrow",
+    "to",
+    "times",
+    "cdot",
+    "ldots",
+    "log",
+];
+
+const TAGS: &[&str] = &[
+    "<mml:",
+    "<math",
+    "<inline-formula",
+    "<tex-math",
+    "<sub>",
+    "<sup>",
+    "<i>",
+    "<b>",
+    "<jats:",
+    "<scp>",
+    "</",
+];
+
+const MOJIBAKE: &[&str] = &["Ã©", "Ã¨", "Ã¶", "Ã¼", "Ã¤", "â€", "Â ", "\u{fffd}"];
+
+/// The problems in a field's value, each reported once.
+pub fn check_chunks(chunks: &[Spanned<Chunk>]) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut report = |problem| {
+        if !problems.contains(&problem) {
+            problems.push(problem);
+        }
+    };
+    for chunk in chunks {
+        let text = chunk.v.get();
+        if text.contains(r"\\") {
+            report(Problem::DoubledBackslash);
+        }
+        if MOJIBAKE.iter().any(|m| text.contains(m)) {
+            report(Problem::Mojibake);
+        }
+        if let Chunk::Math(_) = chunk.v {
+            continue;
+        }
+        let lower = text.to_lowercase();
+        if TAGS.iter().any(|tag| lower.contains(tag)) {
+            report(Problem::Markup);
+        }
+        if let Chunk::Normal(text) = &chunk.v {
+            if text.contains('$') {
+                report(Problem::EscapedDollar);
+            }
+        }
+        let bare_math = text.split('\\').skip(1).any(|after| {
+            let command: String = after
+                .chars()
+                .take_while(char::is_ascii_alphabetic)
+                .collect();
+            MATH_COMMANDS.contains(&command.as_str())
+        });
+        if bare_math {
+            report(Problem::BareMath);
+        }
+        let mut depth = 0i32;
+        for c in text.chars() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                _ => {}
+            }
+            if depth < 0 {
+                break;
+            }
+        }
+        if depth != 0 {
+            report(Problem::UnbalancedBraces);
+        }
+    }
+    problems
+}
+
+/// The problems in the checked fields of `entry`, by field.
+pub fn check(entry: &Entry) -> Vec<(&'static str, Vec<Problem>)> {
+    FIELDS
+        .iter()
+        .filter_map(|&field| {
+            let problems = check_chunks(entry.get(field)?);
+            (!problems.is_empty()).then_some((field, problems))
+        })
+        .collect()
+}
+
+/// Remotes that fetch entries by DOI, with the id they expect.
+fn alternatives(doi: &str) -> [Provenance; 3] {
+    [
+        Provenance::new("crossref", doi),
+        Provenance::new("openalex", &format!("doi:{doi}")),
+        Provenance::new("doi", doi),
+    ]
+}
+
+/// Replaces the mangled fields of `entry` with those of another remote, if
+/// one has them clean. Returns the name of the remote used.
+pub fn repair(entry: &mut Entry) -> Option<String> {
+    let doi = entry.doi().ok()?;
+    let broken: Vec<&str> = check(entry).into_iter().map(|(field, _)| field).collect();
+    let source = provenance::get(entry).map(|p| p.source);
+    for alternative in alternatives(&doi) {
+        if source.as_deref() == Some(&alternative.source) {
+            continue;
+        }
+        let remote = match refetch::fetch_record(&alternative) {
+            Ok(remote) => remote,
+            Err(err) => {
+                debug!(key=%entry.key, source=%alternative.source, %err, "failed to fetch");
+                continue;
+            }
+        };
+        let clean = broken.iter().all(|&field| {
+            remote
+                .get(field)
+                .is_some_and(|chunks| check_chunks(chunks).is_empty())
+        });
+        if clean {
+            for &field in &broken {
+                if let Some(value) = remote.get(field) {
+                    entry.set(field, value.to_vec());
+                }
+            }
+            return Some(alternative.source);
+        }
+    }
+    None
+}
@@ -0,0 +1,118 @@
+//! Merging of incoming entries into an existing bibliography.
+//!
+//! Entries are considered duplicates if they share a DOI or a normalized title.
+//! Entries whose key collides with a different work are renamed by appending a
+//! letter suffix, as DBLP does for authors with several papers in a year.
+
+use biblatex::{Bibliography, ChunksExt, Entry};
+use tracing::{debug, info};
+
+use crate::{doi, summary::Summary};
+
+#[derive(Debug, Default)]
+pub struct MergeReport {
+    /// Keys of entries that were added unchanged
+    pub added: Vec<String>,
+    /// Entries that were added under a new key, as `(old, new)`
+    pub renamed: Vec<(String, String)>,
+    /// Incoming keys skipped because the work is already present, with the
+    /// key of the existing entry
+    pub duplicates: Vec<(String, String)>,
+}
+
+impl MergeReport {
+    /// Maps an incoming key to the key it ended up under, if it was added.
+    pub fn new_key<'a>(&'a self, key: &'a str) -> Option<&'a str> {
+        if self.added.iter().any(|k| k == key) {
+            Some(key)
+        } else {
+            self.renamed
+                .iter()
+                .find(|(old, _)| old == key)
+                .map(|(_, new)| new.as_str())
+        }
+    }
+
+    pub fn summary(&self) -> Summary {
+        let mut summary = Summary::default();
+        for key in &self.added {
+            summary.succeed(key);
+        }
+        for (_, new) in &self.renamed {
+            summary.succeed(new);
+        }
+        for (key, _) in &self.duplicates {
+            summary.skip(key, "duplicate");
+        }
+        summary
+    }
+}
+
+pub fn merge(bib: &mut Bibliography, incoming: impl IntoIterator<Item = Entry>) -> MergeReport {
+    let mut report = MergeReport::default();
+
+    for mut entry in incoming {
+        if let Some(existing) = find_duplicate(bib, &entry) {
+            debug!(key=%entry.key, %existing, "skipping duplicate entry");
+            report.duplicates.push((entry.key, existing));
+            continue;
+        }
+
+        if bib.get(&entry.key).is_some() {
+            let old = entry.key.clone();
+            entry.key = free_key(bib, &old);
+            debug!(%old, new=%entry.key, "renaming entry with colliding key");
+            report.renamed.push((old, entry.key.clone()));
+        } else {
+            report.added.push(entry.key.clone());
+        }
+        bib.insert(entry);
+    }
+
+    info!(
+        added = report.added.len(),
+        renamed = report.renamed.len(),
+        duplicates = report.duplicates.len(),
+        "merged entries"
+    );
+    report
+}
+
+/// Finds an entry in `bib` describing the same work as `entry`.
+pub fn find_duplicate(bib: &Bibliography, entry: &Entry) -> Option<String> {
+    let doi = entry.doi().ok().map(|doi| doi::normalize(&doi));
+    let title = normalized_title(entry);
+    bib.iter()
+        .find(|other| {
+            if let (Some(doi), Ok(other_doi)) = (&doi, other.doi()) {
+                return *doi == doi::normalize(&other_doi);
+            }
+            title.is_some() && title == normalized_title(other)
+        })
+        .map(|other| other.key.clone())
+}
+
+fn normalized_title(entry: &Entry) -> Option<String> {
+    let normalized = normalize_title(&entry.title().ok()?.format_verbatim());
+    (!normalized.is_empty()).then_some(normalized)
+}
+
+/// A title reduced to its lowercase letters and digits, to compare titles
+/// regardless of case, punctuation and braces.
+pub fn normalize_title(title: &str) -> String {
+    title
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// `key` with the first suffix that makes it unused in `bib`, e.g.
+/// `reynolds2002a`.
+pub fn free_key(bib: &Bibliography, key: &str) -> String {
+    ('a'..='z')
+        .map(|suffix| format!("{key}{suffix}"))
+        .chain((2..).map(|n| format!("{key}-{n}")))
+        .find(|candidate| bib.get(candidate).is_none())
+        .unwrap()
+}
@@ -0,0 +1,53 @@
+//! Free-form user metadata kept in `meta/<key>.yaml`.
+//!
+//! The files are optional and zime does not interpret their content beyond
+//! showing it, making it searchable, and exposing it to note templates.
+
+use std::fs;
+
+use camino::Utf8PathBuf;
+use color_eyre::eyre::eyre;
+use serde_yaml::{Mapping, Value};
+
+use crate::{Result, config::Setup};
+
+pub fn path(setup: &Setup, key: &str) -> Utf8PathBuf {
+    setup.meta_dir().join(format!("{key}.yaml"))
+}
+
+/// Loads the metadata of an entry, if it has any.
+pub fn load(setup: &Setup, key: &str) -> Result<Option<Mapping>> {
+    let path = path(setup, key);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let value: Value = serde_yaml::from_str(&fs::read_to_string(&path)?)
+        .map_err(|err| eyre!("failed to parse {path}: {err}"))?;
+    match value {
+        Value::Mapping(mapping) => Ok(Some(mapping)),
+        Value::Null => Ok(None),
+        _ => Err(eyre!("{path} must contain a mapping at the top level")),
+    }
+}
+
+/// Renders a value on a single line, as it should be displayed.
+pub fn display(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => s.clone(),
+        Value::Sequence(items) => items.iter().map(display).collect::<Vec<_>>().join(", "),
+        Value::Mapping(mapping) => mapping
+            .iter()
+            .map(|(k, v)| format!("{}: {}", display(k), display(v)))
+            .collect::<Vec<_>>()
+            .join(", "),
+        Value::Tagged(tagged) => display(&tagged.value),
+    }
+}
+
+/// All keys and values of the metadata as text, for searching.
+pub fn searchable_text(mapping: &Mapping) -> String {
+    display(&Value::Mapping(mapping.clone()))
+}
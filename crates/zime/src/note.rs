@@ -0,0 +1,177 @@
+//! Notes on entries, kept in `notes/<key>.<ext>`, or in `notes/<member>/` for
+//! each member of a reading group.
+//!
+//! New notes are created from a template in which `{name}` placeholders are
+//! replaced with the entry's `title`, `authors`, `year`, `venue`, `doi`,
+//! `abstract`, `tags` and `key`, and `{meta.<name>}` with values from its
+//! metadata file. Unknown placeholders are left as they are.
+//!
+//! Templates are looked up in the library's `templates/` directory first
+//! (`templates/<name>.<ext>`, with `note` as the default name), then in the
+//! config, falling back to a minimal built-in template.
+
+use std::fs;
+
+use biblatex::{ChunksExt, Entry};
+use camino::Utf8PathBuf;
+use color_eyre::eyre::{bail, eyre};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use crate::{Result, config::Setup, dry_run, entry, group, meta, transaction::Transaction};
+
+const DEFAULT_TEMPLATE: &str = "# {title}\n\n{authors} ({year})\n\n";
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct NotesConfig {
+    /// File extension of notes
+    pub extension: String,
+    /// Template used when there is no `templates/note.<ext>`
+    pub template: Option<String>,
+    /// Command to open notes with, defaults to `$VISUAL` or `$EDITOR`
+    pub editor: Option<String>,
+}
+
+impl Default for NotesConfig {
+    fn default() -> Self {
+        Self {
+            extension: "md".to_string(),
+            template: None,
+            editor: None,
+        }
+    }
+}
+
+/// Where the note on `key` is kept, in a directory of its own for each member
+/// of a reading group.
+pub fn path(setup: &Setup, config: &NotesConfig, member: Option<&str>, key: &str) -> Utf8PathBuf {
+    let dir = match member {
+        Some(member) => setup.notes_dir().join(group::slug(member)),
+        None => setup.notes_dir(),
+    };
+    dir.join(format!("{key}.{}", config.extension))
+}
+
+/// The reading group members with notes, by the names of their directories
+/// in `notes/`. Directories of keys with `/` in them, such as DBLP keys, are
+/// told apart by not being named like a member.
+pub fn members(setup: &Setup) -> Result<Vec<String>> {
+    let notes = setup.notes_dir();
+    if !notes.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut members = Vec::new();
+    for dir in notes.read_dir_utf8()? {
+        let dir = dir?;
+        let name = dir.file_name();
+        if dir.file_type()?.is_dir() && group::slug(name) == name {
+            members.push(name.to_string());
+        }
+    }
+    Ok(members)
+}
+
+fn template(setup: &Setup, config: &NotesConfig, name: Option<&str>) -> Result<String> {
+    let file =
+        setup
+            .templates_dir()
+            .join(format!("{}.{}", name.unwrap_or("note"), config.extension));
+    if file.exists() {
+        debug!(%file, "using note template");
+        return Ok(fs::read_to_string(file)?);
+    }
+    if name.is_some() {
+        bail!("no template at {file}");
+    }
+    Ok(config
+        .template
+        .clone()
+        .unwrap_or_else(|| DEFAULT_TEMPLATE.to_string()))
+}
+
+/// Fills in the placeholders of `template` for `entry`.
+pub fn render(setup: &Setup, template: &str, entry: &Entry) -> Result<String> {
+    let meta = meta::load(setup, &entry.key)?.unwrap_or_default();
+    let value = |name: &str| -> Option<String> {
+        Some(match name {
+            "title" => entry::title(entry),
+            "authors" => entry::authors(entry),
+            "year" => entry::year(entry)
+                .map(|y| y.to_string())
+                .unwrap_or_default(),
+            "venue" => entry::venue(entry).unwrap_or_default(),
+            "doi" => entry.doi().unwrap_or_default(),
+            "abstract" => entry
+                .abstract_()
+                .map(|a| a.format_verbatim())
+                .unwrap_or_default(),
+            "tags" => entry::tags(entry).join(", "),
+            "key" => entry.key.clone(),
+            _ => meta::display(meta.get(name.strip_prefix("meta.")?)?),
+        })
+    };
+
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after
+            .find('}')
+            .and_then(|end| Some((value(&after[..end])?, end)))
+        {
+            Some((value, end)) => {
+                out.push_str(&value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Opens the note of `entry` in the editor, creating it from a template if it
+/// does not exist yet. The note is recorded in `tx`, so that it is put back if
+/// the command fails. With `--dry-run`, only says which note it would write.
+pub fn open(
+    setup: &Setup,
+    tx: &mut Transaction,
+    entry: &Entry,
+    template_name: Option<&str>,
+) -> Result<()> {
+    let config = setup.config()?.notes;
+    let member = group::member(setup)?;
+    let path = path(setup, &config, member.as_deref(), &entry.key);
+    if dry_run::enabled() {
+        dry_run::would_write(&path);
+        return Ok(());
+    }
+    tx.track(&path)?;
+    if !path.exists() {
+        let content = render(setup, &template(setup, &config, template_name)?, entry)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, content)?;
+        info!(%path, "created note");
+    }
+
+    let editor = config
+        .editor
+        .or_else(|| std::env::var("VISUAL").ok())
+        .or_else(|| std::env::var("EDITOR").ok())
+        .unwrap_or_else(|| "vi".to_string());
+    let mut args = editor.split_whitespace();
+    let program = args
+        .next()
+        .ok_or_else(|| eyre!("editor command is empty"))?;
+    let mut args: Vec<String> = args.map(str::to_string).collect();
+    args.push(path.to_string());
+    duct::cmd(program, args).run()?;
+    Ok(())
+}
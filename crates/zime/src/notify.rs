@@ -0,0 +1,50 @@
+//! Desktop notifications for long-running operations, such as downloading
+//! PDFs or syncing from a cron job, so that they can run unattended.
+//!
+//! Notifications are off by default and enabled with
+//!
+//! ```toml
+//! [notifications]
+//! enabled = true
+//! ```
+//!
+//! in the global config.
+
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::config::Config;
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct NotifyConfig {
+    /// Show desktop notifications when downloads or syncs finish
+    pub enabled: bool,
+}
+
+fn enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| {
+        Config::load_global()
+            .map(|config| config.notifications.enabled)
+            .unwrap_or_default()
+    })
+}
+
+/// Shows a notification if they are enabled. Failures, e.g. when no
+/// notification daemon is running, are only logged.
+pub fn send(summary: &str, body: &str) {
+    if !enabled() {
+        return;
+    }
+    let result = notify_rust::Notification::new()
+        .appname("zime")
+        .summary(summary)
+        .body(body)
+        .show();
+    if let Err(err) = result {
+        debug!(%err, "failed to show notification");
+    }
+}
@@ -0,0 +1,97 @@
+//! Commands run on every PDF after it is downloaded, e.g. to add a text layer,
+//! linearize it or drop the cover page a proxy puts in front:
+//!
+//! ```toml
+//! [[pdf_steps]]
+//! name = "ocr"
+//! command = "ocrmypdf --skip-text {input} {output}"
+//!
+//! [[pdf_steps]]
+//! name = "cover"
+//! command = "qpdf {input} --pages . 2-z -- {output}"
+//! sources = ["proxy"]
+//! ```
+//!
+//! `{input}` is the PDF and `{output}` where the step writes the result.
+//! Steps without `{output}` change `{input}` in place. Either way a step works
+//! on a copy, which only replaces the PDF if the step succeeds and leaves a
+//! PDF behind, so a failing step is skipped and the next one still runs.
+
+use std::fs;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use color_eyre::eyre::{bail, eyre};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::{Result, cancel};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Step {
+    /// Shown when the step fails
+    pub name: String,
+    pub command: String,
+    /// Remotes whose PDFs the step runs on, all of them if empty. PDFs given
+    /// to `add --pdf` come from `local`
+    #[serde(default)]
+    pub sources: Vec<String>,
+}
+
+/// Runs the steps for PDFs from `source` on the PDF at `path`, one after the
+/// other, warning about those that fail.
+pub fn run(steps: &[Step], path: &Utf8Path, source: &str) {
+    for step in steps {
+        if cancel::cancelled() {
+            return;
+        }
+        if !step.sources.is_empty() && !step.sources.iter().any(|s| s == source) {
+            continue;
+        }
+        debug!(step = %step.name, %path, "processing PDF");
+        if let Err(err) = run_step(step, path) {
+            warn!(step = %step.name, %path, %err, "PDF step failed, skipping it");
+        }
+    }
+}
+
+fn run_step(step: &Step, path: &Utf8Path) -> Result<()> {
+    let scratch = Utf8PathBuf::from(format!("{path}.step"));
+    let result = run_on(step, path, &scratch);
+    if result.is_err() {
+        let _ = fs::remove_file(&scratch);
+    }
+    result
+}
+
+fn run_on(step: &Step, path: &Utf8Path, scratch: &Utf8Path) -> Result<()> {
+    let in_place = !step.command.split_whitespace().any(|arg| arg == "{output}");
+    if in_place {
+        fs::copy(path, scratch)?;
+    }
+    let input = if in_place { scratch } else { path };
+
+    let mut args = step.command.split_whitespace().map(|arg| match arg {
+        "{input}" => input.as_str(),
+        "{output}" => scratch.as_str(),
+        arg => arg,
+    });
+    let program = args.next().ok_or_else(|| eyre!("the command is empty"))?;
+    let output = duct::cmd(program, args)
+        .stdout_null()
+        .stderr_capture()
+        .unchecked()
+        .run()
+        .map_err(|err| eyre!("failed to run {program}: {err}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().rfind(|line| !line.trim().is_empty());
+        bail!("{program} failed: {}", reason.unwrap_or("no output"));
+    }
+
+    let result = fs::read(scratch).map_err(|_| eyre!("{program} wrote no PDF"))?;
+    if !result.starts_with(b"%PDF") {
+        bail!("{program} did not leave a PDF behind");
+    }
+    fs::rename(scratch, path)?;
+    Ok(())
+}
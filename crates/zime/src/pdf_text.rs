@@ -0,0 +1,86 @@
+//! The text of PDFs, extracted once and kept in the cache directory.
+//!
+//! The text is extracted with `pdftotext` from poppler, which has to be
+//! installed, and kept in `pdf-text/` by the checksum of the PDF, so that a
+//! PDF replaced by another version is extracted again. Pages are kept apart,
+//! so that the DOI of a paper can be looked for on its first page only,
+//! instead of among its references.
+//!
+//! `text:` queries search the text, and `add --pdf` looks for the DOI in it
+//! when the PDF has none in its metadata.
+
+use std::fs;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use color_eyre::eyre::eyre;
+use duct::cmd;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::{Result, config, pdfs};
+
+const DIR: &str = "pdf-text";
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Text {
+    pub text: String,
+    /// The offset in `text` at which each page starts
+    pub pages: Vec<usize>,
+}
+
+impl Text {
+    fn parse(text: String) -> Self {
+        // pdftotext ends every page with a form feed
+        let mut pages = vec![0];
+        pages.extend(text.match_indices('\u{c}').map(|(i, _)| i + 1));
+        if pages.len() > 1 && pages.last() == Some(&text.len()) {
+            pages.pop();
+        }
+        Text { text, pages }
+    }
+
+    /// The text of page `n`, counting from 1.
+    pub fn page(&self, n: usize) -> &str {
+        let Some(&start) = self.pages.get(n.wrapping_sub(1)) else {
+            return "";
+        };
+        let end = self.pages.get(n).copied().unwrap_or(self.text.len());
+        &self.text[start..end]
+    }
+}
+
+fn cache_path(checksum: &str) -> Result<Utf8PathBuf> {
+    Ok(config::cache_dir()?
+        .join(DIR)
+        .join(format!("{checksum}.json")))
+}
+
+/// The text of the PDF at `path`, extracted now unless it was before.
+pub fn extract(path: &Utf8Path) -> Result<Text> {
+    let checksum = pdfs::sha256(path)?;
+    let cache = cache_path(&checksum)?;
+    let cached = fs::read_to_string(&cache)
+        .ok()
+        .and_then(|src| serde_json::from_str::<Text>(&src).ok());
+    if let Some(text) = cached {
+        debug!(%path, "using cached text");
+        return Ok(text);
+    }
+
+    debug!(%path, "extracting text");
+    let output = cmd!("pdftotext", "-enc", "UTF-8", path, "-")
+        .stdout_capture()
+        .stderr_null()
+        .unchecked()
+        .run()
+        .map_err(|err| eyre!("failed to run pdftotext, which is part of poppler: {err}"))?;
+    if !output.status.success() {
+        return Err(eyre!("pdftotext could not read {path}"));
+    }
+    let text = Text::parse(String::from_utf8_lossy(&output.stdout).into_owned());
+    if let Some(dir) = cache.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&cache, serde_json::to_string(&text)?)?;
+    Ok(text)
+}
@@ -0,0 +1,451 @@
+//! Management of the downloaded PDFs in the library.
+//!
+//! Next to the PDFs, `pdfs/manifest.json` records the file name, checksum and
+//! source of every downloaded PDF by DOI. It is used to carry files over when
+//! the naming scheme changes.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    sync::Mutex,
+};
+
+use biblatex::{Bibliography, Chunk, Spanned};
+use camino::{Utf8Path, Utf8PathBuf};
+use color_eyre::owo_colors::OwoColorize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, info, warn};
+
+use crate::{
+    Result, bulk, cancel, config::Setup, doi, dry_run, entry, i18n::t, notify, pdf_steps, prompt,
+    query::Query, remotes, summary::Summary, transaction::Transaction,
+};
+
+pub const MANIFEST: &str = "manifest.json";
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Manifest {
+    /// Downloaded PDFs by normalized DOI
+    pub files: BTreeMap<String, PdfRecord>,
+    /// The last failed download attempt by normalized DOI
+    #[serde(default)]
+    pub failures: BTreeMap<String, FailureRecord>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PdfRecord {
+    /// File name inside the PDF directory
+    pub file: String,
+    pub sha256: String,
+    /// Name of the remote the PDF was downloaded from
+    pub source: Option<String>,
+    pub downloaded: Option<jiff::Timestamp>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FailureRecord {
+    pub source: String,
+    pub reason: FailureReason,
+    /// The error as it was reported
+    pub error: String,
+    pub at: jiff::Timestamp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FailureReason {
+    NotFound,
+    Paywalled,
+    Network,
+    Other,
+}
+
+impl FailureReason {
+    pub fn classify(err: &color_eyre::eyre::Error) -> Self {
+        if let Some(err) = err.downcast_ref::<reqwest::Error>() {
+            return match err.status().map(|s| s.as_u16()) {
+                Some(404 | 410) => Self::NotFound,
+                Some(401 | 402 | 403 | 451) => Self::Paywalled,
+                Some(_) => Self::Other,
+                None if err.is_connect() || err.is_timeout() || err.is_request() => Self::Network,
+                None => Self::Other,
+            };
+        }
+        if err.downcast_ref::<remotes::NotAvailable>().is_some() {
+            return Self::Paywalled;
+        }
+        Self::Other
+    }
+}
+
+impl std::fmt::Display for FailureReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "not found"),
+            Self::Paywalled => write!(f, "paywalled"),
+            Self::Network => write!(f, "network error"),
+            Self::Other => write!(f, "error"),
+        }
+    }
+}
+
+impl Manifest {
+    pub fn load(setup: &Setup) -> Result<Self> {
+        let path = setup.pdf_dir().join(MANIFEST);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, setup: &Setup) -> Result<()> {
+        if dry_run::enabled() {
+            dry_run::would_write(&setup.pdf_dir().join(MANIFEST));
+            return Ok(());
+        }
+        fs::create_dir_all(setup.pdf_dir())?;
+        fs::write(
+            setup.pdf_dir().join(MANIFEST),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+
+    /// Records a PDF that was just written to `path`.
+    pub fn record(&mut self, doi: &str, path: &Utf8Path, source: Option<&str>) -> Result<()> {
+        let record = PdfRecord {
+            file: path.file_name().unwrap_or_default().to_string(),
+            sha256: sha256(path)?,
+            source: source.map(str::to_string),
+            downloaded: Some(jiff::Timestamp::now()),
+        };
+        self.files.insert(doi::normalize(doi), record);
+        self.failures.remove(&doi::normalize(doi));
+        Ok(())
+    }
+
+    pub fn record_failure(&mut self, doi: &str, source: &str, err: &color_eyre::eyre::Error) {
+        let record = FailureRecord {
+            source: source.to_string(),
+            reason: FailureReason::classify(err),
+            error: err.to_string(),
+            at: jiff::Timestamp::now(),
+        };
+        self.failures.insert(doi::normalize(doi), record);
+    }
+}
+
+/// Downloads the PDF of every entry matching `only` with a DOI that does not
+/// have one yet, several at a time. With `--dry-run`, only lists the PDFs it
+/// would download.
+pub fn download_all(setup: &Setup, only: &Query) -> Result<Summary> {
+    let bib = setup.bib()?;
+    let outdated = plan_migration(setup, &bib, &Manifest::load(setup)?)?.len();
+    if outdated > 0 {
+        eprintln!("{}", t!("pdfs-outdated-names", count = outdated).yellow());
+    }
+    let mut summary = Summary::default();
+    let mut wanted = Vec::new();
+    for entry in only.filter(setup, &bib)? {
+        let title = entry::title(entry);
+        let doi = match entry.doi() {
+            Ok(doi) => doi,
+            Err(err) => {
+                warn!(title=%title, %err, "failed to extract DOI");
+                summary.skip(&entry.key, "no DOI");
+                continue;
+            }
+        };
+        let path = setup.pdf_path(&doi);
+
+        if path.exists() {
+            debug!(%path, "skipping PDF, already exists");
+            summary.skip(&entry.key, "already downloaded");
+            continue;
+        }
+        wanted.push((&entry.key, title, doi));
+    }
+    if dry_run::enabled() {
+        for (_, _, doi) in &wanted {
+            dry_run::would_write(&setup.pdf_path(doi));
+        }
+        return Ok(summary);
+    }
+
+    fs::create_dir_all(setup.pdf_dir())?;
+    // Saved after every download, so that a cancelled run keeps track of
+    // the PDFs it got
+    let manifest = Mutex::new(Manifest::load(setup)?);
+    let steps = setup.config()?.pdf_steps;
+    let spinner = prompt::spinner();
+    spinner.start(t!("pdfs-downloading", count = wanted.len()));
+    let results = bulk::fetch(
+        wanted.iter().map(|(_, _, doi)| doi.clone()),
+        |doi| spinner.set_message(doi),
+        |doi| {
+            let path = setup.pdf_path(doi);
+            let (source, result) = remotes::fetch_pdf(doi);
+            let pdf = result.and_then(|pdf| {
+                debug!(path=%path, "writing PDF to file");
+                cancel::write(&path, pdf)
+            });
+            if pdf.is_ok() {
+                pdf_steps::run(&steps, &path, source);
+            }
+            let mut manifest = manifest.lock().unwrap();
+            match &pdf {
+                Ok(()) => {
+                    manifest.record(doi, &path, Some(source))?;
+                    info!(path=%path, "downloaded PDF");
+                }
+                Err(err) => manifest.record_failure(doi, source, err),
+            }
+            manifest.save(setup)?;
+            pdf
+        },
+    )?;
+    spinner.stop("");
+
+    for (key, title, doi) in wanted {
+        match &results[&doi] {
+            Ok(()) => summary.succeed(key),
+            Err(err) => {
+                warn!(title=%title, %doi, %err, "failed to download PDF");
+                summary.fail(key, FailureReason::classify(err));
+            }
+        }
+    }
+    if summary.succeeded + summary.failed > 0 {
+        notify::send(
+            &t!("notify-pdfs-downloaded", count = summary.succeeded),
+            &t!("notify-pdfs-failed", count = summary.failed),
+        );
+    }
+    Ok(summary)
+}
+
+/// Prints whether each entry has a PDF, where it came from, and why the last
+/// download attempt failed for those that do not.
+pub fn print_status(setup: &Setup, only: &Query) -> Result<()> {
+    let bib = setup.bib()?;
+    let manifest = Manifest::load(setup)?;
+    let (mut present, mut missing) = (0, 0);
+    for entry in only.filter(setup, &bib)? {
+        let title = entry::title(entry);
+        let Ok(doi) = entry.doi() else {
+            missing += 1;
+            println!("{} {}  {}", "✗".red(), title, t!("pdf-no-doi").dimmed());
+            continue;
+        };
+        let normalized = doi::normalize(&doi);
+        if setup.pdf_path(&doi).exists() {
+            present += 1;
+            let source = manifest
+                .files
+                .get(&normalized)
+                .and_then(|record| record.source.as_deref())
+                .unwrap_or("unknown source");
+            println!("{} {}  {}", "✓".green(), title, source.dimmed());
+        } else if let Some(failure) = manifest.failures.get(&normalized) {
+            missing += 1;
+            println!(
+                "{} {}  {}",
+                "✗".red(),
+                title,
+                format!(
+                    "{} on {} ({})",
+                    failure.reason,
+                    failure.source,
+                    failure.at.strftime("%Y-%m-%d")
+                )
+                .dimmed()
+            );
+        } else {
+            missing += 1;
+            println!(
+                "{} {}  {}",
+                "·".yellow(),
+                title,
+                t!("pdf-not-attempted").dimmed()
+            );
+        }
+    }
+    println!(
+        "\n{}",
+        t!("pdfs-summary", present = present, total = present + missing)
+    );
+    Ok(())
+}
+
+pub fn sha256(path: &Utf8Path) -> Result<String> {
+    let digest = Sha256::digest(fs::read(path)?);
+    Ok(digest.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+#[derive(Debug)]
+pub struct Rename {
+    pub old: Utf8PathBuf,
+    pub new: Utf8PathBuf,
+    pub doi: String,
+}
+
+/// Works out which PDFs are not stored under the current naming scheme and
+/// what they should be renamed to.
+///
+/// A file is matched to a DOI by its manifest record, by its checksum, by
+/// decoding its name, or by the legacy `--` scheme, in that order. Legacy
+/// names are ambiguous (`a/b` and `a--b` both became `a--b.pdf`), so they are
+/// only used if exactly one DOI in the bibliography maps to the name.
+pub fn plan_migration(
+    setup: &Setup,
+    bib: &Bibliography,
+    manifest: &Manifest,
+) -> Result<Vec<Rename>> {
+    let mut renames = Vec::new();
+    if !setup.pdf_dir().exists() {
+        return Ok(renames);
+    }
+
+    let mut dois = HashSet::new();
+    let mut legacy: HashMap<String, Vec<String>> = HashMap::new();
+    for doi in bib.iter().filter_map(|entry| entry.doi().ok()) {
+        let normalized = doi::normalize(&doi);
+        if dois.insert(normalized.clone()) {
+            legacy
+                .entry(doi::legacy_file_stem(&doi))
+                .or_default()
+                .push(normalized);
+        }
+    }
+    let by_file: HashMap<&str, &str> = manifest
+        .files
+        .iter()
+        .map(|(doi, record)| (record.file.as_str(), doi.as_str()))
+        .collect();
+    let by_checksum: HashMap<&str, &str> = manifest
+        .files
+        .iter()
+        .map(|(doi, record)| (record.sha256.as_str(), doi.as_str()))
+        .collect();
+
+    let mut files: Vec<Utf8PathBuf> = setup
+        .pdf_dir()
+        .read_dir_utf8()?
+        .map(|entry| entry.map(|entry| entry.into_path()))
+        .collect::<Result<_, _>>()?;
+    files.sort();
+
+    for old in files {
+        let (Some(name), Some(stem)) = (old.file_name(), old.file_stem()) else {
+            continue;
+        };
+        if old.extension() != Some("pdf") {
+            continue;
+        }
+        let decoded = doi::from_file_stem(stem).map(|doi| doi::normalize(&doi));
+        if decoded
+            .as_ref()
+            .is_some_and(|doi| dois.contains(doi) && doi::to_file_stem(doi) == stem)
+        {
+            continue;
+        }
+
+        let doi = if let Some(doi) = by_file.get(name) {
+            Some(doi.to_string())
+        } else if let Some(doi) = by_checksum.get(sha256(&old)?.as_str()) {
+            Some(doi.to_string())
+        } else if let Some(doi) = decoded.filter(|doi| dois.contains(doi)) {
+            Some(doi)
+        } else {
+            match legacy.get(stem).map(Vec::as_slice) {
+                Some([doi]) => Some(doi.clone()),
+                Some(candidates) => {
+                    warn!(path=%old, ?candidates, "legacy PDF name is ambiguous, not migrating");
+                    None
+                }
+                None => None,
+            }
+        };
+        let Some(doi) = doi else {
+            debug!(path=%old, "no DOI found for PDF, leaving it");
+            continue;
+        };
+
+        let new = setup.pdf_path(&doi);
+        if new == old {
+            continue;
+        }
+        if new.exists() || renames.iter().any(|r: &Rename| r.new == new) {
+            warn!(%old, %new, "PDF already exists under the new name, keeping both");
+            continue;
+        }
+        renames.push(Rename { old, new, doi });
+    }
+
+    Ok(renames)
+}
+
+/// Performs the renames through `tx`, updating the manifest and any `file`
+/// fields in the bibliography that point at the old names. Returns whether the
+/// bibliography was changed.
+pub fn apply_migration(
+    tx: &mut Transaction,
+    bib: &mut Bibliography,
+    manifest: &mut Manifest,
+    renames: &[Rename],
+) -> Result<bool> {
+    let mut bib_changed = false;
+    for rename in renames {
+        info!(old=%rename.old, new=%rename.new, "renaming PDF");
+        tx.rename(&rename.old, &rename.new)?;
+
+        let old_name = rename.old.file_name().unwrap_or_default();
+        let new_name = rename.new.file_name().unwrap_or_default();
+        let record = manifest
+            .files
+            .entry(doi::normalize(&rename.doi))
+            .or_insert_with(|| PdfRecord {
+                file: old_name.to_string(),
+                sha256: String::new(),
+                source: None,
+                downloaded: None,
+            });
+        record.file = new_name.to_string();
+        if record.sha256.is_empty() {
+            record.sha256 = sha256(&rename.new)?;
+        }
+
+        for entry in bib.iter_mut() {
+            let Ok(file) = entry.file() else { continue };
+            let path = Utf8Path::new(&file);
+            if path.file_name() == Some(old_name) {
+                let updated = path.with_file_name(new_name);
+                debug!(key=%entry.key, %updated, "updating file field");
+                entry.set(
+                    "file",
+                    vec![Spanned::detached(Chunk::Verbatim(updated.to_string()))],
+                );
+                bib_changed = true;
+            }
+        }
+    }
+    Ok(bib_changed)
+}
+
+/// Brings the PDF directory in line with the current naming scheme, writing
+/// the bibliography and manifest if anything changed. The renames are recorded
+/// in `tx`, so that they are undone with the rest of the command.
+pub fn migrate(setup: &Setup, tx: &mut Transaction, dry_run: bool) -> Result<Vec<Rename>> {
+    let mut bib = setup.bib()?;
+    let mut manifest = Manifest::load(setup)?;
+    let renames = plan_migration(setup, &bib, &manifest)?;
+    if dry_run || renames.is_empty() {
+        return Ok(renames);
+    }
+    if apply_migration(tx, &mut bib, &mut manifest, &renames)? {
+        setup.write_bib(&bib)?;
+    }
+    manifest.save(setup)?;
+    Ok(renames)
+}
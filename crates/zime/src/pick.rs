@@ -0,0 +1,120 @@
+//! Picking an entry and doing something with it, either interactively or
+//! through a dmenu-style launcher.
+//!
+//! With `--dmenu`, `zime pick` prints one line per entry, and with `--then` it
+//! reads the chosen line back from stdin, so that it can be chained with rofi,
+//! dmenu or fzf:
+//!
+//! ```sh
+//! zime pick --dmenu | rofi -dmenu | zime pick --dmenu --then pdf
+//! ```
+
+use std::io::BufRead;
+
+use biblatex::{Bibliography, Entry};
+use color_eyre::eyre::eyre;
+
+use crate::{Result, config::Setup, entry, i18n::t, links, prompt, query::Query, select, share};
+
+/// What to do with the picked entry.
+#[derive(Debug, Default, Clone, Copy, clap::ValueEnum)]
+pub enum Action {
+    /// Print its cite key
+    #[default]
+    Key,
+    /// Print a `\cite` command for it
+    Cite,
+    /// Print its DOI or arXiv link
+    Url,
+    /// Open its PDF in the default viewer
+    Pdf,
+}
+
+/// The line representing `entry` in a launcher. The key comes first, so that
+/// the choice can be mapped back to the entry.
+fn line(entry: &Entry) -> String {
+    let mut line = format!("{}\t{}", entry.key, entry::title(entry));
+    let authors = entry::authors(entry);
+    if !authors.is_empty() {
+        line.push_str(&format!(" — {authors}"));
+    }
+    if let Some(year) = entry::year(entry) {
+        line.push_str(&format!(" ({year})"));
+    }
+    line
+}
+
+/// The entry a line read back from a launcher refers to.
+fn chosen<'a>(bib: &'a Bibliography, line: &str) -> Result<&'a Entry> {
+    let key = line.split('\t').next().unwrap_or_default().trim();
+    bib.get(key)
+        .ok_or_else(|| eyre!(t!("no-entry-found", query = key)))
+}
+
+fn act(setup: &Setup, entry: &Entry, action: Action) -> Result<()> {
+    match action {
+        Action::Key => println!("{}", entry.key),
+        Action::Cite => println!("\\cite{{{}}}", entry.key),
+        Action::Url => share::run(entry, false)?,
+        Action::Pdf => links::open_pdf(setup, entry)?,
+    }
+    Ok(())
+}
+
+pub fn run(setup: &Setup, query: &Query, dmenu: bool, then: Option<Action>) -> Result<()> {
+    let bib = setup.bib()?;
+    match (dmenu, then) {
+        (true, None) => {
+            for entry in query.filter(setup, &bib)? {
+                println!("{}", line(entry));
+            }
+            Ok(())
+        }
+        (true, Some(action)) => {
+            let mut line = String::new();
+            std::io::stdin().lock().read_line(&mut line)?;
+            // launchers exit without output when the user cancels
+            if line.trim().is_empty() {
+                return Ok(());
+            }
+            act(setup, chosen(&bib, &line)?, action)
+        }
+        (false, then) => {
+            let entries = query.filter(setup, &bib)?;
+            if entries.is_empty() {
+                println!("{}", t!("no-matches"));
+                return Ok(());
+            }
+            let entry = select::select(&t!("select-article"), &entries)?;
+            act(setup, entry, then.unwrap_or_default())
+        }
+    }
+}
+
+/// Lets the user narrow down the entries matching `query` by typing, and
+/// prints the key of the chosen one, for inserting citations from an editor.
+///
+/// The selector is drawn on stderr, so that stdout only ever contains the key.
+/// Cancelling exits with status 1 and prints nothing.
+pub fn pick_key(setup: &Setup, query: &Query) -> Result<()> {
+    let bib = setup.bib()?;
+    let entries = query.filter(setup, &bib)?;
+    if entries.is_empty() {
+        eprintln!("{}", t!("no-matches"));
+        std::process::exit(1);
+    }
+    let picked = prompt::select_filtered(
+        t!("select-article"),
+        entries
+            .iter()
+            .map(|entry| (line(entry).replacen('\t', "  ", 1), "")),
+    );
+    match picked {
+        Ok(i) => {
+            println!("{}", entries[i].key);
+            Ok(())
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::Interrupted => std::process::exit(1),
+        Err(err) => Err(err.into()),
+    }
+}
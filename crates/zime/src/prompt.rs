@@ -0,0 +1,445 @@
+//! The interactive prompts zime asks questions with: picking an entry,
+//! choosing which changes to apply, confirming a removal, and the spinners
+//! shown while waiting on remotes.
+//!
+//! The prompts are drawn by one of several backends, chosen in the global
+//! config or for a single command with `--prompt`:
+//!
+//! ```toml
+//! [prompt]
+//! backend = "plain"
+//! ```
+//!
+//! - `cliclack` draws the usual prompts, navigated with the arrow keys.
+//! - `plain` prints numbered choices and reads a line, for terminals and
+//!   screen readers that do not cope with redrawing.
+//! - `non-interactive` never asks, and takes the default of every prompt,
+//!   for scripts and CI. A query matching several entries is an error
+//!   instead, unless the command was given `--all`.
+//! - `auto`, the default, is `non-interactive` when stdin or stderr is not a
+//!   terminal, `plain` when `TERM=dumb` and `cliclack` otherwise.
+//!
+//! Cancelling a prompt is an [`std::io::ErrorKind::Interrupted`] error with
+//! each backend, as it is with cliclack.
+
+use std::{
+    fmt::Display,
+    io::{self, BufRead, IsTerminal, Write},
+    sync::OnceLock,
+};
+
+use color_eyre::owo_colors::OwoColorize;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{config::Config, i18n::t};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Kind {
+    #[default]
+    Auto,
+    Cliclack,
+    Plain,
+    NonInteractive,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PromptConfig {
+    pub backend: Kind,
+}
+
+static KIND: OnceLock<Kind> = OnceLock::new();
+
+/// Uses the `kind` backend instead of the configured one.
+pub fn set(kind: Kind) {
+    let _ = KIND.set(kind);
+}
+
+fn kind() -> Kind {
+    let kind = *KIND.get_or_init(|| {
+        Config::load_global()
+            .map(|c| c.prompt.backend)
+            .unwrap_or_default()
+    });
+    match kind {
+        Kind::Auto if !io::stdin().is_terminal() || !io::stderr().is_terminal() => {
+            Kind::NonInteractive
+        }
+        Kind::Auto if std::env::var("TERM").is_ok_and(|term| term == "dumb") => Kind::Plain,
+        Kind::Auto => Kind::Cliclack,
+        kind => kind,
+    }
+}
+
+/// Whether questions are asked at all, rather than answered with defaults.
+pub fn interactive() -> bool {
+    kind() != Kind::NonInteractive
+}
+
+/// One choice of a prompt.
+pub struct Item {
+    pub label: String,
+    /// Shown dimmed after the label, e.g. the DOI of an entry
+    pub hint: String,
+}
+
+impl<L: Display, H: Display> From<(L, H)> for Item {
+    fn from((label, hint): (L, H)) -> Self {
+        Item {
+            label: label.to_string(),
+            hint: hint.to_string(),
+        }
+    }
+}
+
+/// Shows that zime is waiting, with a message that may change on the way.
+pub enum Spinner {
+    Cliclack(cliclack::ProgressBar),
+    Plain,
+    Silent,
+}
+
+impl Spinner {
+    pub fn start(&self, message: impl Display) {
+        match self {
+            Spinner::Cliclack(bar) => bar.start(message),
+            Spinner::Plain => eprintln!("{message}"),
+            Spinner::Silent => {}
+        }
+    }
+
+    pub fn set_message(&self, message: impl Display) {
+        if let Spinner::Cliclack(bar) = self {
+            bar.set_message(message);
+        }
+    }
+
+    pub fn stop(&self, message: impl Display) {
+        match self {
+            Spinner::Cliclack(bar) => bar.stop(message),
+            Spinner::Plain => {
+                let message = message.to_string();
+                if !message.is_empty() {
+                    eprintln!("{message}");
+                }
+            }
+            Spinner::Silent => {}
+        }
+    }
+}
+
+/// A way of asking the user.
+pub trait Backend {
+    /// The index of the item chosen, starting at `initial`. With `filter`,
+    /// the items can be narrowed down by typing.
+    fn select(
+        &self,
+        prompt: &str,
+        items: &[Item],
+        initial: usize,
+        filter: bool,
+    ) -> io::Result<usize>;
+    /// The indices of the items chosen, starting with those in `initial`.
+    fn multiselect(
+        &self,
+        prompt: &str,
+        items: &[Item],
+        initial: &[usize],
+    ) -> io::Result<Vec<usize>>;
+    fn confirm(&self, prompt: &str, default: bool) -> io::Result<bool>;
+    fn spinner(&self) -> Spinner;
+    /// Closes a series of prompts.
+    fn outro(&self, message: &str) -> io::Result<()>;
+}
+
+struct Cliclack;
+
+impl Backend for Cliclack {
+    fn select(
+        &self,
+        prompt: &str,
+        items: &[Item],
+        initial: usize,
+        filter: bool,
+    ) -> io::Result<usize> {
+        let mut select = cliclack::select(prompt)
+            .items(
+                &items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| (i, &item.label, &item.hint))
+                    .collect_vec(),
+            )
+            .initial_value(initial);
+        if filter {
+            select = select.filter_mode();
+        }
+        select.interact()
+    }
+
+    fn multiselect(
+        &self,
+        prompt: &str,
+        items: &[Item],
+        initial: &[usize],
+    ) -> io::Result<Vec<usize>> {
+        cliclack::multiselect(prompt)
+            .items(
+                &items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| (i, &item.label, &item.hint))
+                    .collect_vec(),
+            )
+            .initial_values(initial.to_vec())
+            .required(false)
+            .interact()
+    }
+
+    fn confirm(&self, prompt: &str, default: bool) -> io::Result<bool> {
+        cliclack::confirm(prompt).initial_value(default).interact()
+    }
+
+    fn spinner(&self) -> Spinner {
+        Spinner::Cliclack(cliclack::spinner())
+    }
+
+    fn outro(&self, message: &str) -> io::Result<()> {
+        cliclack::outro(message)
+    }
+}
+
+/// Numbered choices on stderr, answered with a line on stdin.
+struct Plain;
+
+impl Plain {
+    /// Asks `question` and reads the answer, cancelling at the end of input.
+    fn ask(question: &str) -> io::Result<String> {
+        eprint!("{question} ");
+        io::stderr().flush()?;
+        let mut line = String::new();
+        if io::stdin().lock().read_line(&mut line)? == 0 {
+            eprintln!();
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "end of input"));
+        }
+        Ok(line.trim().to_string())
+    }
+
+    fn list(items: &[Item], shown: &[usize], marked: &[usize]) {
+        for &i in shown {
+            let mark = if marked.contains(&i) { "*" } else { " " };
+            let item = &items[i];
+            if item.hint.is_empty() {
+                eprintln!("{mark}{:>3}) {}", i + 1, item.label);
+            } else {
+                eprintln!("{mark}{:>3}) {}  {}", i + 1, item.label, item.hint.dimmed());
+            }
+        }
+    }
+
+    /// The item numbered in `answer`, as an index.
+    fn number(answer: &str, len: usize) -> Option<usize> {
+        answer
+            .parse::<usize>()
+            .ok()
+            .filter(|n| (1..=len).contains(n))
+            .map(|n| n - 1)
+    }
+
+    /// The items numbered in `answer`, such as `1 3 5-7`, as indices.
+    fn numbers(answer: &str, len: usize) -> Option<Vec<usize>> {
+        let mut chosen = Vec::new();
+        for part in answer.split([' ', ',']).filter(|part| !part.is_empty()) {
+            match part.split_once('-') {
+                Some((from, to)) => {
+                    let (from, to) = (Self::number(from, len)?, Self::number(to, len)?);
+                    chosen.extend(from..=to);
+                }
+                None => chosen.push(Self::number(part, len)?),
+            }
+        }
+        Some(chosen.into_iter().unique().sorted().collect())
+    }
+}
+
+impl Backend for Plain {
+    fn select(
+        &self,
+        prompt: &str,
+        items: &[Item],
+        initial: usize,
+        filter: bool,
+    ) -> io::Result<usize> {
+        let mut shown = (0..items.len()).collect_vec();
+        loop {
+            eprintln!("{prompt}");
+            Self::list(items, &shown, &[initial]);
+            let answer = Self::ask(&t!("prompt-select", default = initial + 1))?;
+            if answer.is_empty() {
+                return Ok(initial);
+            }
+            if let Some(i) = Self::number(&answer, items.len()) {
+                return Ok(i);
+            }
+            if filter {
+                let needle = answer.to_lowercase();
+                let matching = (0..items.len())
+                    .filter(|&i| items[i].label.to_lowercase().contains(&needle))
+                    .collect_vec();
+                if let [i] = matching.as_slice() {
+                    return Ok(*i);
+                }
+                if !matching.is_empty() {
+                    shown = matching;
+                    continue;
+                }
+            }
+            eprintln!("{}", t!("prompt-invalid", answer = answer).yellow());
+        }
+    }
+
+    fn multiselect(
+        &self,
+        prompt: &str,
+        items: &[Item],
+        initial: &[usize],
+    ) -> io::Result<Vec<usize>> {
+        let shown = (0..items.len()).collect_vec();
+        loop {
+            eprintln!("{prompt}");
+            Self::list(items, &shown, initial);
+            let answer = Self::ask(&t!("prompt-multiselect"))?;
+            match answer.as_str() {
+                "" => return Ok(initial.to_vec()),
+                "all" => return Ok(shown),
+                "none" => return Ok(Vec::new()),
+                _ => {}
+            }
+            if let Some(chosen) = Self::numbers(&answer, items.len()) {
+                return Ok(chosen);
+            }
+            eprintln!("{}", t!("prompt-invalid", answer = answer).yellow());
+        }
+    }
+
+    fn confirm(&self, prompt: &str, default: bool) -> io::Result<bool> {
+        let choices = if default { "[Y/n]" } else { "[y/N]" };
+        loop {
+            let answer = Self::ask(&format!("{prompt} {choices}"))?.to_lowercase();
+            match answer.as_str() {
+                "" => return Ok(default),
+                "y" | "yes" => return Ok(true),
+                "n" | "no" => return Ok(false),
+                _ => eprintln!("{}", t!("prompt-invalid", answer = answer).yellow()),
+            }
+        }
+    }
+
+    fn spinner(&self) -> Spinner {
+        Spinner::Plain
+    }
+
+    fn outro(&self, message: &str) -> io::Result<()> {
+        eprintln!("{message}");
+        Ok(())
+    }
+}
+
+/// Takes the default of every prompt without asking.
+struct NonInteractive;
+
+impl Backend for NonInteractive {
+    fn select(
+        &self,
+        prompt: &str,
+        items: &[Item],
+        initial: usize,
+        _filter: bool,
+    ) -> io::Result<usize> {
+        let label = items.get(initial).map_or("", |item| item.label.as_str());
+        warn!(prompt, choice = label, "not asking, taking the default");
+        Ok(initial)
+    }
+
+    fn multiselect(
+        &self,
+        prompt: &str,
+        _items: &[Item],
+        initial: &[usize],
+    ) -> io::Result<Vec<usize>> {
+        warn!(
+            prompt,
+            count = initial.len(),
+            "not asking, taking the default"
+        );
+        Ok(initial.to_vec())
+    }
+
+    fn confirm(&self, prompt: &str, default: bool) -> io::Result<bool> {
+        warn!(prompt, default, "not asking, taking the default");
+        Ok(default)
+    }
+
+    fn spinner(&self) -> Spinner {
+        Spinner::Silent
+    }
+
+    fn outro(&self, _message: &str) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The backend prompts are drawn with.
+pub fn backend() -> &'static dyn Backend {
+    match kind() {
+        Kind::Plain => &Plain,
+        Kind::NonInteractive => &NonInteractive,
+        Kind::Auto | Kind::Cliclack => &Cliclack,
+    }
+}
+
+/// Lets the user pick one of `items`, returning its index.
+pub fn select<I: Into<Item>>(
+    prompt: impl Display,
+    items: impl IntoIterator<Item = I>,
+    initial: usize,
+) -> io::Result<usize> {
+    let items = items.into_iter().map(Into::into).collect_vec();
+    backend().select(&prompt.to_string(), &items, initial, false)
+}
+
+/// Like [`select`], but the items can be narrowed down by typing.
+pub fn select_filtered<I: Into<Item>>(
+    prompt: impl Display,
+    items: impl IntoIterator<Item = I>,
+) -> io::Result<usize> {
+    let items = items.into_iter().map(Into::into).collect_vec();
+    backend().select(&prompt.to_string(), &items, 0, true)
+}
+
+/// Lets the user pick any of `items`, returning their indices. Those in
+/// `initial` are selected to begin with.
+pub fn multiselect<I: Into<Item>>(
+    prompt: impl Display,
+    items: impl IntoIterator<Item = I>,
+    initial: &[usize],
+) -> io::Result<Vec<usize>> {
+    let items = items.into_iter().map(Into::into).collect_vec();
+    backend().multiselect(&prompt.to_string(), &items, initial)
+}
+
+/// Asks a yes or no question, no unless answered.
+pub fn confirm(prompt: impl Display) -> io::Result<bool> {
+    backend().confirm(&prompt.to_string(), false)
+}
+
+pub fn spinner() -> Spinner {
+    backend().spinner()
+}
+
+pub fn outro(message: impl Display) -> io::Result<()> {
+    backend().outro(&message.to_string())
+}
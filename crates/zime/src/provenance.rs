@@ -0,0 +1,82 @@
+//! Where an entry was fetched from, and who added it.
+//!
+//! Provenance is stored in reserved `zime-*` fields of the entry itself, which
+//! BibTeX and biber ignore.
+
+use biblatex::{Chunk, ChunksExt, Entry, Spanned};
+use duct::cmd;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Setup;
+
+pub const SOURCE: &str = "zime-source";
+pub const SOURCE_ID: &str = "zime-source-id";
+pub const FETCHED: &str = "zime-fetched";
+/// The git identity of whoever added the entry, for shared libraries
+pub const ADDED_BY: &str = "zime-added-by";
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Provenance {
+    /// Name of the remote, e.g. `dblp`
+    pub source: String,
+    /// Identifier of the record at the remote, e.g. the DBLP key
+    pub id: String,
+    pub fetched: Option<jiff::Timestamp>,
+}
+
+impl Provenance {
+    pub fn new(source: &str, id: &str) -> Self {
+        Self {
+            source: source.to_string(),
+            id: id.to_string(),
+            fetched: Some(jiff::Timestamp::now()),
+        }
+    }
+}
+
+pub fn get(entry: &Entry) -> Option<Provenance> {
+    let field = |name| entry.get(name).map(|chunks| chunks.format_verbatim());
+    Some(Provenance {
+        source: field(SOURCE)?,
+        id: field(SOURCE_ID)?,
+        fetched: field(FETCHED).and_then(|t| t.parse().ok()),
+    })
+}
+
+/// Removes zime's own `zime-*` fields, for entries leaving the library.
+pub fn clear(entry: &mut Entry) {
+    entry.fields.retain(|name, _| !name.starts_with("zime-"));
+}
+
+fn verbatim(s: &str) -> Vec<Spanned<Chunk>> {
+    vec![Spanned::detached(Chunk::Verbatim(s.to_string()))]
+}
+
+pub fn set(entry: &mut Entry, provenance: &Provenance) {
+    entry.set(SOURCE, verbatim(&provenance.source));
+    entry.set(SOURCE_ID, verbatim(&provenance.id));
+    match provenance.fetched {
+        Some(fetched) => entry.set(FETCHED, verbatim(&fetched.to_string())),
+        None => {
+            entry.remove(FETCHED);
+        }
+    }
+}
+
+pub fn added_by(entry: &Entry) -> Option<String> {
+    entry.get(ADDED_BY).map(|chunks| chunks.format_verbatim())
+}
+
+pub fn set_added_by(entry: &mut Entry, name: &str) {
+    entry.set(ADDED_BY, verbatim(name));
+}
+
+/// The `user.name` of the library's git repository, or the global one.
+pub fn identity(setup: &Setup) -> Option<String> {
+    cmd!("git", "config", "user.name")
+        .dir(setup.root())
+        .stderr_null()
+        .read()
+        .ok()
+        .filter(|name| !name.is_empty())
+}
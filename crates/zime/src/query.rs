@@ -0,0 +1,406 @@
+//! A small query language for filtering entries.
+//!
+//! Words match case-insensitively anywhere in the title, authors, venue, year,
+//! tags, key, DOI and abstract of an entry; `"quoted phrases"` must appear as
+//! written. Terms are combined with `AND` (implied between adjacent terms),
+//! `OR` and `NOT`, in order of increasing precedence, and can be grouped with
+//! parentheses:
+//!
+//! A term can be scoped to a single field with a prefix such as `author:`,
+//! and years can be compared or given as an inclusive range:
+//!
+//! ```text
+//! "separation logic" AND NOT concurrency
+//! (rust OR ocaml) verification
+//! author:reynolds year:>2015 venue:POPL tag:to-read
+//! title:"type inference" year:2010..2019 status:unread
+//! text:"frame rule"
+//! ```
+//!
+//! `text:` searches the downloaded PDFs, see [`crate::pdf_text`].
+
+use biblatex::{Bibliography, ChunksExt, Entry};
+use clap::ValueEnum;
+use color_eyre::eyre::{bail, eyre};
+use serde_yaml::Mapping;
+
+use crate::{
+    Result,
+    config::Setup,
+    doi, entry, group, meta, pdf_text,
+    sidecar::{ReadStatus, Sidecar},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    /// Matches every entry
+    All,
+    Term(String),
+    Field(Field, String),
+    Year(YearRange),
+    /// Read status from the sidecar, entries without one are unread
+    Status(ReadStatus),
+    /// Words in the text of the entry's PDF
+    Text(String),
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Author,
+    Title,
+    Venue,
+    Tag,
+    Key,
+    Doi,
+    Type,
+}
+
+impl Field {
+    fn from_prefix(prefix: &str) -> Option<Self> {
+        Some(match prefix {
+            "author" => Field::Author,
+            "title" => Field::Title,
+            "venue" => Field::Venue,
+            "tag" => Field::Tag,
+            "key" => Field::Key,
+            "doi" => Field::Doi,
+            "type" => Field::Type,
+            _ => return None,
+        })
+    }
+
+    /// Whether `value`, which is lowercased, matches this field of `entry`.
+    /// Tags, keys and types must match exactly, the others as a substring.
+    fn matches(self, entry: &Entry, value: &str) -> bool {
+        let contains = |text: String| text.to_lowercase().contains(value);
+        match self {
+            Field::Author => contains(entry::authors(entry)),
+            Field::Title => contains(entry::title(entry)),
+            Field::Venue => entry::venue(entry).is_some_and(contains),
+            Field::Tag => entry::tags(entry)
+                .iter()
+                .any(|tag| tag.to_lowercase() == value),
+            Field::Key => entry.key.to_lowercase() == value,
+            Field::Doi => entry
+                .doi()
+                .is_ok_and(|doi| doi::normalize(&doi) == doi::normalize(value)),
+            Field::Type => entry.entry_type.to_string().to_lowercase() == value,
+        }
+    }
+}
+
+/// An inclusive range of years, open on either side.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct YearRange {
+    pub min: Option<i32>,
+    pub max: Option<i32>,
+}
+
+impl YearRange {
+    fn parse(value: &str) -> Result<Self> {
+        let year = |s: &str| {
+            s.trim()
+                .parse::<i32>()
+                .map_err(|_| eyre!("invalid year in query: {value}"))
+        };
+        let (min, max) = if let Some(rest) = value.strip_prefix(">=") {
+            (Some(year(rest)?), None)
+        } else if let Some(rest) = value.strip_prefix("<=") {
+            (None, Some(year(rest)?))
+        } else if let Some(rest) = value.strip_prefix('>') {
+            (Some(year(rest)? + 1), None)
+        } else if let Some(rest) = value.strip_prefix('<') {
+            (None, Some(year(rest)? - 1))
+        } else if let Some((from, to)) = value.split_once("..") {
+            let bound = |s: &str| (!s.is_empty()).then(|| year(s)).transpose();
+            (bound(from)?, bound(to)?)
+        } else {
+            let year = year(value.strip_prefix('=').unwrap_or(value))?;
+            (Some(year), Some(year))
+        };
+        Ok(YearRange { min, max })
+    }
+
+    fn contains(self, year: i32) -> bool {
+        self.min.is_none_or(|min| year >= min) && self.max.is_none_or(|max| year <= max)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+    Word(String),
+}
+
+fn lex(src: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = src.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '"' => {
+                chars.next();
+                let mut phrase = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => phrase.push(c),
+                        None => bail!("unterminated quote in query: {src}"),
+                    }
+                }
+                tokens.push(Token::Word(phrase));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    chars.next();
+                    if c == '"' {
+                        // A quoted field value, as in `title:"type inference"`
+                        loop {
+                            match chars.next() {
+                                Some('"') => break,
+                                Some(c) => word.push(c),
+                                None => bail!("unterminated quote in query: {src}"),
+                            }
+                        }
+                    } else {
+                        word.push(c);
+                    }
+                }
+                tokens.push(match word.as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Word(word),
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn or(&mut self) -> Result<Query> {
+        let mut lhs = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            lhs = Query::Or(Box::new(lhs), Box::new(self.and()?));
+        }
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Query> {
+        let mut lhs = self.unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.next();
+                }
+                Some(Token::Not | Token::Open | Token::Word(_)) => {}
+                _ => break,
+            }
+            lhs = Query::And(Box::new(lhs), Box::new(self.unary()?));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Query> {
+        match self.next() {
+            Some(Token::Not) => Ok(Query::Not(Box::new(self.unary()?))),
+            Some(Token::Open) => {
+                let inner = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(inner),
+                    _ => Err(eyre!("missing closing parenthesis in query")),
+                }
+            }
+            Some(Token::Word(word)) => term(&word),
+            Some(token) => Err(eyre!("unexpected {token:?} in query")),
+            None => Err(eyre!("query ended unexpectedly")),
+        }
+    }
+}
+
+/// Parses a single word, which might be scoped to a field.
+fn term(word: &str) -> Result<Query> {
+    let Some((prefix, value)) = word.split_once(':') else {
+        return Ok(Query::Term(word.to_lowercase()));
+    };
+    if prefix == "year" {
+        return Ok(Query::Year(YearRange::parse(value)?));
+    }
+    if prefix == "text" {
+        return Ok(Query::Text(value.to_lowercase()));
+    }
+    if prefix == "status" {
+        let status = ReadStatus::from_str(value, true)
+            .map_err(|_| eyre!("unknown read status in query: {value}"))?;
+        return Ok(Query::Status(status));
+    }
+    match Field::from_prefix(prefix) {
+        Some(field) => Ok(Query::Field(field, value.to_lowercase())),
+        None => Ok(Query::Term(word.to_lowercase())),
+    }
+}
+
+impl Query {
+    pub fn parse(src: &str) -> Result<Self> {
+        let tokens = lex(src)?;
+        if tokens.is_empty() {
+            return Ok(Query::All);
+        }
+        let mut parser = Parser { tokens, pos: 0 };
+        let query = parser.or()?;
+        if let Some(token) = parser.peek() {
+            bail!("unexpected {token:?} in query");
+        }
+        Ok(query)
+    }
+
+    pub fn matches(&self, entry: &Entry) -> bool {
+        self.matches_local(entry, None, None, None)
+    }
+
+    /// Like [`Query::matches`], but also taking data kept outside the
+    /// bibliography into account: plain terms match the entry's metadata file,
+    /// `status:` matches its read status and `text:` the lowercased text of
+    /// its PDF.
+    pub fn matches_local(
+        &self,
+        entry: &Entry,
+        meta: Option<&Mapping>,
+        status: Option<ReadStatus>,
+        pdf: Option<&str>,
+    ) -> bool {
+        let mut text = searchable_text(entry);
+        if let Some(meta) = meta {
+            text.push('\n');
+            text.push_str(&meta::searchable_text(meta).to_lowercase());
+        }
+        let status = status.unwrap_or(ReadStatus::Unread);
+        self.matches_with(entry, &text, status, pdf)
+    }
+
+    fn matches_with(
+        &self,
+        entry: &Entry,
+        text: &str,
+        status: ReadStatus,
+        pdf: Option<&str>,
+    ) -> bool {
+        match self {
+            Query::All => true,
+            Query::Term(term) => text.contains(term.as_str()),
+            Query::Field(field, value) => field.matches(entry, value),
+            Query::Year(range) => entry::year(entry).is_some_and(|year| range.contains(year)),
+            Query::Status(wanted) => status == *wanted,
+            Query::Text(term) => pdf.is_some_and(|pdf| pdf.contains(term.as_str())),
+            Query::And(a, b) => {
+                a.matches_with(entry, text, status, pdf) && b.matches_with(entry, text, status, pdf)
+            }
+            Query::Or(a, b) => {
+                a.matches_with(entry, text, status, pdf) || b.matches_with(entry, text, status, pdf)
+            }
+            Query::Not(q) => !q.matches_with(entry, text, status, pdf),
+        }
+    }
+
+    /// Whether the query has a `text:` term, which needs the text of PDFs.
+    fn searches_text(&self) -> bool {
+        match self {
+            Query::Text(_) => true,
+            Query::And(a, b) | Query::Or(a, b) => a.searches_text() || b.searches_text(),
+            Query::Not(q) => q.searches_text(),
+            _ => false,
+        }
+    }
+
+    /// Whether the query only looks at the bibliography, with no `status:` or
+    /// `text:` terms.
+    pub fn bib_only(&self) -> bool {
+        match self {
+            Query::Status(_) | Query::Text(_) => false,
+            Query::And(a, b) | Query::Or(a, b) => a.bib_only() && b.bib_only(),
+            Query::Not(q) => q.bib_only(),
+            _ => true,
+        }
+    }
+
+    /// The entries of `bib` matching the query, including data kept outside
+    /// the bibliography, with the current member's read status in
+    /// reading-group mode.
+    pub fn filter<'a>(&self, setup: &Setup, bib: &'a Bibliography) -> Result<Vec<&'a Entry>> {
+        let member = group::member(setup)?;
+        let sidecar = Sidecar::load(setup)?;
+        let mut entries = Vec::new();
+        for entry in bib.iter() {
+            let meta = meta::load(setup, &entry.key)?;
+            let status = sidecar
+                .get(&entry.key)
+                .and_then(|meta| meta.status_of(member.as_deref()));
+            let pdf = match entry.doi() {
+                Ok(doi) if self.searches_text() && setup.pdf_path(&doi).exists() => Some(
+                    pdf_text::extract(&setup.pdf_path(&doi))?
+                        .text
+                        .to_lowercase(),
+                ),
+                _ => None,
+            };
+            if self.matches_local(entry, meta.as_ref(), status, pdf.as_deref()) {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Lowercased text of all searchable fields, separated by newlines so that
+/// phrases do not match across fields.
+fn searchable_text(entry: &Entry) -> String {
+    let mut fields = vec![
+        entry::title(entry),
+        entry::authors(entry),
+        entry.key.clone(),
+        entry::tags(entry).join(", "),
+    ];
+    fields.extend(entry::venue(entry));
+    fields.extend(entry::year(entry).map(|y| y.to_string()));
+    fields.extend(entry.doi().ok());
+    fields.extend(entry.abstract_().ok().map(|a| a.format_verbatim()));
+    fields.join("\n").to_lowercase()
+}
@@ -0,0 +1,131 @@
+//! The reading queue.
+//!
+//! Queued entries are stored in the sidecar (see [`crate::sidecar`]) together
+//! with their priority and optional due date, so they leave the queue when the
+//! entry is removed. The queue is ordered with entries that are due first,
+//! then by priority, due date and the time they were queued.
+
+use std::cmp::Reverse;
+
+use biblatex::{Bibliography, Entry};
+use color_eyre::{eyre::bail, owo_colors::OwoColorize};
+use jiff::civil::Date;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Result,
+    config::Setup,
+    entry, group,
+    i18n::t,
+    meta, show,
+    sidecar::{ReadStatus, Sidecar},
+};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct QueueItem {
+    /// Higher priorities are read first
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<Date>,
+    pub queued: jiff::Timestamp,
+}
+
+fn today() -> Date {
+    jiff::Zoned::now().date()
+}
+
+/// The queued entries of `bib` in reading order.
+pub fn ordered<'a>(bib: &'a Bibliography, sidecar: &'a Sidecar) -> Vec<(&'a Entry, &'a QueueItem)> {
+    let today = today();
+    let mut queue: Vec<_> = bib
+        .iter()
+        .filter_map(|entry| {
+            let item = sidecar.get(&entry.key)?.queue.as_ref()?;
+            Some((entry, item))
+        })
+        .collect();
+    queue.sort_by_key(|(_, item)| {
+        (
+            item.due.is_none_or(|due| due > today),
+            Reverse(item.priority),
+            item.due.is_none(),
+            item.due,
+            item.queued,
+        )
+    });
+    queue
+}
+
+pub fn add(setup: &Setup, entry: &Entry, priority: i32, due: Option<Date>) -> Result<()> {
+    let member = group::member(setup)?;
+    let mut sidecar = Sidecar::load(setup)?;
+    let meta = sidecar.get_mut(&entry.key);
+    let queued = meta
+        .queue
+        .as_ref()
+        .map_or_else(jiff::Timestamp::now, |item| item.queued);
+    meta.queue = Some(QueueItem {
+        priority,
+        due,
+        queued,
+    });
+    if meta.status_of(member.as_deref()).is_none() {
+        meta.set_status(member.as_deref(), ReadStatus::Unread);
+    }
+    sidecar.save(setup)
+}
+
+/// Removes an entry from the queue and marks it as read.
+pub fn done(setup: &Setup, entry: &Entry) -> Result<()> {
+    let member = group::member(setup)?;
+    let mut sidecar = Sidecar::load(setup)?;
+    let meta = sidecar.get_mut(&entry.key);
+    if meta.queue.take().is_none() {
+        bail!("{} is not in the reading queue", entry::title(entry));
+    }
+    meta.set_status(member.as_deref(), ReadStatus::Read);
+    sidecar.save(setup)
+}
+
+/// Shows what to read today: the first entry of the queue.
+pub fn next(setup: &Setup) -> Result<()> {
+    let bib = setup.bib()?;
+    let sidecar = Sidecar::load(setup)?;
+    let queue = ordered(&bib, &sidecar);
+    let Some((entry, item)) = queue.first() else {
+        println!("{}", t!("queue-empty"));
+        return Ok(());
+    };
+    if let Some(due) = item.due {
+        let when = if due < today() { "overdue" } else { "due" };
+        println!("{}\n", format!("{when} {due}").yellow());
+    }
+    let meta = meta::load(setup, &entry.key)?;
+    show::print(setup, &bib, entry, sidecar.get(&entry.key), meta.as_ref());
+    if queue.len() > 1 {
+        println!("\n{}", t!("queue-more", count = queue.len() - 1).dimmed());
+    }
+    Ok(())
+}
+
+pub fn print(setup: &Setup) -> Result<()> {
+    let bib = setup.bib()?;
+    let sidecar = Sidecar::load(setup)?;
+    for (i, (entry, item)) in ordered(&bib, &sidecar).into_iter().enumerate() {
+        let mut details = Vec::new();
+        if item.priority != 0 {
+            details.push(format!("priority {}", item.priority));
+        }
+        if let Some(due) = item.due {
+            details.push(format!("due {due}"));
+        }
+        println!(
+            "{:>3}. {} {}",
+            i + 1,
+            entry::title(entry),
+            details.join(", ").dimmed()
+        );
+    }
+    Ok(())
+}
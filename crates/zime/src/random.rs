@@ -0,0 +1,22 @@
+//! Picking a random entry, for rediscovering forgotten papers.
+
+use std::hash::{BuildHasher, RandomState};
+
+use crate::{Result, config::Setup, i18n::t, meta, query::Query, show, sidecar::Sidecar};
+
+pub fn run(setup: &Setup, query: &Query) -> Result<()> {
+    let bib = setup.bib()?;
+    let entries = query.filter(setup, &bib)?;
+    if entries.is_empty() {
+        println!("{}", t!("no-matches"));
+        return Ok(());
+    }
+    // a freshly seeded hasher is the cheapest source of randomness in std
+    let index = RandomState::new().hash_one(()) as usize % entries.len();
+    let entry = entries[index];
+
+    let sidecar = Sidecar::load(setup)?;
+    let meta = meta::load(setup, &entry.key)?;
+    show::print(setup, &bib, entry, sidecar.get(&entry.key), meta.as_ref());
+    Ok(())
+}
@@ -0,0 +1,152 @@
+//! Venue rankings such as CORE ranks or SJR quartiles, loaded from CSV files
+//! listed in the config.
+//!
+//! A dataset needs a header row with a venue column (`venue`, `title` or
+//! `name`) and a rank column (`rank`, `quartile`, `sjr best quartile` or
+//! `core rank`), and may have an `acronym` column. Both `,` and `;` separated
+//! files are accepted.
+
+use std::fs;
+
+use biblatex::Entry;
+use camino::Utf8PathBuf;
+use color_eyre::eyre::eyre;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::{Result, config::Setup, entry};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RankingConfig {
+    /// Name shown next to the rank, e.g. `CORE`
+    pub name: String,
+    /// CSV file, relative to the library root
+    pub file: Utf8PathBuf,
+}
+
+#[derive(Debug)]
+pub struct Ranking {
+    pub name: String,
+    rows: Vec<Row>,
+}
+
+#[derive(Debug)]
+struct Row {
+    venue: String,
+    acronym: Option<String>,
+    rank: String,
+}
+
+pub fn load_all(setup: &Setup) -> Result<Vec<Ranking>> {
+    setup
+        .config()?
+        .rankings
+        .iter()
+        .map(|config| Ranking::load(setup, config))
+        .collect()
+}
+
+impl Ranking {
+    pub fn load(setup: &Setup, config: &RankingConfig) -> Result<Self> {
+        let path = setup.root().join(&config.file);
+        debug!(%path, name=%config.name, "loading venue ranking");
+        let src = fs::read_to_string(&path)?;
+        let mut lines = src.lines().filter(|line| !line.trim().is_empty());
+        let header = lines.next().ok_or_else(|| eyre!("{path} is empty"))?;
+        let separator = if header.contains(';') { ';' } else { ',' };
+        let columns: Vec<String> = split(header, separator)
+            .into_iter()
+            .map(|c| c.to_lowercase())
+            .collect();
+        let column = |names: &[&str]| columns.iter().position(|c| names.contains(&c.as_str()));
+        let venue = column(&["venue", "title", "name"])
+            .ok_or_else(|| eyre!("{path} has no venue column"))?;
+        let rank = column(&["rank", "quartile", "sjr best quartile", "core rank"])
+            .ok_or_else(|| eyre!("{path} has no rank column"))?;
+        let acronym = column(&["acronym", "abbreviation"]);
+
+        let rows = lines
+            .filter_map(|line| {
+                let fields = split(line, separator);
+                Some(Row {
+                    venue: normalize(fields.get(venue)?),
+                    acronym: acronym
+                        .and_then(|i| fields.get(i))
+                        .filter(|a| !a.is_empty())
+                        .cloned(),
+                    rank: fields.get(rank)?.clone(),
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            name: config.name.clone(),
+            rows,
+        })
+    }
+
+    /// The rank of the venue the entry was published in.
+    ///
+    /// Venues match if their full names are equal, or if the acronym appears
+    /// as a word in the entry's venue, as in DBLP's `POPL 2019` style titles.
+    pub fn lookup(&self, entry: &Entry) -> Option<&str> {
+        let venue = entry::venue(entry)?;
+        let normalized = normalize(&venue);
+        let words: Vec<&str> = venue
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect();
+        self.rows
+            .iter()
+            .find(|row| row.venue == normalized)
+            .or_else(|| {
+                self.rows.iter().find(|row| {
+                    row.acronym
+                        .as_deref()
+                        .is_some_and(|acronym| words.contains(&acronym))
+                })
+            })
+            .map(|row| row.rank.as_str())
+    }
+}
+
+/// The ranks of an entry in all datasets, formatted as `CORE: A*, SJR: Q1`.
+pub fn describe(rankings: &[Ranking], entry: &Entry) -> Option<String> {
+    let ranks: Vec<String> = rankings
+        .iter()
+        .filter_map(|ranking| Some(format!("{}: {}", ranking.name, ranking.lookup(entry)?)))
+        .collect();
+    (!ranks.is_empty()).then(|| ranks.join(", "))
+}
+
+fn normalize(venue: &str) -> String {
+    venue
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Splits a CSV line, honoring double-quoted fields.
+fn split(line: &str, separator: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => fields.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    fields.push(current);
+    fields.into_iter().map(|f| f.trim().to_string()).collect()
+}
@@ -0,0 +1,62 @@
+//! Recommending papers related to an entry, from Semantic Scholar, for
+//! exploring the literature around a paper without leaving zime.
+//!
+//! The recommendations are listed until the user is done, and each one picked
+//! is added to the library right away, the same way `zime index` adds it.
+
+use biblatex::Entry;
+use color_eyre::{eyre::eyre, owo_colors::OwoColorize};
+use itertools::Itertools;
+
+use crate::{Result, config::Setup, i18n::t, index, prompt, remotes::semanticscholar};
+
+pub fn run(setup: &Setup, entry: &Entry, limit: usize) -> Result<()> {
+    let id = semanticscholar::paper_id(entry)
+        .ok_or_else(|| eyre!(t!("recommend-no-id", key = entry.key.as_str())))?;
+    let bib = setup.bib()?;
+    let spinner = prompt::spinner();
+    spinner.start(t!("looking-up"));
+    let papers = semanticscholar::recommend(&id, limit)?;
+    spinner.stop("");
+
+    let mut candidates = papers
+        .into_iter()
+        .map(|paper| paper.candidate())
+        .filter(|candidate| !index::in_library(&bib, candidate))
+        .collect_vec();
+    if candidates.is_empty() {
+        println!("{}", t!("recommend-none", key = entry.key.as_str()));
+        return Ok(());
+    }
+
+    let mut added = 0;
+    while !candidates.is_empty() {
+        let mut items = candidates
+            .iter()
+            .map(|candidate| {
+                (
+                    format!(
+                        "{} ({})",
+                        candidate.title.bold(),
+                        candidate.authors.italic()
+                    ),
+                    candidate
+                        .doi
+                        .as_deref()
+                        .map(|doi| format!("DOI: {doi}"))
+                        .unwrap_or_default(),
+                )
+            })
+            .collect_vec();
+        items.push((t!("recommend-done").dimmed().to_string(), String::new()));
+        let selection =
+            prompt::select(t!("select-recommendation-to-add", count = added), items, 0)?;
+        if selection == candidates.len() {
+            break;
+        }
+        let candidate = candidates.remove(selection);
+        index::add_candidate(setup, &candidate)?;
+        added += 1;
+    }
+    Ok(())
+}
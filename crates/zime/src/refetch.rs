@@ -0,0 +1,107 @@
+//! Re-downloading the record an entry was originally fetched from and
+//! applying selected changes to the local entry.
+
+use biblatex::Entry;
+use color_eyre::eyre::eyre;
+
+use crate::{
+    Result,
+    config::Setup,
+    consistency,
+    diff::{self, FieldChange},
+    i18n::t,
+    prompt,
+    provenance::{self, Provenance},
+    remotes, select,
+    transaction::Transaction,
+};
+
+/// Downloads the record described by `provenance`.
+pub fn fetch_record(provenance: &Provenance) -> Result<Entry> {
+    let remote = remotes::get(&provenance.source)
+        .filter(|remote| remote.capabilities().fetch_entry)
+        .ok_or_else(|| eyre!("refetching from {} is not supported", provenance.source))?;
+    remote.fetch_entry(&provenance.id)
+}
+
+/// Differences between the local entry and the remote record, ignoring zime's
+/// own fields which the remote never has.
+pub fn changes(local: &Entry, remote: &Entry) -> Vec<FieldChange> {
+    diff::diff_entries(local, remote)
+        .into_iter()
+        .filter(|change| !change.name.starts_with("zime-"))
+        .collect()
+}
+
+/// Applies the given changes to `entry`.
+pub fn apply(entry: &mut Entry, remote: &Entry, changes: &[&FieldChange]) {
+    for change in changes {
+        if change.name == "type" {
+            entry.entry_type = remote.entry_type.clone();
+        } else if let Some(value) = remote.fields.get(&change.name) {
+            entry.set(&change.name, value.clone());
+        } else {
+            entry.remove(&change.name);
+        }
+    }
+}
+
+pub fn run(setup: &Setup, query: &str, yes: bool) -> Result<()> {
+    let mut bib = setup.bib()?;
+    let entry = select::find_one(&bib, query, &t!("select-article-to-refetch"))?;
+    let provenance = provenance::get(entry)
+        .ok_or_else(|| eyre!("{} has no recorded source to refetch from", entry.key))?;
+
+    let spinner = prompt::spinner();
+    spinner.start(t!(
+        "fetching-record",
+        id = provenance.id.as_str(),
+        source = provenance.source.as_str(),
+    ));
+    let remote = fetch_record(&provenance)?;
+    spinner.stop("");
+
+    let key = entry.key.clone();
+    let changes = changes(entry, &remote);
+    if changes.is_empty() {
+        println!(
+            "{}",
+            t!(
+                "record-unchanged",
+                key = key,
+                source = provenance.source,
+                id = provenance.id,
+            )
+        );
+        return Ok(());
+    }
+    diff::print_fields(&changes);
+
+    let selected: Vec<&FieldChange> = if yes {
+        changes.iter().collect()
+    } else {
+        prompt::multiselect(
+            t!("select-changes"),
+            changes.iter().map(|change| (&change.name, "")),
+            &(0..changes.len()).collect::<Vec<_>>(),
+        )?
+        .into_iter()
+        .map(|i| &changes[i])
+        .collect()
+    };
+    if selected.is_empty() {
+        return Ok(());
+    }
+
+    let before = bib.clone();
+    let mut tx = Transaction::begin(setup)?;
+    let entry = bib.get_mut(&key).unwrap();
+    apply(entry, &remote, &selected);
+    provenance::set(entry, &Provenance::new(&provenance.source, &provenance.id));
+    // A corrected DOI renames the PDF
+    consistency::apply(setup, &mut tx, &before, &mut bib, &Default::default())?;
+    setup.write_bib(&bib)?;
+    setup.sync_git()?;
+    tx.commit();
+    Ok(())
+}
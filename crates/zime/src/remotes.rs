@@ -1,3 +1,241 @@
+//! The remote sources zime fetches metadata and PDFs from.
+//!
+//! Every source implements [`Remote`] with the capabilities it has, and is
+//! listed in [`REMOTES`], which `index`, `refetch` and `pdfs` look remotes up
+//! in by name.
+
+use biblatex::{Bibliography, Entry};
+use color_eyre::eyre::eyre;
+use tracing::debug;
+
+use crate::{Result, pdfs::FailureReason, provenance::Provenance, venues::Venue};
+
+pub mod acm;
 pub mod arxiv;
+pub mod biorxiv;
+pub mod core;
+pub mod crossref;
 pub mod dblp;
+pub mod doi;
+pub mod eventdata;
+pub mod grobid;
+pub mod hal;
+pub mod ieee;
+pub mod inspire;
+pub mod openalex;
+pub mod opencitations;
+pub mod orcid;
+pub mod proxy;
+pub mod pubmed;
+pub mod record;
 pub mod scihub;
+pub mod semanticscholar;
+pub mod ssrn;
+pub mod unpaywall;
+pub mod zbmath;
+
+/// A work most remotes know, asked for by [`Remote::ping`]: Lamport's "Time,
+/// clocks, and the ordering of events in a distributed system".
+pub const PING_DOI: &str = "10.1145/359545.359563";
+
+/// Error for when a remote responded, but does not have the requested document.
+#[derive(Debug)]
+pub struct NotAvailable(pub String);
+
+impl std::fmt::Display for NotAvailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for NotAvailable {}
+
+/// What a remote can do, see [`Remote`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Capabilities {
+    /// Searching for works with [`Remote::search`]
+    pub search: bool,
+    /// Fetching entries by their id in provenance with [`Remote::fetch_entry`]
+    pub fetch_entry: bool,
+    /// Which PDFs [`Remote::fetch_pdf`] is tried for
+    pub pdfs: Pdfs,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Pdfs {
+    #[default]
+    None,
+    /// Only those of works the remote hosts, see [`Remote::hosts`]
+    Hosted,
+    /// Those of any DOI
+    Any,
+}
+
+/// A search result, identified by the remote record it can be fetched from.
+#[derive(Debug)]
+pub struct Candidate {
+    pub title: String,
+    pub authors: String,
+    pub doi: Option<String>,
+    pub provenance: Provenance,
+}
+
+/// A page of search results.
+#[derive(Debug)]
+pub struct Page {
+    pub candidates: Vec<Candidate>,
+    /// Whether the remote has more results after this page
+    pub more: bool,
+}
+
+impl Page {
+    /// All results of a remote that is not searched a page at a time.
+    pub fn all(candidates: impl IntoIterator<Item = Candidate>) -> Self {
+        Page {
+            candidates: candidates.into_iter().collect(),
+            more: false,
+        }
+    }
+}
+
+pub trait Remote: Sync {
+    /// The name of the remote in provenance and on the command line
+    fn name(&self) -> &'static str;
+
+    /// Other names accepted on the command line
+    fn aliases(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// What the remote covers, shown in `--help`
+    fn description(&self) -> &'static str;
+
+    fn capabilities(&self) -> Capabilities;
+
+    /// Searches for works matching `query`, within `venue` if the remote
+    /// supports it, starting at the result `first`.
+    fn search(&self, _query: &str, _venue: Option<&Venue>, _first: usize) -> Result<Page> {
+        Err(self.unsupported("searching"))
+    }
+
+    /// Fetches the record with the given id, as in the provenance of entries.
+    fn fetch_entry(&self, _id: &str) -> Result<Entry> {
+        Err(self.unsupported("fetching records"))
+    }
+
+    /// Whether the work with `doi` is hosted by this remote, e.g. an arXiv
+    /// preprint by arXiv.
+    fn hosts(&self, _doi: &str) -> bool {
+        false
+    }
+
+    fn fetch_pdf(&self, _doi: &str) -> Result<Vec<u8>> {
+        Err(self.unsupported("fetching PDFs"))
+    }
+
+    /// The setting the remote needs but is not given, e.g. `http.mailto`.
+    fn missing_config(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Checks that the remote answers and accepts the configured credentials,
+    /// for `zime remotes status`. Remotes that can be searched are searched.
+    fn ping(&self) -> Result<()> {
+        if !self.capabilities().search {
+            return Err(self.unsupported("checking the status"));
+        }
+        self.search("zime", None, 0).map(drop)
+    }
+
+    fn unsupported(&self, what: &str) -> color_eyre::eyre::Error {
+        eyre!("{what} is not supported by {}", self.name())
+    }
+}
+
+/// Every remote. `index` lists the searchable ones in this order, and PDFs
+/// are tried in this order, preferring legal open access copies, then the
+/// institutional proxy, and using Sci-Hub only as a last resort.
+pub static REMOTES: &[&dyn Remote] = &[
+    &dblp::Dblp,
+    &semanticscholar::SemanticScholar,
+    &openalex::OpenAlex,
+    &arxiv::Arxiv,
+    &pubmed::Pubmed,
+    &zbmath::Zbmath,
+    &inspire::Inspire,
+    &unpaywall::Unpaywall,
+    &hal::Hal,
+    &core::Core,
+    &ssrn::Ssrn,
+    &ieee::Ieee,
+    &acm::Acm,
+    &biorxiv::Biorxiv,
+    &crossref::Crossref,
+    &doi::Doi,
+    &orcid::Orcid,
+    &grobid::Grobid,
+    &proxy::Proxy,
+    &scihub::SciHub,
+];
+
+/// The remote with the given name or alias.
+pub fn get(name: &str) -> Option<&'static dyn Remote> {
+    REMOTES
+        .iter()
+        .copied()
+        .find(|remote| remote.name() == name || remote.aliases().contains(&name))
+}
+
+/// The remotes with the capabilities `filter` accepts.
+pub fn with(filter: impl Fn(Capabilities) -> bool) -> impl Iterator<Item = &'static dyn Remote> {
+    REMOTES
+        .iter()
+        .copied()
+        .filter(move |remote| filter(remote.capabilities()))
+}
+
+/// Fetches the PDF of `doi` from the remote hosting the work, or else from the
+/// first remote that has it. When none has it, the failure reported is that of
+/// the first remote that answered, as the remotes are tried from the most to
+/// the least authoritative and a remote being unreachable says nothing about
+/// the work, with what every remote said in its message.
+pub fn fetch_pdf(doi: &str) -> (&'static str, Result<Vec<u8>>) {
+    let hosted = with(|c| c.pdfs == Pdfs::Hosted).find(|remote| remote.hosts(doi));
+    if let Some(remote) = hosted {
+        return (remote.name(), remote.fetch_pdf(doi));
+    }
+    let mut failures = Vec::new();
+    for remote in with(|c| c.pdfs == Pdfs::Any) {
+        match remote.fetch_pdf(doi) {
+            Ok(pdf) => return (remote.name(), Ok(pdf)),
+            Err(err) => {
+                debug!(%doi, source = remote.name(), %err, "no PDF");
+                failures.push((remote.name(), err));
+            }
+        }
+    }
+    let all = failures
+        .iter()
+        .map(|(name, err)| format!("{name}: {err}"))
+        .collect::<Vec<_>>()
+        .join("; ");
+    let count = failures.len();
+    let reported = failures
+        .iter()
+        .position(|(_, err)| FailureReason::classify(err) != FailureReason::Network)
+        .unwrap_or(0);
+    match failures.into_iter().nth(reported) {
+        Some((name, err)) if count > 1 => (name, Err(err.wrap_err(all))),
+        Some((name, err)) => (name, Err(err)),
+        None => ("none", Err(eyre!("no remote fetches PDFs"))),
+    }
+}
+
+/// The first entry of a bibliography fetched from `remote` for `id`.
+pub fn parse_entry(src: &str, remote: &str, id: &str) -> Result<Entry> {
+    Bibliography::parse(src)
+        .map_err(|err| eyre!("failed to parse bibliography entry: {err}"))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| eyre!("{remote} returned no entry for {id}"))
+}
@@ -0,0 +1,58 @@
+//! URL: `https://dl.acm.org/action/downloadCitation?doi={doi}&format=bibTex`
+//!
+//! The ACM Digital Library exports its own BibTeX for works with an ACM DOI,
+//! which unlike DBLP's records includes the abstract, the article number and
+//! the publisher's address. The keys are the DOIs themselves.
+
+use biblatex::Entry;
+
+use crate::{
+    Result, doi, http,
+    remotes::{Capabilities, PING_DOI, Remote, parse_entry},
+};
+
+pub fn is_acm(id: &str) -> bool {
+    doi::normalize(id).starts_with("10.1145/")
+}
+
+/// Downloads ACM's BibTeX export of the work with the given DOI.
+pub fn fetch_bib(id: &str) -> Result<String> {
+    http::ACM
+        .get("https://dl.acm.org/action/downloadCitation")
+        .query(&[
+            ("doi", doi::normalize(id).as_str()),
+            ("format", "bibTex"),
+            ("include", "abs"),
+        ])
+        .send()?
+        .error_for_status()?
+        .text()
+        .map_err(Into::into)
+}
+
+pub struct Acm;
+
+impl Remote for Acm {
+    fn name(&self) -> &'static str {
+        "acm"
+    }
+
+    fn description(&self) -> &'static str {
+        "The ACM Digital Library, by DOI"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            fetch_entry: true,
+            ..Default::default()
+        }
+    }
+
+    fn fetch_entry(&self, id: &str) -> Result<Entry> {
+        parse_entry(&fetch_bib(id)?, self.name(), id)
+    }
+
+    fn ping(&self) -> Result<()> {
+        fetch_bib(PING_DOI).map(drop)
+    }
+}
@@ -1,27 +1,229 @@
+//! URLs: `https://export.arxiv.org/api/query?search_query={query}` for
+//! metadata, `https://arxiv.org/pdf/{id}.pdf` for PDFs.
+
+use biblatex::Entry;
 use color_eyre::eyre::eyre;
 
-use crate::Result;
+use crate::{
+    Result, doi, http,
+    provenance::Provenance,
+    remotes::{
+        Candidate, Capabilities, Page, Pdfs, Remote,
+        record::{Author, Record},
+    },
+    venues::Venue,
+};
 
-const USER_AGENT: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 11_3_1 like Mac OS X) AppleWebKit/603.1.30 (KHTML, like Gecko) Version/10.0 Mobile/14E304 Safari/602.1";
+const API: &str = "https://export.arxiv.org/api/query";
+const ATOM: &str = "http://www.w3.org/2005/Atom";
+const ARXIV: &str = "http://arxiv.org/schemas/atom";
 
 pub fn is_arxiv(doi: &str) -> bool {
-    doi.contains("/ARXIV.")
+    doi::normalize(doi).contains("/arxiv.")
+}
+
+/// The arXiv identifier of an arXiv DOI, e.g. `2103.03230` for
+/// `10.48550/arXiv.2103.03230`.
+pub fn id(doi: &str) -> Option<String> {
+    doi::normalize(doi)
+        .split_once("/arxiv.")
+        .map(|(_, id)| id.to_string())
 }
 
 /// Fetches a PDF from arXiv given a DOI.
 ///
 /// For example, `fetch_pdf("10.48550/ARXIV.2207.0282")` will fetch the PDF from `https://arxiv.org/pdf/2103.03230.pdf`.
 pub fn fetch_pdf(doi: &str) -> Result<Vec<u8>> {
-    let id = doi
-        .split_once("/ARXIV.")
-        .map(|(_, id)| id)
-        .ok_or_else(|| eyre!("Invalid arXiv DOI"))?;
+    let id = id(doi).ok_or_else(|| eyre!("Invalid arXiv DOI"))?;
     let url = format!("https://arxiv.org/pdf/{id}.pdf");
-    let response = reqwest::blocking::Client::builder()
-        .user_agent(USER_AGENT)
-        .build()?
-        .get(&url)
-        .send()?;
+    let response = http::ARXIV.get(&url).send()?.error_for_status()?;
     let body = response.bytes()?;
     Ok(body.to_vec())
 }
+
+/// A paper as described by the arXiv API.
+#[derive(Debug, Clone)]
+pub struct Paper {
+    /// The arXiv identifier without version, e.g. `2103.03230`
+    pub id: String,
+    pub title: String,
+    pub authors: Vec<String>,
+    pub summary: String,
+    pub year: Option<i32>,
+    /// Where the paper was published, if the authors gave it
+    pub journal_ref: Option<String>,
+    /// For example `cs.PL`
+    pub category: Option<String>,
+    /// The latest version, e.g. `2` for `2103.03230v2`
+    pub version: Option<u32>,
+    /// When the latest version was submitted
+    pub updated: Option<jiff::Timestamp>,
+}
+
+/// Searches arXiv, see the [query syntax](https://info.arxiv.org/help/api/user-manual.html#query_details).
+/// Plain words are searched for in all fields.
+pub fn search(query: &str) -> Result<Vec<Paper>> {
+    let query = if query.contains(':') {
+        query.to_string()
+    } else {
+        query
+            .split_whitespace()
+            .map(|word| format!("all:{word}"))
+            .collect::<Vec<_>>()
+            .join(" AND ")
+    };
+    let feed = http::ARXIV
+        .get(API)
+        .query(&[("search_query", query.as_str()), ("max_results", "20")])
+        .send()?
+        .error_for_status()?
+        .text()?;
+    parse_feed(&feed)
+}
+
+/// Fetches the papers with the given arXiv identifiers, a batch at a time.
+pub fn fetch_many(ids: &[String]) -> Result<Vec<Paper>> {
+    let mut papers = Vec::new();
+    for batch in ids.chunks(50) {
+        let feed = http::ARXIV
+            .get(API)
+            .query(&[
+                ("id_list", batch.join(",")),
+                ("max_results", batch.len().to_string()),
+            ])
+            .send()?
+            .error_for_status()?
+            .text()?;
+        papers.extend(parse_feed(&feed)?);
+    }
+    Ok(papers)
+}
+
+/// Fetches a paper by its arXiv identifier.
+pub fn fetch(id: &str) -> Result<Paper> {
+    let feed = http::ARXIV
+        .get(API)
+        .query(&[("id_list", id)])
+        .send()?
+        .error_for_status()?
+        .text()?;
+    parse_feed(&feed)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| eyre!("arXiv has no paper {id}"))
+}
+
+fn parse_feed(feed: &str) -> Result<Vec<Paper>> {
+    let doc = roxmltree::Document::parse(feed)?;
+    let child_text = |node: roxmltree::Node, ns: &str, name: &str| {
+        node.children()
+            .find(|c| c.has_tag_name((ns, name)))
+            .and_then(|c| c.text())
+            .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+    };
+    let papers = doc
+        .root_element()
+        .children()
+        .filter(|node| node.has_tag_name((ATOM, "entry")))
+        .filter_map(|entry| {
+            // `http://arxiv.org/abs/2103.03230v1`
+            let url = child_text(entry, ATOM, "id")?;
+            let id = url.rsplit_once("/abs/")?.1;
+            let (id, version) = match id.rsplit_once('v') {
+                Some((id, version)) if version.chars().all(|c| c.is_ascii_digit()) => {
+                    (id, version.parse().ok())
+                }
+                _ => (id, None),
+            };
+            Some(Paper {
+                id: id.to_string(),
+                title: child_text(entry, ATOM, "title").unwrap_or_default(),
+                authors: entry
+                    .children()
+                    .filter(|c| c.has_tag_name((ATOM, "author")))
+                    .filter_map(|author| child_text(author, ATOM, "name"))
+                    .collect(),
+                summary: child_text(entry, ATOM, "summary").unwrap_or_default(),
+                year: child_text(entry, ATOM, "published")
+                    .and_then(|date| date.get(..4)?.parse().ok()),
+                journal_ref: child_text(entry, ARXIV, "journal_ref"),
+                category: entry
+                    .children()
+                    .find(|c| c.has_tag_name((ARXIV, "primary_category")))
+                    .and_then(|c| c.attribute("term"))
+                    .map(str::to_string),
+                version,
+                updated: child_text(entry, ATOM, "updated").and_then(|date| date.parse().ok()),
+            })
+        })
+        .collect();
+    Ok(papers)
+}
+
+impl Paper {
+    /// The DOI arXiv assigns to the paper, under which zime finds its PDF.
+    pub fn arxiv_doi(&self) -> String {
+        format!("10.48550/arXiv.{}", self.id)
+    }
+
+    pub fn record(&self) -> Record {
+        Record {
+            entry_type: "misc",
+            title: self.title.clone(),
+            authors: self
+                .authors
+                .iter()
+                .map(|name| Author::from_full_name(name))
+                .collect(),
+            year: self.year,
+            venue: self.journal_ref.clone(),
+            doi: Some(self.arxiv_doi()),
+            abstract_: Some(self.summary.clone()),
+            eprint: Some((self.id.clone(), self.category.clone())),
+            ..Default::default()
+        }
+    }
+}
+
+pub struct Arxiv;
+
+impl Remote for Arxiv {
+    fn name(&self) -> &'static str {
+        "arxiv"
+    }
+
+    fn description(&self) -> &'static str {
+        "arXiv preprints"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            search: true,
+            fetch_entry: true,
+            pdfs: Pdfs::Hosted,
+        }
+    }
+
+    fn search(&self, query: &str, _venue: Option<&Venue>, _first: usize) -> Result<Page> {
+        Ok(Page::all(search(query)?.into_iter().map(|paper| {
+            Candidate {
+                authors: paper.authors.join(", "),
+                doi: Some(paper.arxiv_doi()),
+                provenance: Provenance::new("arxiv", &paper.id),
+                title: paper.title,
+            }
+        })))
+    }
+
+    fn fetch_entry(&self, id: &str) -> Result<Entry> {
+        Ok(fetch(id)?.record().into_entry())
+    }
+
+    fn hosts(&self, doi: &str) -> bool {
+        is_arxiv(doi)
+    }
+
+    fn fetch_pdf(&self, doi: &str) -> Result<Vec<u8>> {
+        fetch_pdf(doi)
+    }
+}
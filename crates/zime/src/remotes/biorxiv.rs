@@ -0,0 +1,149 @@
+//! URLs: `https://api.biorxiv.org/details/{server}/{doi}` for metadata,
+//! `https://www.{server}.org/content/{doi}v{version}.full.pdf` for PDFs.
+//!
+//! bioRxiv and medRxiv share the `10.1101` DOI prefix and an API, so a DOI is
+//! looked up on bioRxiv first and on medRxiv if bioRxiv does not have it.
+
+use biblatex::Entry;
+use color_eyre::eyre::eyre;
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::{
+    Result, doi, http,
+    remotes::{
+        Capabilities, Pdfs, Remote,
+        record::{Author, Record},
+    },
+};
+
+const SERVERS: [&str; 2] = ["biorxiv", "medrxiv"];
+
+/// Whether the DOI was registered by bioRxiv or medRxiv.
+pub fn is_biorxiv(doi: &str) -> bool {
+    doi::normalize(doi).starts_with("10.1101/")
+}
+
+#[derive(Debug, Deserialize)]
+struct Details {
+    #[serde(default)]
+    collection: Vec<Preprint>,
+}
+
+/// A version of a preprint as described by the API.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Preprint {
+    pub doi: String,
+    pub title: String,
+    /// For example `Doe, J.; Smith, A. B.`
+    pub authors: String,
+    /// Date the version was posted, e.g. `2020-03-01`
+    pub date: String,
+    pub version: String,
+    #[serde(rename = "abstract")]
+    pub abstract_: Option<String>,
+    pub server: Option<String>,
+}
+
+/// Fetches the latest version of the preprint with the given DOI, and the
+/// server that has it.
+pub fn fetch(id: &str) -> Result<(&'static str, Preprint)> {
+    let id = doi::normalize(id);
+    for server in SERVERS {
+        let details: Details = http::BIORXIV
+            .get(format!("https://api.biorxiv.org/details/{server}/{id}"))
+            .send()?
+            .error_for_status()?
+            .json()?;
+        // versions are listed oldest first
+        if let Some(preprint) = details.collection.into_iter().last() {
+            return Ok((server, preprint));
+        }
+        debug!(server, doi=%id, "preprint not found");
+    }
+    Err(eyre!("neither bioRxiv nor medRxiv has {id}"))
+}
+
+/// Fetches the PDF of the latest version of a preprint given its DOI.
+pub fn fetch_pdf(doi: &str) -> Result<Vec<u8>> {
+    let (server, preprint) = fetch(doi)?;
+    let url = format!(
+        "https://www.{server}.org/content/{}v{}.full.pdf",
+        preprint.doi, preprint.version
+    );
+    let response = http::BIORXIV.get(&url).send()?.error_for_status()?;
+    Ok(response.bytes()?.to_vec())
+}
+
+impl Preprint {
+    pub fn record(&self) -> Record {
+        let server = match self.server.as_deref() {
+            Some("medrxiv" | "medRxiv") => "medRxiv",
+            _ => "bioRxiv",
+        };
+        Record {
+            entry_type: "misc",
+            title: self.title.clone(),
+            authors: self
+                .authors
+                .split(';')
+                .filter(|name| !name.trim().is_empty())
+                .map(|name| match name.split_once(',') {
+                    Some((family, given)) => Author {
+                        given: given.trim().to_string(),
+                        family: family.trim().to_string(),
+                    },
+                    None => Author::organization(name),
+                })
+                .collect(),
+            year: self.date.get(..4).and_then(|year| year.parse().ok()),
+            venue: Some(server.to_string()),
+            publisher: Some("Cold Spring Harbor Laboratory".to_string()),
+            doi: Some(self.doi.clone()),
+            abstract_: self.abstract_.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+pub struct Biorxiv;
+
+impl Remote for Biorxiv {
+    fn name(&self) -> &'static str {
+        "biorxiv"
+    }
+
+    fn description(&self) -> &'static str {
+        "bioRxiv and medRxiv preprints, by DOI"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            fetch_entry: true,
+            pdfs: Pdfs::Hosted,
+            ..Default::default()
+        }
+    }
+
+    fn fetch_entry(&self, id: &str) -> Result<Entry> {
+        Ok(fetch(id)?.1.record().into_entry())
+    }
+
+    fn hosts(&self, doi: &str) -> bool {
+        is_biorxiv(doi)
+    }
+
+    fn fetch_pdf(&self, doi: &str) -> Result<Vec<u8>> {
+        fetch_pdf(doi)
+    }
+
+    /// Asks for the first preprint of 2020, as bioRxiv's API has no cheaper
+    /// request.
+    fn ping(&self) -> Result<()> {
+        http::BIORXIV
+            .get("https://api.biorxiv.org/details/biorxiv/2020-01-01/2020-01-01/0")
+            .send()?
+            .error_for_status()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+//! URL: `https://api.core.ac.uk/v3/search/works?q=doi:"{doi}"`
+//!
+//! CORE aggregates institutional repositories, which hold accepted
+//! manuscripts of many paywalled papers. Its API requires a key, set as
+//! `http.core_api_key` in the global config.
+
+use color_eyre::eyre::eyre;
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::{
+    Result, doi, http,
+    remotes::{Capabilities, NotAvailable, PING_DOI, Pdfs, Remote},
+};
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    results: Vec<Work>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Work {
+    download_url: Option<String>,
+}
+
+/// Searches CORE for copies of the work with the given DOI.
+fn search(id: &str, limit: usize) -> Result<SearchResponse> {
+    let key = http::core_api_key().ok_or_else(|| eyre!("CORE requires http.core_api_key"))?;
+    Ok(http::CORE
+        .get("https://api.core.ac.uk/v3/search/works")
+        .bearer_auth(key)
+        .query(&[
+            ("q", format!("doi:\"{}\"", doi::normalize(id)).as_str()),
+            ("limit", limit.to_string().as_str()),
+        ])
+        .send()?
+        .error_for_status()?
+        .json()?)
+}
+
+/// Fetches a repository copy of the work with the given DOI.
+pub fn fetch_pdf(id: &str) -> Result<Vec<u8>> {
+    let response = search(id, 5)?;
+    for url in response
+        .results
+        .iter()
+        .filter_map(|work| work.download_url.as_deref())
+    {
+        debug!(url, "fetching PDF from CORE");
+        let pdf = http::CORE.get(url).send()?.error_for_status()?.bytes()?;
+        if pdf.starts_with(b"%PDF") {
+            return Ok(pdf.to_vec());
+        }
+    }
+    Err(NotAvailable("CORE has no copy of this work".to_string()).into())
+}
+
+pub struct Core;
+
+impl Remote for Core {
+    fn name(&self) -> &'static str {
+        "core"
+    }
+
+    fn description(&self) -> &'static str {
+        "CORE, open access research outputs"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            pdfs: Pdfs::Any,
+            ..Default::default()
+        }
+    }
+
+    fn fetch_pdf(&self, doi: &str) -> Result<Vec<u8>> {
+        fetch_pdf(doi)
+    }
+
+    fn missing_config(&self) -> Option<&'static str> {
+        http::core_api_key()
+            .is_none()
+            .then_some("http.core_api_key")
+    }
+
+    fn ping(&self) -> Result<()> {
+        search(PING_DOI, 1).map(drop)
+    }
+}
@@ -0,0 +1,254 @@
+//! URLs: `https://api.crossref.org/works/{doi}` for a single work,
+//! `https://api.crossref.org/works?query.bibliographic={query}` for searching.
+
+use biblatex::Entry;
+use itertools::Itertools;
+
+use crate::{
+    Result, doi, http,
+    provenance::Provenance,
+    remotes::{
+        Candidate, Capabilities, Page, Remote,
+        record::{Author, Record},
+    },
+    venues::Venue,
+};
+
+/// Number of works fetched per page of a search.
+const PAGE_SIZE: usize = 20;
+
+pub fn fetch_work(doi: &str) -> Result<response::Work> {
+    let response: response::Response = http::CROSSREF
+        .get(format!(
+            "https://api.crossref.org/works/{}",
+            doi::url_path(doi)
+        ))
+        .send()?
+        .error_for_status()?
+        .json()?;
+    Ok(response.message)
+}
+
+/// One page of works matching `query`, starting at the work `offset`.
+pub fn search(query: &str, offset: usize) -> Result<response::Items> {
+    let response: response::SearchResponse = http::CROSSREF
+        .get("https://api.crossref.org/works")
+        .query(&[
+            ("query.bibliographic", query),
+            ("rows", &PAGE_SIZE.to_string()),
+            ("offset", &offset.to_string()),
+        ])
+        .send()?
+        .error_for_status()?
+        .json()?;
+    Ok(response.message)
+}
+
+impl response::Work {
+    pub fn record(&self) -> Record {
+        let entry_type = match self.work_type.as_str() {
+            "journal-article" => "article",
+            "proceedings-article" => "inproceedings",
+            "book" | "monograph" | "edited-book" => "book",
+            "book-chapter" | "book-section" | "book-part" => "incollection",
+            "report" => "techreport",
+            "dissertation" => "phdthesis",
+            _ => "misc",
+        };
+        Record {
+            entry_type,
+            title: self.title.first().cloned().unwrap_or_default(),
+            authors: self
+                .author
+                .iter()
+                .map(
+                    |author| match (&author.given, &author.family, &author.name) {
+                        (_, None, Some(name)) => Author::organization(name),
+                        (given, family, _) => Author {
+                            given: given.clone().unwrap_or_default(),
+                            family: family.clone().unwrap_or_default(),
+                        },
+                    },
+                )
+                .collect(),
+            year: self
+                .issued
+                .as_ref()
+                .and_then(|date| date.date_parts.first())
+                .and_then(|parts| parts.first().copied()),
+            venue: self.container_title.first().cloned(),
+            volume: self.volume.clone(),
+            number: self.issue.clone(),
+            pages: self.page.clone(),
+            publisher: self.publisher.clone(),
+            doi: Some(self.doi.clone()),
+            ..Default::default()
+        }
+    }
+}
+
+pub struct Crossref;
+
+impl Remote for Crossref {
+    fn name(&self) -> &'static str {
+        "crossref"
+    }
+
+    fn description(&self) -> &'static str {
+        "Crossref, covering works with a DOI"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            search: true,
+            fetch_entry: true,
+            ..Default::default()
+        }
+    }
+
+    fn search(&self, query: &str, _venue: Option<&Venue>, first: usize) -> Result<Page> {
+        let items = search(query, first)?;
+        Ok(Page {
+            more: first + items.items.len() < items.total_results,
+            candidates: items
+                .items
+                .iter()
+                .map(|work| Candidate {
+                    title: work.title.first().cloned().unwrap_or_default(),
+                    authors: work
+                        .author
+                        .iter()
+                        .map(|a| {
+                            [&a.given, &a.family, &a.name]
+                                .into_iter()
+                                .flatten()
+                                .join(" ")
+                        })
+                        .join(", "),
+                    doi: Some(work.doi.clone()),
+                    provenance: Provenance::new("crossref", &doi::normalize(&work.doi)),
+                })
+                .collect(),
+        })
+    }
+
+    fn fetch_entry(&self, id: &str) -> Result<Entry> {
+        Ok(fetch_work(id)?.record().into_entry())
+    }
+}
+
+pub mod response {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct Response {
+        pub message: Work,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct SearchResponse {
+        pub message: Items,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct Items {
+        #[serde(default)]
+        pub items: Vec<Work>,
+        #[serde(default)]
+        pub total_results: usize,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct Work {
+        #[serde(rename = "DOI")]
+        pub doi: String,
+        /// For example `journal-article` or `proceedings-article`
+        #[serde(rename = "type", default)]
+        pub work_type: String,
+        #[serde(default)]
+        pub title: Vec<String>,
+        #[serde(default)]
+        pub author: Vec<Person>,
+        /// The journal, proceedings or book the work is part of
+        #[serde(default)]
+        pub container_title: Vec<String>,
+        pub issued: Option<Date>,
+        pub volume: Option<String>,
+        pub issue: Option<String>,
+        pub page: Option<String>,
+        pub publisher: Option<String>,
+        /// Notices such as retractions and corrections that update this work
+        #[serde(default)]
+        pub updated_by: Vec<Update>,
+        /// The works this work cites, if the publisher deposited them
+        #[serde(default)]
+        pub reference: Vec<Reference>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct Reference {
+        #[serde(rename = "DOI")]
+        pub doi: Option<String>,
+        pub article_title: Option<String>,
+        /// The first author's family name
+        pub author: Option<String>,
+        pub year: Option<String>,
+        /// The reference as printed, for those deposited without structure
+        pub unstructured: Option<String>,
+    }
+
+    impl Reference {
+        /// How the reference is shown, e.g. `Separation logic (Reynolds 2002)`.
+        pub fn label(&self) -> String {
+            let title = self
+                .article_title
+                .as_deref()
+                .or(self.unstructured.as_deref())
+                .or(self.doi.as_deref())
+                .unwrap_or_default();
+            match (&self.author, &self.year) {
+                (Some(author), Some(year)) => format!("{title} ({author} {year})"),
+                (Some(author), None) => format!("{title} ({author})"),
+                (None, Some(year)) => format!("{title} ({year})"),
+                (None, None) => title.to_string(),
+            }
+        }
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct Person {
+        pub given: Option<String>,
+        pub family: Option<String>,
+        /// Name of an organization, used instead of `given` and `family`
+        pub name: Option<String>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct Update {
+        /// The DOI of the notice
+        #[serde(rename = "DOI")]
+        pub doi: String,
+        /// For example `retraction`, `correction`, `erratum` or `expression_of_concern`
+        #[serde(rename = "type")]
+        pub update_type: String,
+        pub updated: Option<Date>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct Date {
+        pub date_parts: Vec<Vec<i32>>,
+    }
+
+    impl Update {
+        pub fn is_retraction(&self) -> bool {
+            matches!(
+                self.update_type.as_str(),
+                "retraction" | "withdrawal" | "removal"
+            )
+        }
+    }
+}
@@ -1,29 +1,190 @@
 //! URL: `https://dblp.org/search/publ/api?format=json&q={query}`
 
-use crate::Result;
+use biblatex::Entry;
+use itertools::Itertools;
+use serde::Deserialize;
 
-pub fn search(query: &str) -> Result<response::Response> {
-    reqwest::blocking::Client::new()
+use crate::{
+    Result, http,
+    provenance::Provenance,
+    remotes::{Candidate, Capabilities, Page, Remote, parse_entry},
+    venues::Venue,
+};
+
+/// Number of hits fetched per page of a search.
+pub const PAGE_SIZE: usize = 30;
+
+/// One page of publications matching `query`, starting at the hit `first`.
+pub fn search(query: &str, first: usize) -> Result<response::Response> {
+    http::DBLP
         .get("https://dblp.org/search/publ/api")
-        .query(&[("format", "json"), ("q", query)])
+        .query(&[
+            ("format", "json"),
+            ("q", query),
+            ("h", &PAGE_SIZE.to_string()),
+            ("f", &first.to_string()),
+        ])
         .send()?
         .json()
         .map_err(Into::into)
 }
 
-impl response::Hit {
-    /// Download .bib
-    ///
-    /// Stored at `https://dblp.org/rec/{key}.bib?param=1`
-    pub fn bib(&self) -> Result<String> {
-        reqwest::blocking::Client::new()
-            .get(format!(
-                "https://dblp.org/rec/{}.bib?param=1",
-                self.info.key
-            ))
-            .send()?
-            .text()
-            .map_err(Into::into)
+/// Download the .bib of a record by its DBLP key
+///
+/// Stored at `https://dblp.org/rec/{key}.bib?param=1`
+pub fn fetch_bib(key: &str) -> Result<String> {
+    http::DBLP
+        .get(format!("https://dblp.org/rec/{key}.bib?param=1"))
+        .send()?
+        .error_for_status()?
+        .text()
+        .map_err(Into::into)
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthorSearch {
+    result: AuthorResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthorResult {
+    hits: AuthorHits,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthorHits {
+    #[serde(default)]
+    hit: Vec<AuthorHit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthorHit {
+    info: AuthorInfo,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuthorInfo {
+    /// The name as DBLP writes it, e.g. `Jane Doe 0001`
+    pub author: String,
+    /// For example `https://dblp.org/pid/12/3456`
+    pub url: String,
+}
+
+impl AuthorInfo {
+    pub fn pid(&self) -> &str {
+        pid(&self.url)
+    }
+}
+
+/// The PID in an author page URL, or the PID itself, e.g. `12/3456`.
+pub fn pid(url: &str) -> &str {
+    let url = url.trim().trim_end_matches(".html");
+    url.split_once("/pid/").map_or(url, |(_, pid)| pid)
+}
+
+/// Authors matching `name`, best match first.
+pub fn search_author(name: &str) -> Result<Vec<AuthorInfo>> {
+    let response: AuthorSearch = http::DBLP
+        .get("https://dblp.org/search/author/api")
+        .query(&[("format", "json"), ("q", name)])
+        .send()?
+        .error_for_status()?
+        .json()?;
+    Ok(response
+        .result
+        .hits
+        .hit
+        .into_iter()
+        .map(|hit| hit.info)
+        .collect())
+}
+
+/// Download the .bib of every publication of an author by their PID
+///
+/// Stored at `https://dblp.org/pid/{pid}.bib?param=1`
+pub fn fetch_author_bib(pid: &str) -> Result<String> {
+    http::DBLP
+        .get(format!("https://dblp.org/pid/{pid}.bib?param=1"))
+        .send()?
+        .error_for_status()?
+        .text()
+        .map_err(Into::into)
+}
+
+/// The table of contents of a conference edition or journal volume, from its
+/// key or page URL, e.g. `conf/popl/popl2020` or
+/// `https://dblp.org/db/journals/pacmpl/pacmpl4.html`.
+pub fn toc(venue: &str) -> String {
+    let venue = venue.trim().trim_end_matches('/');
+    let venue = venue
+        .split_once("dblp.org/")
+        .map_or(venue, |(_, path)| path);
+    let venue = venue
+        .trim_end_matches(".html")
+        .trim_end_matches(".bht")
+        .trim_start_matches("db/");
+    format!("db/{venue}.bht")
+}
+
+/// Download the .bib of every publication in a table of contents, see [`toc`]
+///
+/// Queried at `https://dblp.org/search/publ/api?q=toc:{toc}:&format=bib1`
+pub fn fetch_toc_bib(toc: &str) -> Result<String> {
+    http::DBLP
+        .get("https://dblp.org/search/publ/api")
+        .query(&[
+            ("q", format!("toc:{toc}:").as_str()),
+            ("format", "bib1"),
+            ("h", "1000"),
+        ])
+        .send()?
+        .error_for_status()?
+        .text()
+        .map_err(Into::into)
+}
+
+pub struct Dblp;
+
+impl Remote for Dblp {
+    fn name(&self) -> &'static str {
+        "dblp"
+    }
+
+    fn description(&self) -> &'static str {
+        "DBLP, covering computer science"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            search: true,
+            fetch_entry: true,
+            ..Default::default()
+        }
+    }
+
+    fn search(&self, query: &str, venue: Option<&Venue>, first: usize) -> Result<Page> {
+        let query = match venue {
+            Some(venue) => format!("{query} {}", venue.dblp_query()),
+            None => query.to_string(),
+        };
+        let hits = search(&query, first)?.result.hits;
+        Ok(Page {
+            more: hits.has_more(),
+            candidates: hits
+                .hit
+                .into_iter()
+                .map(|hit| Candidate {
+                    authors: hit.info.authors.author.iter().map(|a| &a.text).join(", "),
+                    title: hit.info.title,
+                    doi: hit.info.doi,
+                    provenance: Provenance::new("dblp", &hit.info.key),
+                })
+                .collect(),
+        })
+    }
+
+    fn fetch_entry(&self, id: &str) -> Result<Entry> {
+        parse_entry(&fetch_bib(id)?, self.name(), id)
     }
 }
 
@@ -91,9 +252,19 @@ pub mod response {
         pub sent: String,
         #[serde(rename = "@first")]
         pub first: String,
+        /// Missing when nothing matches
+        #[serde(default)]
         pub hit: Vec<Hit>,
     }
 
+    impl Hits {
+        /// Whether there are hits after this page.
+        pub fn has_more(&self) -> bool {
+            let number = |n: &str| n.parse::<usize>().unwrap_or_default();
+            number(&self.first) + number(&self.sent) < number(&self.total)
+        }
+    }
+
     #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
     pub struct Hit {
         #[serde(rename = "@score")]
@@ -0,0 +1,50 @@
+//! URL: `https://doi.org/{doi}` with `Accept: application/x-bibtex`
+//!
+//! The DOI resolver hands requests for BibTeX to the registration agency of
+//! the DOI, so this works for DataCite, mEDRA and other agencies besides
+//! Crossref, though the records are less detailed.
+
+use biblatex::Entry;
+
+use crate::{
+    Result, doi, http,
+    remotes::{Capabilities, PING_DOI, Remote, parse_entry},
+};
+
+/// Downloads the BibTeX record of a DOI by content negotiation.
+pub fn fetch_bib(id: &str) -> Result<String> {
+    http::DOI
+        .get(format!("https://doi.org/{}", doi::url_path(id)))
+        .header("accept", "application/x-bibtex")
+        .send()?
+        .error_for_status()?
+        .text()
+        .map_err(Into::into)
+}
+
+pub struct Doi;
+
+impl Remote for Doi {
+    fn name(&self) -> &'static str {
+        "doi"
+    }
+
+    fn description(&self) -> &'static str {
+        "The registration agency of a DOI"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            fetch_entry: true,
+            ..Default::default()
+        }
+    }
+
+    fn fetch_entry(&self, id: &str) -> Result<Entry> {
+        parse_entry(&fetch_bib(id)?, self.name(), id)
+    }
+
+    fn ping(&self) -> Result<()> {
+        fetch_bib(PING_DOI).map(drop)
+    }
+}
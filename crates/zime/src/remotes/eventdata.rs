@@ -0,0 +1,60 @@
+//! URL: `https://api.eventdata.crossref.org/v1/events?obj-id={doi}`
+//!
+//! Crossref Event Data collects mentions of DOIs outside the literature, such
+//! as Wikipedia citations, Reddit posts and news coverage, each event tagged
+//! with the source it was found in.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::{Result, doi, http};
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    message: Message,
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    #[serde(rename = "next-cursor")]
+    next_cursor: Option<String>,
+    #[serde(default)]
+    events: Vec<Event>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Event {
+    /// For example `wikipedia` or `reddit`
+    source_id: String,
+}
+
+/// The number of events mentioning the DOI, by source.
+pub fn count_by_source(id: &str) -> Result<BTreeMap<String, usize>> {
+    let id = doi::normalize(id);
+    let mut counts = BTreeMap::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let mut query = vec![("obj-id", id.as_str()), ("rows", "1000")];
+        if let Some(mailto) = http::mailto() {
+            query.push(("mailto", mailto));
+        }
+        if let Some(cursor) = &cursor {
+            query.push(("cursor", cursor));
+        }
+        let response: Response = http::EVENT_DATA
+            .get("https://api.eventdata.crossref.org/v1/events")
+            .query(&query)
+            .send()?
+            .error_for_status()?
+            .json()?;
+        let done = response.message.events.is_empty();
+        for event in response.message.events {
+            *counts.entry(event.source_id).or_default() += 1;
+        }
+        match response.message.next_cursor {
+            Some(next) if !done => cursor = Some(next),
+            _ => return Ok(counts),
+        }
+    }
+}
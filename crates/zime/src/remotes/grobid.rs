@@ -0,0 +1,191 @@
+//! URL: `{server}/api/processHeaderDocument`
+//!
+//! GROBID extracts the title, authors, venue and DOI from the first pages of
+//! a PDF, for `zime add --pdf` when the PDF has no DOI embedded. It runs as a
+//! server, by default expected at `http://localhost:8070`, e.g. started with
+//! `docker run -p 8070:8070 lfoppiano/grobid`. Another server is configured in
+//! the global config:
+//!
+//! ```toml
+//! [grobid]
+//! url = "https://grobid.example.org"
+//! ```
+//!
+//! GROBID is asked to consolidate the header with Crossref, which fills in the
+//! DOI of most published papers.
+
+use std::sync::OnceLock;
+
+use color_eyre::eyre::eyre;
+use roxmltree::Node;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Result,
+    config::Config,
+    http,
+    remotes::{
+        Capabilities, Remote,
+        record::{Author, Record},
+    },
+};
+
+const DEFAULT_URL: &str = "http://localhost:8070";
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GrobidConfig {
+    /// Base URL of the GROBID server
+    pub url: Option<String>,
+}
+
+fn config() -> &'static GrobidConfig {
+    static CONFIG: OnceLock<GrobidConfig> = OnceLock::new();
+    CONFIG.get_or_init(|| Config::load_global().map(|c| c.grobid).unwrap_or_default())
+}
+
+pub fn url() -> &'static str {
+    config()
+        .url
+        .as_deref()
+        .unwrap_or(DEFAULT_URL)
+        .trim_end_matches('/')
+}
+
+/// A `multipart/form-data` body with the PDF as the `input` field, asking
+/// GROBID to consolidate the header.
+fn form(pdf: &[u8], file_name: &str) -> (String, Vec<u8>) {
+    let boundary = format!("zime-{:x}", jiff::Timestamp::now().as_nanosecond());
+    let file_name = file_name.replace('"', "");
+    let mut body = format!(
+        "--{boundary}\r\n\
+         Content-Disposition: form-data; name=\"consolidateHeader\"\r\n\r\n1\r\n\
+         --{boundary}\r\n\
+         Content-Disposition: form-data; name=\"input\"; filename=\"{file_name}\"\r\n\
+         Content-Type: application/pdf\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend_from_slice(pdf);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+    (format!("multipart/form-data; boundary={boundary}"), body)
+}
+
+/// Extracts the metadata of the PDF.
+pub fn extract(pdf: &[u8], file_name: &str) -> Result<Record> {
+    let (content_type, body) = form(pdf, file_name);
+    let tei = http::GROBID
+        .post(format!("{}/api/processHeaderDocument", url()))
+        .header("content-type", &content_type)
+        .header("accept", "application/xml")
+        .body(body)
+        .send()
+        .map_err(|err| eyre!("failed to reach GROBID at {}: {err}", url()))?
+        .error_for_status()?
+        .text()?;
+    parse(&tei)
+}
+
+/// The descendant of `node` at `path`, following the first matching child.
+fn find<'a, 'i>(node: Node<'a, 'i>, path: &[&str]) -> Option<Node<'a, 'i>> {
+    path.iter().try_fold(node, |node, name| {
+        node.children().find(|c| c.has_tag_name(*name))
+    })
+}
+
+/// The text of `node`, including that of nested markup.
+fn text(node: Node) -> String {
+    let text: String = node
+        .descendants()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .collect();
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn parse(tei: &str) -> Result<Record> {
+    let doc = roxmltree::Document::parse(tei)?;
+    let header = doc
+        .descendants()
+        .find(|n| n.has_tag_name("teiHeader"))
+        .ok_or_else(|| eyre!("GROBID returned no header"))?;
+    let title = find(header, &["fileDesc", "titleStmt", "title"])
+        .map(text)
+        .filter(|title| !title.is_empty())
+        .ok_or_else(|| eyre!("GROBID found no title in the PDF"))?;
+    let bibl = find(header, &["fileDesc", "sourceDesc", "biblStruct"]);
+    let authors = bibl
+        .and_then(|bibl| find(bibl, &["analytic"]))
+        .into_iter()
+        .flat_map(|analytic| analytic.children().filter(|c| c.has_tag_name("author")))
+        .filter_map(|author| {
+            let name = find(author, &["persName"])?;
+            let given = name
+                .children()
+                .filter(|c| c.has_tag_name("forename"))
+                .map(text)
+                .collect::<Vec<_>>()
+                .join(" ");
+            Some(Author {
+                given,
+                family: find(name, &["surname"]).map(text)?,
+            })
+        })
+        .collect();
+    let monogr = bibl.and_then(|bibl| find(bibl, &["monogr"]));
+    let venue = monogr.and_then(|monogr| {
+        monogr
+            .children()
+            .find(|c| c.has_tag_name("title"))
+            .map(|title| (title.attribute("level"), text(title)))
+            .filter(|(_, title)| !title.is_empty())
+    });
+    let year = header
+        .descendants()
+        .filter(|n| n.has_tag_name("date"))
+        .find_map(|date| date.attribute("when")?.get(..4)?.parse().ok());
+    let doi = header
+        .descendants()
+        .find(|n| n.has_tag_name("idno") && n.attribute("type") == Some("DOI"))
+        .map(text);
+    let abstract_ = find(header, &["profileDesc", "abstract"])
+        .map(text)
+        .filter(|a| !a.is_empty());
+    Ok(Record {
+        entry_type: match venue.as_ref().and_then(|(level, _)| *level) {
+            Some("j") => "article",
+            Some("m") => "inproceedings",
+            _ => "misc",
+        },
+        title,
+        authors,
+        year,
+        venue: venue.map(|(_, title)| title),
+        doi,
+        abstract_,
+        ..Default::default()
+    })
+}
+
+pub struct Grobid;
+
+impl Remote for Grobid {
+    fn name(&self) -> &'static str {
+        "grobid"
+    }
+
+    fn description(&self) -> &'static str {
+        "GROBID, extracting metadata from PDFs for `add --pdf`"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+
+    fn ping(&self) -> Result<()> {
+        http::GROBID
+            .get(format!("{}/api/isalive", url()))
+            .send()?
+            .error_for_status()?;
+        Ok(())
+    }
+}
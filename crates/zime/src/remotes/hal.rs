@@ -0,0 +1,132 @@
+//! URL: `https://api.archives-ouvertes.fr/search/?q={query}`
+//!
+//! HAL is the French open archive, where many European computer science and
+//! mathematics papers are deposited, often with the only open access PDF.
+
+use biblatex::Entry;
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::{
+    Result, doi, http,
+    provenance::Provenance,
+    remotes::{Candidate, Capabilities, NotAvailable, Page, Pdfs, Remote, parse_entry},
+    venues::Venue,
+};
+
+const API: &str = "https://api.archives-ouvertes.fr/search/";
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    response: Docs,
+}
+
+#[derive(Debug, Deserialize)]
+struct Docs {
+    #[serde(default)]
+    docs: Vec<Document>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Document {
+    /// For example `hal-01234567`
+    #[serde(rename = "halId_s")]
+    pub hal_id: String,
+    #[serde(rename = "title_s", default)]
+    pub titles: Vec<String>,
+    #[serde(rename = "authFullName_s", default)]
+    pub authors: Vec<String>,
+    #[serde(rename = "doiId_s")]
+    pub doi: Option<String>,
+    /// The deposited files, the main PDF first
+    #[serde(rename = "files_s", default)]
+    pub files: Vec<String>,
+}
+
+const FIELDS: &str = "halId_s,title_s,authFullName_s,doiId_s,files_s";
+
+fn query(q: &str, rows: &str) -> Result<Vec<Document>> {
+    let response: SearchResponse = http::HAL
+        .get(API)
+        .query(&[("q", q), ("wt", "json"), ("fl", FIELDS), ("rows", rows)])
+        .send()?
+        .error_for_status()?
+        .json()?;
+    Ok(response.response.docs)
+}
+
+pub fn search(q: &str) -> Result<Vec<Document>> {
+    query(q, "20")
+}
+
+/// Download the .bib of a document by its HAL ID
+pub fn fetch_bib(id: &str) -> Result<String> {
+    http::HAL
+        .get(API)
+        .query(&[
+            ("q", format!("halId_s:\"{id}\"").as_str()),
+            ("wt", "bibtex"),
+        ])
+        .send()?
+        .error_for_status()?
+        .text()
+        .map_err(Into::into)
+}
+
+/// Fetches the PDF deposited on HAL for the work with the given DOI.
+pub fn fetch_pdf(id: &str) -> Result<Vec<u8>> {
+    let docs = query(&format!("doiId_s:\"{}\"", doi::normalize(id)), "1")?;
+    let url = docs
+        .iter()
+        .flat_map(|doc| &doc.files)
+        .find(|file| file.ends_with(".pdf") || file.ends_with("/document"))
+        .ok_or_else(|| NotAvailable("HAL has no PDF for this DOI".to_string()))?;
+    debug!(url, "fetching PDF from HAL");
+    let pdf = http::HAL.get(url).send()?.error_for_status()?.bytes()?;
+    Ok(pdf.to_vec())
+}
+
+impl Document {
+    pub fn title(&self) -> String {
+        self.titles.first().cloned().unwrap_or_default()
+    }
+}
+
+pub struct Hal;
+
+impl Remote for Hal {
+    fn name(&self) -> &'static str {
+        "hal"
+    }
+
+    fn description(&self) -> &'static str {
+        "HAL, the French open archive"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            search: true,
+            fetch_entry: true,
+            pdfs: Pdfs::Any,
+        }
+    }
+
+    fn search(&self, query: &str, _venue: Option<&Venue>, _first: usize) -> Result<Page> {
+        Ok(Page::all(search(query)?.into_iter().map(|document| {
+            Candidate {
+                authors: document.authors.join(", "),
+                title: document.title(),
+                provenance: Provenance::new("hal", &document.hal_id),
+                doi: document.doi,
+            }
+        })))
+    }
+
+    fn fetch_entry(&self, id: &str) -> Result<Entry> {
+        parse_entry(&fetch_bib(id)?, self.name(), id)
+    }
+
+    fn fetch_pdf(&self, doi: &str) -> Result<Vec<u8>> {
+        fetch_pdf(doi)
+    }
+}
@@ -0,0 +1,184 @@
+//! URL: `https://ieeexploreapi.ieee.org/api/v1/search/articles?querytext={query}`
+//!
+//! IEEE Xplore has the full metadata of IEEE papers, including the volume,
+//! pages and conference location DBLP often leaves out. Its API requires a
+//! key, set as `http.ieee_api_key` in the global config.
+
+use biblatex::Entry;
+use color_eyre::eyre::eyre;
+use itertools::Itertools;
+
+use crate::{
+    Result, doi, http,
+    provenance::Provenance,
+    remotes::{
+        Candidate, Capabilities, Page, Remote,
+        record::{Author, Record},
+    },
+    venues::Venue,
+};
+
+const API: &str = "https://ieeexploreapi.ieee.org/api/v1/search/articles";
+
+fn query(params: &[(&str, &str)]) -> Result<Vec<response::Article>> {
+    let key =
+        http::ieee_api_key().ok_or_else(|| eyre!("IEEE Xplore requires http.ieee_api_key"))?;
+    let response: response::Search = http::IEEE
+        .get(API)
+        .query(&[("apikey", key), ("format", "json")])
+        .query(params)
+        .send()?
+        .error_for_status()?
+        .json()?;
+    Ok(response.articles)
+}
+
+pub fn search(query_text: &str) -> Result<Vec<response::Article>> {
+    query(&[("querytext", query_text), ("max_records", "20")])
+}
+
+/// Fetches an article by its Xplore article number, e.g. `8930503`.
+pub fn fetch_article(number: &str) -> Result<response::Article> {
+    query(&[("article_number", number)])?
+        .into_iter()
+        .next()
+        .ok_or_else(|| eyre!("IEEE Xplore has no article {number}"))
+}
+
+pub fn fetch_article_by_doi(id: &str) -> Result<response::Article> {
+    let id = doi::normalize(id);
+    query(&[("doi", &id)])?
+        .into_iter()
+        .next()
+        .ok_or_else(|| eyre!("IEEE Xplore has no article with DOI {id}"))
+}
+
+impl response::Article {
+    pub fn author_names(&self) -> impl Iterator<Item = &str> {
+        self.authors.authors.iter().map(|a| a.full_name.as_str())
+    }
+
+    pub fn record(&self) -> Record {
+        let entry_type = match self.content_type.as_deref() {
+            Some("Conferences") => "inproceedings",
+            Some("Journals" | "Magazines" | "Early Access Articles") => "article",
+            Some("Books") => "incollection",
+            Some("Standards") => "techreport",
+            _ => "misc",
+        };
+        let pages = match (&self.start_page, &self.end_page) {
+            (Some(start), Some(end)) if start != end => Some(format!("{start}-{end}")),
+            (Some(start), _) => Some(start.clone()),
+            _ => None,
+        };
+        Record {
+            entry_type,
+            title: self.title.clone(),
+            authors: self.author_names().map(Author::from_full_name).collect(),
+            year: self
+                .publication_year
+                .as_deref()
+                .and_then(|year| year.parse().ok()),
+            venue: self.publication_title.clone(),
+            volume: self.volume.clone(),
+            number: self.issue.clone(),
+            pages,
+            publisher: self.publisher.clone(),
+            doi: self.doi.as_deref().map(doi::normalize),
+            url: self.html_url.clone(),
+            abstract_: self.abstract_.clone(),
+            location: self.conference_location.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+pub struct Ieee;
+
+impl Remote for Ieee {
+    fn name(&self) -> &'static str {
+        "ieee"
+    }
+
+    fn description(&self) -> &'static str {
+        "IEEE Xplore, covering electrical engineering and computing, also looks up DOIs"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            search: true,
+            fetch_entry: true,
+            ..Default::default()
+        }
+    }
+
+    fn missing_config(&self) -> Option<&'static str> {
+        http::ieee_api_key()
+            .is_none()
+            .then_some("http.ieee_api_key")
+    }
+
+    fn search(&self, query: &str, _venue: Option<&Venue>, _first: usize) -> Result<Page> {
+        let articles = if query.trim().starts_with("10.") || query.contains("doi.org/") {
+            vec![fetch_article_by_doi(query)?]
+        } else {
+            search(query)?
+        };
+        Ok(Page::all(articles.into_iter().map(|article| Candidate {
+            authors: article.author_names().join(", "),
+            provenance: Provenance::new("ieee", &article.article_number),
+            doi: article.doi.as_deref().map(doi::normalize),
+            title: article.title.clone(),
+        })))
+    }
+
+    fn fetch_entry(&self, id: &str) -> Result<Entry> {
+        Ok(fetch_article(id)?.record().into_entry())
+    }
+}
+
+pub mod response {
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    pub struct Search {
+        #[serde(default)]
+        pub articles: Vec<Article>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct Article {
+        pub article_number: String,
+        #[serde(default)]
+        pub title: String,
+        pub doi: Option<String>,
+        #[serde(default)]
+        pub authors: Authors,
+        /// For example `Conferences` or `Journals`
+        pub content_type: Option<String>,
+        /// The journal or proceedings
+        pub publication_title: Option<String>,
+        pub publication_year: Option<String>,
+        pub volume: Option<String>,
+        pub issue: Option<String>,
+        pub start_page: Option<String>,
+        pub end_page: Option<String>,
+        pub publisher: Option<String>,
+        /// For example `Montreal, QC, Canada`
+        pub conference_location: Option<String>,
+        pub html_url: Option<String>,
+        #[serde(rename = "abstract")]
+        pub abstract_: Option<String>,
+    }
+
+    #[derive(Debug, Default, Deserialize)]
+    pub struct Authors {
+        #[serde(default)]
+        pub authors: Vec<Author>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct Author {
+        pub full_name: String,
+    }
+}
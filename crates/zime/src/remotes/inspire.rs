@@ -0,0 +1,154 @@
+//! URL: `https://inspirehep.net/api/literature?q={query}`
+//!
+//! INSPIRE indexes high-energy physics, and serializes its records as BibTeX
+//! with the usual INSPIRE texkeys such as `Maldacena:1997re`.
+
+use biblatex::Entry;
+use itertools::Itertools;
+use serde::Deserialize;
+
+use crate::{
+    Result, http,
+    provenance::Provenance,
+    remotes::{Candidate, Capabilities, Page, Remote, parse_entry},
+    venues::Venue,
+};
+
+const API: &str = "https://inspirehep.net/api/literature";
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    hits: Hits,
+}
+
+#[derive(Debug, Deserialize)]
+struct Hits {
+    #[serde(default)]
+    hits: Vec<Hit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Hit {
+    metadata: Literature,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Literature {
+    /// The INSPIRE record ID
+    pub control_number: u64,
+    #[serde(default)]
+    pub titles: Vec<Title>,
+    #[serde(default)]
+    pub authors: Vec<Person>,
+    #[serde(default)]
+    pub dois: Vec<Doi>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Title {
+    pub title: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Person {
+    /// For example `Maldacena, Juan Martin`
+    pub full_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Doi {
+    pub value: String,
+}
+
+/// Searches INSPIRE, see the [query syntax](https://help.inspirehep.net/knowledge-base/inspire-paper-search/).
+pub fn search(query: &str) -> Result<Vec<Literature>> {
+    let response: SearchResponse = http::INSPIRE
+        .get(API)
+        .query(&[
+            ("q", query),
+            ("size", "20"),
+            ("fields", "control_number,titles,authors.full_name,dois"),
+        ])
+        .send()?
+        .error_for_status()?
+        .json()?;
+    Ok(response
+        .hits
+        .hits
+        .into_iter()
+        .map(|hit| hit.metadata)
+        .collect())
+}
+
+/// Download the .bib of a record by its INSPIRE record ID
+pub fn fetch_bib(id: &str) -> Result<String> {
+    http::INSPIRE
+        .get(format!("{API}/{id}"))
+        .query(&[("format", "bibtex")])
+        .send()?
+        .error_for_status()?
+        .text()
+        .map_err(Into::into)
+}
+
+impl Literature {
+    pub fn title(&self) -> String {
+        self.titles
+            .first()
+            .map(|title| title.title.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn doi(&self) -> Option<String> {
+        self.dois.first().map(|doi| doi.value.clone())
+    }
+}
+
+impl Person {
+    /// The name as `Juan Martin Maldacena`.
+    pub fn display_name(&self) -> String {
+        match self.full_name.split_once(", ") {
+            Some((family, given)) => format!("{given} {family}"),
+            None => self.full_name.clone(),
+        }
+    }
+}
+
+pub struct Inspire;
+
+impl Remote for Inspire {
+    fn name(&self) -> &'static str {
+        "inspire"
+    }
+
+    fn description(&self) -> &'static str {
+        "INSPIRE, covering high-energy physics"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            search: true,
+            fetch_entry: true,
+            ..Default::default()
+        }
+    }
+
+    fn search(&self, query: &str, _venue: Option<&Venue>, _first: usize) -> Result<Page> {
+        Ok(Page::all(search(query)?.into_iter().map(|literature| {
+            Candidate {
+                authors: literature
+                    .authors
+                    .iter()
+                    .map(|a| a.display_name())
+                    .join(", "),
+                title: literature.title(),
+                doi: literature.doi(),
+                provenance: Provenance::new("inspire", &literature.control_number.to_string()),
+            }
+        })))
+    }
+
+    fn fetch_entry(&self, id: &str) -> Result<Entry> {
+        parse_entry(&fetch_bib(id)?, self.name(), id)
+    }
+}
@@ -0,0 +1,218 @@
+//! URL: `https://api.openalex.org/works?search={query}`
+//!
+//! OpenAlex indexes works from all fields, including the biology, physics and
+//! social science venues DBLP does not cover.
+
+use std::collections::BTreeMap;
+
+use biblatex::Entry;
+use itertools::Itertools;
+
+use crate::{
+    Result, doi, http,
+    provenance::Provenance,
+    remotes::{
+        Candidate, Capabilities, Page, Remote,
+        record::{Author, Record},
+    },
+    venues::Venue,
+};
+
+const API: &str = "https://api.openalex.org";
+
+pub fn search(query: &str) -> Result<Vec<response::Work>> {
+    let response: response::Search = http::OPENALEX
+        .get(format!("{API}/works"))
+        .query(&[("search", query), ("per-page", "20")])
+        .send()?
+        .error_for_status()?
+        .json()?;
+    Ok(response.results)
+}
+
+/// Fetches a work by its OpenAlex ID, e.g. `W2741809807`.
+pub fn fetch_work(id: &str) -> Result<response::Work> {
+    http::OPENALEX
+        .get(format!("{API}/works/{id}"))
+        .send()?
+        .error_for_status()?
+        .json()
+        .map_err(Into::into)
+}
+
+pub fn fetch_work_by_doi(id: &str) -> Result<response::Work> {
+    fetch_work(&format!("doi:{}", doi::normalize(id)))
+}
+
+impl response::Work {
+    /// The short OpenAlex ID, e.g. `W2741809807`.
+    pub fn short_id(&self) -> &str {
+        self.id.rsplit('/').next().unwrap_or(&self.id)
+    }
+
+    /// The DOI without the `https://doi.org/` prefix OpenAlex includes.
+    pub fn doi(&self) -> Option<String> {
+        self.doi.as_deref().map(doi::normalize)
+    }
+
+    /// The abstract, which OpenAlex only serves as an index from words to
+    /// their positions.
+    pub fn abstract_text(&self) -> Option<String> {
+        let index = self.abstract_inverted_index.as_ref()?;
+        let positioned: BTreeMap<usize, &str> = index
+            .iter()
+            .flat_map(|(word, positions)| positions.iter().map(move |&p| (p, word.as_str())))
+            .collect();
+        let words: Vec<&str> = positioned.into_values().collect();
+        (!words.is_empty()).then(|| words.join(" "))
+    }
+
+    pub fn record(&self) -> Record {
+        let source = self
+            .primary_location
+            .as_ref()
+            .and_then(|location| location.source.as_ref());
+        let entry_type = match (
+            self.work_type.as_deref(),
+            source.and_then(|s| s.source_type.as_deref()),
+        ) {
+            (Some("article"), Some("conference")) => "inproceedings",
+            (Some("article" | "review" | "letter"), _) => "article",
+            (Some("book"), _) => "book",
+            (Some("book-chapter"), _) => "incollection",
+            (Some("dissertation"), _) => "phdthesis",
+            (Some("report"), _) => "techreport",
+            _ => "misc",
+        };
+        let pages =
+            self.biblio
+                .as_ref()
+                .and_then(|biblio| match (&biblio.first_page, &biblio.last_page) {
+                    (Some(first), Some(last)) if first != last => Some(format!("{first}-{last}")),
+                    (Some(first), _) => Some(first.clone()),
+                    _ => None,
+                });
+        Record {
+            entry_type,
+            title: self.title.clone().unwrap_or_default(),
+            authors: self
+                .authorships
+                .iter()
+                .map(|authorship| Author::from_full_name(&authorship.author.display_name))
+                .collect(),
+            year: self.publication_year,
+            venue: source.map(|source| source.display_name.clone()),
+            volume: self.biblio.as_ref().and_then(|b| b.volume.clone()),
+            number: self.biblio.as_ref().and_then(|b| b.issue.clone()),
+            pages,
+            doi: self.doi(),
+            abstract_: self.abstract_text(),
+            ..Default::default()
+        }
+    }
+}
+
+pub struct OpenAlex;
+
+impl Remote for OpenAlex {
+    fn name(&self) -> &'static str {
+        "openalex"
+    }
+
+    fn description(&self) -> &'static str {
+        "OpenAlex, covering all fields, also looks up DOIs"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            search: true,
+            fetch_entry: true,
+            ..Default::default()
+        }
+    }
+
+    fn search(&self, query: &str, _venue: Option<&Venue>, _first: usize) -> Result<Page> {
+        let works = if query.trim().starts_with("10.") || query.contains("doi.org/") {
+            vec![fetch_work_by_doi(query)?]
+        } else {
+            search(query)?
+        };
+        Ok(Page::all(works.into_iter().map(|work| {
+            Candidate {
+                authors: work
+                    .authorships
+                    .iter()
+                    .map(|a| &a.author.display_name)
+                    .join(", "),
+                doi: work.doi(),
+                provenance: Provenance::new("openalex", work.short_id()),
+                title: work.title.unwrap_or_default(),
+            }
+        })))
+    }
+
+    fn fetch_entry(&self, id: &str) -> Result<Entry> {
+        Ok(fetch_work(id)?.record().into_entry())
+    }
+}
+
+pub mod response {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct Search {
+        #[serde(default)]
+        pub results: Vec<Work>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct Work {
+        /// For example `https://openalex.org/W2741809807`
+        pub id: String,
+        /// For example `https://doi.org/10.7717/peerj.4375`
+        pub doi: Option<String>,
+        pub title: Option<String>,
+        pub publication_year: Option<i32>,
+        /// For example `article`, `book-chapter` or `preprint`
+        #[serde(rename = "type")]
+        pub work_type: Option<String>,
+        #[serde(default)]
+        pub authorships: Vec<Authorship>,
+        pub primary_location: Option<Location>,
+        pub biblio: Option<Biblio>,
+        pub abstract_inverted_index: Option<HashMap<String, Vec<usize>>>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct Authorship {
+        pub author: Author,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct Author {
+        pub display_name: String,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct Location {
+        pub source: Option<Source>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct Source {
+        pub display_name: String,
+        /// For example `journal`, `conference` or `repository`
+        #[serde(rename = "type")]
+        pub source_type: Option<String>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct Biblio {
+        pub volume: Option<String>,
+        pub issue: Option<String>,
+        pub first_page: Option<String>,
+        pub last_page: Option<String>,
+    }
+}
@@ -0,0 +1,38 @@
+//! URL: `https://api.opencitations.net/index/v2/citations/doi:{doi}`
+//!
+//! OpenCitations indexes the open reference lists deposited with Crossref and
+//! others, which makes it a second source of citing papers for `zime
+//! cited-by`, by DOI only.
+
+use serde::Deserialize;
+
+use crate::{Result, doi, http};
+
+#[derive(Debug, Deserialize)]
+struct Citation {
+    /// The identifiers of the citing work separated by spaces, e.g.
+    /// `omid:br/06101 doi:10.1145/3290380 openalex:W2905`
+    citing: String,
+}
+
+/// The DOIs of the works citing the work with `id`.
+pub fn citing(id: &str) -> Result<Vec<String>> {
+    let citations: Vec<Citation> = http::OPEN_CITATIONS
+        .get(format!(
+            "https://api.opencitations.net/index/v2/citations/doi:{}",
+            doi::url_path(&doi::normalize(id))
+        ))
+        .send()?
+        .error_for_status()?
+        .json()?;
+    Ok(citations
+        .into_iter()
+        .filter_map(|citation| {
+            citation
+                .citing
+                .split_whitespace()
+                .find_map(|id| id.strip_prefix("doi:"))
+                .map(doi::normalize)
+        })
+        .collect())
+}
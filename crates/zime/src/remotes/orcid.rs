@@ -0,0 +1,229 @@
+//! URL: `https://pub.orcid.org/v3.0/{orcid}/works`
+//!
+//! ORCID profiles list the works of a researcher, usually with a DOI. Works
+//! without one are fetched individually, as the listing has no authors.
+
+use biblatex::Entry;
+use color_eyre::eyre::eyre;
+
+use crate::{
+    Result, doi, http,
+    remotes::{
+        Capabilities, Remote,
+        record::{Author, Record},
+    },
+};
+
+const API: &str = "https://pub.orcid.org/v3.0";
+
+/// The bare iD from an iD or profile URL, e.g. `0000-0002-1825-0097`.
+pub fn parse_id(id: &str) -> Result<String> {
+    let id = id.trim().trim_end_matches('/');
+    let id = id.rsplit('/').next().unwrap_or(id);
+    let valid = id.len() == 19
+        && id.split('-').count() == 4
+        && id
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '-' || c == 'X');
+    if valid {
+        Ok(id.to_string())
+    } else {
+        Err(eyre!("{id} is not an ORCID iD"))
+    }
+}
+
+/// The works on a profile, one summary per work, preferring the one with a
+/// DOI where sources disagree.
+pub fn works(orcid: &str) -> Result<Vec<response::Work>> {
+    let response: response::Works = http::ORCID
+        .get(format!("{API}/{orcid}/works"))
+        .header("accept", "application/json")
+        .send()?
+        .error_for_status()?
+        .json()?;
+    Ok(response
+        .group
+        .into_iter()
+        .filter_map(|group| {
+            let mut summaries = group.work_summary;
+            let i = summaries
+                .iter()
+                .position(|w| w.doi().is_some())
+                .unwrap_or(0);
+            (i < summaries.len()).then(|| summaries.swap_remove(i))
+        })
+        .collect())
+}
+
+/// Fetches a work with its contributors by `{orcid}/{put-code}`, as in the
+/// provenance of imported works.
+pub fn fetch_work(id: &str) -> Result<response::Work> {
+    let (orcid, put_code) = id
+        .split_once('/')
+        .ok_or_else(|| eyre!("{id} is not of the form <orcid>/<put-code>"))?;
+    http::ORCID
+        .get(format!("{API}/{orcid}/work/{put_code}"))
+        .header("accept", "application/json")
+        .send()?
+        .error_for_status()?
+        .json()
+        .map_err(Into::into)
+}
+
+impl response::Work {
+    /// The id to fetch the full work by, `{orcid}/{put-code}`.
+    pub fn id(&self) -> String {
+        let orcid = self.path.split('/').nth(1).unwrap_or_default();
+        format!("{orcid}/{}", self.put_code)
+    }
+
+    pub fn doi(&self) -> Option<String> {
+        self.external_ids
+            .as_ref()?
+            .external_id
+            .iter()
+            .find(|id| id.id_type.eq_ignore_ascii_case("doi"))
+            .map(|id| doi::normalize(&id.value))
+    }
+
+    pub fn record(&self) -> Record {
+        let entry_type = match self.work_type.as_deref() {
+            Some("journal-article") => "article",
+            Some("conference-paper") => "inproceedings",
+            Some("book") => "book",
+            Some("book-chapter") => "incollection",
+            Some("dissertation-thesis") => "phdthesis",
+            Some("report") => "techreport",
+            _ => "misc",
+        };
+        Record {
+            entry_type,
+            title: self
+                .title
+                .as_ref()
+                .map(|title| title.title.value.clone())
+                .unwrap_or_default(),
+            authors: self
+                .contributors
+                .iter()
+                .flat_map(|c| &c.contributor)
+                .filter_map(|c| c.credit_name.as_ref())
+                .map(|name| Author::from_full_name(&name.value))
+                .collect(),
+            year: self
+                .publication_date
+                .as_ref()
+                .and_then(|date| date.year.as_ref())
+                .and_then(|year| year.value.parse().ok()),
+            venue: self.journal_title.as_ref().map(|t| t.value.clone()),
+            doi: self.doi(),
+            url: self.url.as_ref().map(|url| url.value.clone()),
+            ..Default::default()
+        }
+    }
+}
+
+pub struct Orcid;
+
+impl Remote for Orcid {
+    fn name(&self) -> &'static str {
+        "orcid"
+    }
+
+    fn description(&self) -> &'static str {
+        "Works on ORCID profiles"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            fetch_entry: true,
+            ..Default::default()
+        }
+    }
+
+    fn fetch_entry(&self, id: &str) -> Result<Entry> {
+        Ok(fetch_work(id)?.record().into_entry())
+    }
+
+    /// Lists the works of ORCID's example profile, Josiah Carberry.
+    fn ping(&self) -> Result<()> {
+        works("0000-0002-1825-0097").map(drop)
+    }
+}
+
+pub mod response {
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    pub struct Works {
+        #[serde(default)]
+        pub group: Vec<Group>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct Group {
+        #[serde(default)]
+        pub work_summary: Vec<Work>,
+    }
+
+    /// A work, or the summary of one, which has no contributors.
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct Work {
+        pub put_code: u64,
+        /// For example `/0000-0002-1825-0097/work/12345`
+        pub path: String,
+        pub title: Option<Title>,
+        /// For example `journal-article` or `conference-paper`
+        #[serde(rename = "type")]
+        pub work_type: Option<String>,
+        pub publication_date: Option<Date>,
+        pub journal_title: Option<Value>,
+        pub external_ids: Option<ExternalIds>,
+        pub url: Option<Value>,
+        pub contributors: Option<Contributors>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct Title {
+        pub title: Value,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct Value {
+        pub value: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct Date {
+        pub year: Option<Value>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct ExternalIds {
+        #[serde(default)]
+        pub external_id: Vec<ExternalId>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct ExternalId {
+        #[serde(rename = "external-id-type")]
+        pub id_type: String,
+        #[serde(rename = "external-id-value")]
+        pub value: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct Contributors {
+        #[serde(default)]
+        pub contributor: Vec<Contributor>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct Contributor {
+        pub credit_name: Option<Value>,
+    }
+}
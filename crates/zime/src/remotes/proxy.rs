@@ -0,0 +1,235 @@
+//! URL: `{prefix}https://doi.org/{doi}`
+//!
+//! Downloads PDFs from publishers through an institutional EZProxy, for
+//! papers the user's university subscribes to. Configured in the global
+//! config:
+//!
+//! ```toml
+//! [proxy]
+//! prefix = "https://login.ezproxy.example.edu/login?url="
+//! cookies = "/home/me/.config/zime/ezproxy-cookies.txt"
+//! ```
+//!
+//! zime does not log in itself. Instead the session cookies of a login in the
+//! browser are reused, exported either as a `cookies.txt` file in the Netscape
+//! format or as the value of a `Cookie` header. When the session has expired,
+//! the proxy answers with its login page and the download fails until the
+//! cookies are exported again.
+//!
+//! The publisher's landing page is found through the DOI resolver, and the
+//! PDF through its `citation_pdf_url` meta tag, which most publishers set.
+
+use std::{fs, sync::OnceLock};
+
+use camino::Utf8PathBuf;
+use color_eyre::eyre::{bail, eyre};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::{
+    Result,
+    config::Config,
+    doi, http,
+    remotes::{Capabilities, NotAvailable, PING_DOI, Pdfs, Remote},
+};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ProxyConfig {
+    /// Prefix of proxied URLs, e.g. `https://login.ezproxy.example.edu/login?url=`
+    pub prefix: Option<String>,
+    /// File with the session cookies, in the `cookies.txt` format or as a
+    /// `Cookie` header value
+    pub cookies: Option<Utf8PathBuf>,
+}
+
+fn config() -> &'static ProxyConfig {
+    static CONFIG: OnceLock<ProxyConfig> = OnceLock::new();
+    CONFIG.get_or_init(|| Config::load_global().map(|c| c.proxy).unwrap_or_default())
+}
+
+/// Loads the stored session cookies into the HTTP session, once per run.
+fn load_cookies(prefix: &Url) -> Result<()> {
+    static LOADED: OnceLock<()> = OnceLock::new();
+    if LOADED.get().is_some() {
+        return Ok(());
+    }
+    if let Some(path) = &config().cookies {
+        let src = fs::read_to_string(path)
+            .map_err(|err| eyre!("failed to read the proxy cookies in {path}: {err}"))?;
+        let proxy_host = prefix.host_str().unwrap_or_default();
+        let cookies = parse_cookies(&src, proxy_host);
+        debug!(count = cookies.len(), %path, "loaded proxy cookies");
+        for (domain, cookie) in cookies {
+            let url = Url::parse(&format!("https://{domain}/"))?;
+            http::add_cookie(&format!("{cookie}; Domain={domain}; Path=/"), &url);
+        }
+    }
+    let _ = LOADED.set(());
+    Ok(())
+}
+
+/// The cookies in `src` as `(domain, name=value)`. Cookies given as a header
+/// value are for `proxy_host` without a leading `login.`, and so for the
+/// hosts it rewrites publishers to, e.g. `dl-acm-org.ezproxy.example.edu`.
+fn parse_cookies(src: &str, proxy_host: &str) -> Vec<(String, String)> {
+    let netscape = src.lines().any(|line| line.split('\t').count() == 7);
+    if !netscape {
+        let domain = proxy_host.strip_prefix("login.").unwrap_or(proxy_host);
+        return src
+            .trim()
+            .trim_start_matches("Cookie:")
+            .split(';')
+            .map(str::trim)
+            .filter(|cookie| cookie.contains('='))
+            .map(|cookie| (domain.to_string(), cookie.to_string()))
+            .collect();
+    }
+    src.lines()
+        .map(|line| line.strip_prefix("#HttpOnly_").unwrap_or(line))
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [domain, _, _, _, _, name, value] = fields.as_slice() else {
+                return None;
+            };
+            Some((
+                domain.trim_start_matches('.').to_string(),
+                format!("{name}={value}"),
+            ))
+        })
+        .collect()
+}
+
+/// The content of the `<meta name="{name}">` tag in `html`.
+fn meta_content(html: &str, name: &str) -> Option<String> {
+    html.split("<meta")
+        .skip(1)
+        .filter_map(|tag| tag.split('>').next())
+        .find(|tag| tag.contains(&format!("name=\"{name}\"")))
+        .and_then(|tag| tag.split("content=\"").nth(1)?.split('"').next())
+        .map(|content| content.replace("&amp;", "&"))
+}
+
+/// The configured prefix, with the session cookies loaded.
+fn prefix() -> Result<(&'static str, Url)> {
+    let Some(prefix) = &config().prefix else {
+        bail!("no proxy is configured");
+    };
+    let prefix_url = Url::parse(prefix).map_err(|err| eyre!("invalid proxy prefix: {err}"))?;
+    load_cookies(&prefix_url)?;
+    Ok((prefix, prefix_url))
+}
+
+fn login_expired() -> color_eyre::eyre::Error {
+    eyre!(
+        "the proxy asked to log in, export the cookies of a new session to {}",
+        config()
+            .cookies
+            .as_ref()
+            .map_or("the file in proxy.cookies".to_string(), |p| p.to_string())
+    )
+}
+
+/// Opens the landing page of a work through the proxy, checking that the
+/// session is still valid.
+pub fn ping() -> Result<()> {
+    let (prefix, prefix_url) = prefix()?;
+    let response = http::PROXY
+        .get(format!(
+            "{prefix}https://doi.org/{}",
+            doi::url_path(PING_DOI)
+        ))
+        .send()?
+        .error_for_status()?;
+    if response.url().host_str() == prefix_url.host_str() {
+        return Err(login_expired());
+    }
+    Ok(())
+}
+
+pub fn fetch_pdf(id: &str) -> Result<Vec<u8>> {
+    let (prefix, prefix_url) = prefix()?;
+    let proxied = |url: &str| format!("{prefix}{url}");
+
+    let landing = format!("https://doi.org/{}", doi::url_path(id));
+    let response = http::PROXY
+        .get(proxied(&landing))
+        .send()?
+        .error_for_status()?;
+    let page = response.url().clone();
+    debug!(%page, "landing page");
+    let body = response.bytes()?;
+    if body.starts_with(b"%PDF") {
+        return Ok(body.to_vec());
+    }
+    let html = String::from_utf8_lossy(&body);
+
+    let Some(pdf_url) = meta_content(&html, "citation_pdf_url") else {
+        if page.host_str() == prefix_url.host_str() {
+            return Err(login_expired());
+        }
+        return Err(NotAvailable("the publisher's page links no PDF".to_string()).into());
+    };
+    let pdf_url = page.join(&pdf_url)?;
+    // EZProxy rewrites the links in the pages it serves, but not always those
+    // in meta tags
+    let pdf_url = if pdf_url.host_str() == page.host_str() {
+        pdf_url.to_string()
+    } else {
+        proxied(pdf_url.as_str())
+    };
+    debug!(%pdf_url, "fetching pdf");
+    let pdf = http::PROXY
+        .get(&pdf_url)
+        .send()?
+        .error_for_status()?
+        .bytes()?;
+    if !pdf.starts_with(b"%PDF") {
+        return Err(NotAvailable(
+            "the publisher did not serve a PDF through the proxy".to_string(),
+        )
+        .into());
+    }
+    Ok(pdf.to_vec())
+}
+
+pub struct Proxy;
+
+impl Remote for Proxy {
+    fn name(&self) -> &'static str {
+        "proxy"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["ezproxy"]
+    }
+
+    fn description(&self) -> &'static str {
+        "Publishers, through an institutional EZProxy"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            pdfs: if config().prefix.is_some() {
+                Pdfs::Any
+            } else {
+                Pdfs::None
+            },
+            ..Default::default()
+        }
+    }
+
+    fn fetch_pdf(&self, doi: &str) -> Result<Vec<u8>> {
+        fetch_pdf(doi)
+    }
+
+    fn missing_config(&self) -> Option<&'static str> {
+        config().prefix.is_none().then_some("proxy.prefix")
+    }
+
+    fn ping(&self) -> Result<()> {
+        ping()
+    }
+}
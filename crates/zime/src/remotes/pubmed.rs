@@ -0,0 +1,239 @@
+//! URLs: `https://eutils.ncbi.nlm.nih.gov/entrez/eutils/esearch.fcgi` to search,
+//! `https://eutils.ncbi.nlm.nih.gov/entrez/eutils/efetch.fcgi` for records.
+//!
+//! Searching returns PubMed IDs only, so the records of the results are
+//! fetched in a second request.
+
+use biblatex::Entry;
+use color_eyre::eyre::eyre;
+use itertools::Itertools;
+use roxmltree::Node;
+use serde::Deserialize;
+
+use crate::{
+    Result, http,
+    provenance::Provenance,
+    remotes::{
+        Candidate, Capabilities, Page, Remote,
+        record::{Author, Record},
+    },
+    venues::Venue,
+};
+
+const EUTILS: &str = "https://eutils.ncbi.nlm.nih.gov/entrez/eutils";
+
+/// A PubMed record.
+#[derive(Debug, Clone)]
+pub struct Article {
+    pub pmid: String,
+    pub title: String,
+    pub authors: Vec<Author>,
+    pub journal: Option<String>,
+    pub year: Option<i32>,
+    pub volume: Option<String>,
+    pub issue: Option<String>,
+    pub pages: Option<String>,
+    pub doi: Option<String>,
+    pub abstract_: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    esearchresult: SearchResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResult {
+    #[serde(default)]
+    idlist: Vec<String>,
+}
+
+/// Searches PubMed, or fetches the article if `query` is a PubMed ID.
+pub fn search(query: &str) -> Result<Vec<Article>> {
+    let query = query.trim();
+    if !query.is_empty() && query.chars().all(|c| c.is_ascii_digit()) {
+        return fetch(&[query]);
+    }
+    let response: SearchResponse = http::PUBMED
+        .get(format!("{EUTILS}/esearch.fcgi"))
+        .query(&[
+            ("db", "pubmed"),
+            ("term", query),
+            ("retmode", "json"),
+            ("retmax", "20"),
+            ("tool", "zime"),
+        ])
+        .send()?
+        .error_for_status()?
+        .json()?;
+    let ids: Vec<&str> = response
+        .esearchresult
+        .idlist
+        .iter()
+        .map(String::as_str)
+        .collect();
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    fetch(&ids)
+}
+
+/// Fetches articles by their PubMed IDs.
+pub fn fetch(pmids: &[&str]) -> Result<Vec<Article>> {
+    let xml = http::PUBMED
+        .get(format!("{EUTILS}/efetch.fcgi"))
+        .query(&[
+            ("db", "pubmed"),
+            ("id", pmids.join(",").as_str()),
+            ("retmode", "xml"),
+            ("tool", "zime"),
+        ])
+        .send()?
+        .error_for_status()?
+        .text()?;
+    parse(&xml)
+}
+
+pub fn fetch_one(pmid: &str) -> Result<Article> {
+    fetch(&[pmid])?
+        .into_iter()
+        .next()
+        .ok_or_else(|| eyre!("PubMed has no article {pmid}"))
+}
+
+/// The descendant of `node` at `path`, following the first matching child.
+fn find<'a, 'i>(node: Node<'a, 'i>, path: &[&str]) -> Option<Node<'a, 'i>> {
+    path.iter().try_fold(node, |node, name| {
+        node.children().find(|c| c.has_tag_name(*name))
+    })
+}
+
+/// The text of `node`, including that of nested markup such as `<i>`.
+fn text(node: Node) -> String {
+    let text: String = node
+        .descendants()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .collect();
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn text_at(node: Node, path: &[&str]) -> Option<String> {
+    find(node, path).map(text).filter(|t| !t.is_empty())
+}
+
+fn parse(xml: &str) -> Result<Vec<Article>> {
+    let doc = roxmltree::Document::parse(xml)?;
+    let articles = doc
+        .descendants()
+        .filter(|node| node.has_tag_name("PubmedArticle"))
+        .filter_map(|node| {
+            let citation = find(node, &["MedlineCitation"])?;
+            let article = find(citation, &["Article"])?;
+            let issue = find(article, &["Journal", "JournalIssue"]);
+            let year = issue.and_then(|issue| {
+                text_at(issue, &["PubDate", "Year"])
+                    .or_else(|| text_at(issue, &["PubDate", "MedlineDate"]))
+                    .and_then(|date| date.get(..4)?.parse().ok())
+            });
+            let authors = find(article, &["AuthorList"])
+                .into_iter()
+                .flat_map(|list| list.children().filter(|c| c.has_tag_name("Author")))
+                .filter_map(|author| {
+                    if let Some(name) = text_at(author, &["CollectiveName"]) {
+                        return Some(Author::organization(&name));
+                    }
+                    Some(Author {
+                        family: text_at(author, &["LastName"])?,
+                        given: text_at(author, &["ForeName"]).unwrap_or_default(),
+                    })
+                })
+                .collect();
+            let abstract_ = find(article, &["Abstract"]).map(|abstract_| {
+                abstract_
+                    .children()
+                    .filter(|c| c.has_tag_name("AbstractText"))
+                    .map(text)
+                    .collect::<Vec<_>>()
+                    .join("\n\n")
+            });
+            let doi = find(node, &["PubmedData", "ArticleIdList"]).and_then(|list| {
+                list.children()
+                    .find(|id| id.attribute("IdType") == Some("doi"))
+                    .map(text)
+            });
+            Some(Article {
+                pmid: text_at(citation, &["PMID"])?,
+                title: text_at(article, &["ArticleTitle"])?
+                    .trim_end_matches('.')
+                    .to_string(),
+                authors,
+                journal: text_at(article, &["Journal", "Title"]),
+                year,
+                volume: issue.and_then(|issue| text_at(issue, &["Volume"])),
+                issue: issue.and_then(|issue| text_at(issue, &["Issue"])),
+                pages: text_at(article, &["Pagination", "MedlinePgn"]),
+                doi,
+                abstract_: abstract_.filter(|a| !a.is_empty()),
+            })
+        })
+        .collect();
+    Ok(articles)
+}
+
+impl Article {
+    pub fn record(&self) -> Record {
+        Record {
+            entry_type: "article",
+            title: self.title.clone(),
+            authors: self.authors.clone(),
+            year: self.year,
+            venue: self.journal.clone(),
+            volume: self.volume.clone(),
+            number: self.issue.clone(),
+            pages: self.pages.clone(),
+            doi: self.doi.clone(),
+            abstract_: self.abstract_.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+pub struct Pubmed;
+
+impl Remote for Pubmed {
+    fn name(&self) -> &'static str {
+        "pubmed"
+    }
+
+    fn description(&self) -> &'static str {
+        "PubMed, covering biomedicine, also looks up PubMed IDs"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            search: true,
+            fetch_entry: true,
+            ..Default::default()
+        }
+    }
+
+    fn search(&self, query: &str, _venue: Option<&Venue>, _first: usize) -> Result<Page> {
+        Ok(Page::all(search(query)?.into_iter().map(|article| {
+            Candidate {
+                authors: article
+                    .authors
+                    .iter()
+                    .map(|a| format!("{} {}", a.given, a.family).trim().to_string())
+                    .join(", "),
+                doi: article.doi,
+                provenance: Provenance::new("pubmed", &article.pmid),
+                title: article.title,
+            }
+        })))
+    }
+
+    fn fetch_entry(&self, id: &str) -> Result<Entry> {
+        Ok(fetch_one(id)?.record().into_entry())
+    }
+}
@@ -0,0 +1,142 @@
+//! Bibliographic metadata from remotes that do not serve BibTeX themselves,
+//! and its conversion into entries.
+
+use biblatex::{Chunk, Entry, EntryType, Spanned};
+
+#[derive(Debug, Default, Clone)]
+pub struct Record {
+    /// BibTeX entry type, e.g. `article`, defaults to `misc`
+    pub entry_type: &'static str,
+    pub title: String,
+    pub authors: Vec<Author>,
+    pub year: Option<i32>,
+    /// Journal or proceedings the work appeared in
+    pub venue: Option<String>,
+    pub volume: Option<String>,
+    pub number: Option<String>,
+    pub pages: Option<String>,
+    pub publisher: Option<String>,
+    pub doi: Option<String>,
+    pub url: Option<String>,
+    pub abstract_: Option<String>,
+    /// Where the conference took place, written as BibLaTeX's `venue`
+    pub location: Option<String>,
+    /// arXiv identifier and primary class
+    pub eprint: Option<(String, Option<String>)>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Author {
+    /// Empty for organizations
+    pub given: String,
+    pub family: String,
+}
+
+impl Author {
+    /// Splits a full name such as `Jane Q. Doe` at its last space.
+    pub fn from_full_name(name: &str) -> Self {
+        match name.trim().rsplit_once(' ') {
+            Some((given, family)) => Self {
+                given: given.to_string(),
+                family: family.to_string(),
+            },
+            None => Self::organization(name),
+        }
+    }
+
+    pub fn organization(name: &str) -> Self {
+        Self {
+            given: String::new(),
+            family: name.trim().to_string(),
+        }
+    }
+}
+
+impl Record {
+    /// A citation key such as `doe2020separation`, made of the first author's
+    /// family name, the year and the first long word of the title.
+    pub fn key(&self) -> String {
+        let clean = |s: &str| -> String {
+            s.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect()
+        };
+        let mut key = self
+            .authors
+            .first()
+            .map(|author| clean(&author.family))
+            .unwrap_or_default();
+        if let Some(year) = self.year {
+            key.push_str(&year.to_string());
+        }
+        if let Some(word) = self.title.split_whitespace().find(|w| w.len() > 3) {
+            key.push_str(&clean(word));
+        }
+        if key.is_empty() {
+            key.push_str("untitled");
+        }
+        key
+    }
+
+    pub fn into_entry(self) -> Entry {
+        let entry_type = if self.entry_type.is_empty() {
+            "misc"
+        } else {
+            self.entry_type
+        };
+        let mut entry = Entry::new(self.key(), EntryType::new(entry_type));
+        let normal = |s: String| Spanned::detached(Chunk::Normal(s));
+
+        entry.set("title", vec![normal(self.title)]);
+        if !self.authors.is_empty() {
+            let mut chunks = Vec::new();
+            for (i, author) in self.authors.into_iter().enumerate() {
+                if i > 0 {
+                    chunks.push(normal(" and ".to_string()));
+                }
+                chunks.push(if author.given.is_empty() {
+                    // braced, so that organizations are not split into names
+                    Spanned::detached(Chunk::Verbatim(author.family))
+                } else {
+                    normal(format!("{}, {}", author.family, author.given))
+                });
+            }
+            entry.set("author", chunks);
+        }
+        if let Some(year) = self.year {
+            entry.set("year", vec![normal(year.to_string())]);
+        }
+        if let Some(venue) = self.venue {
+            let field = match entry_type {
+                "article" => "journal",
+                "inproceedings" | "incollection" => "booktitle",
+                _ => "howpublished",
+            };
+            entry.set(field, vec![normal(venue)]);
+        }
+        let optional = [
+            ("volume", self.volume),
+            ("number", self.number),
+            ("pages", self.pages.map(|pages| pages.replace('-', "--"))),
+            ("publisher", self.publisher),
+            ("abstract", self.abstract_),
+            ("venue", self.location),
+            ("doi", self.doi),
+            ("url", self.url),
+        ];
+        for (field, value) in optional {
+            if let Some(value) = value.filter(|v| !v.is_empty()) {
+                entry.set(field, vec![normal(value)]);
+            }
+        }
+        if let Some((id, class)) = self.eprint {
+            entry.set("eprint", vec![normal(id)]);
+            entry.set("eprinttype", vec![normal("arxiv".to_string())]);
+            if let Some(class) = class {
+                entry.set("eprintclass", vec![normal(class)]);
+            }
+        }
+        entry
+    }
+}
@@ -1,24 +1,89 @@
+//! URL: `{mirror}/{doi}`
+//!
+//! Sci-Hub's mirrors come and go, so the ones in `http.scihub_mirrors` are
+//! tried in order, falling back to [`DEFAULT_MIRRORS`]. Once a mirror works,
+//! it is tried first for the rest of the run.
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
 use color_eyre::eyre::eyre;
-use tracing::debug;
+use tracing::{debug, warn};
+
+use crate::{
+    Result, doi, http,
+    remotes::{Capabilities, NotAvailable, Pdfs, Remote},
+};
 
-use crate::Result;
+/// Mirrors used when none are configured.
+pub const DEFAULT_MIRRORS: &[&str] = &[
+    "https://sci-hub.ru",
+    "https://sci-hub.se",
+    "https://sci-hub.st",
+];
 
-const USER_AGENT: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 11_3_1 like Mac OS X) AppleWebKit/603.1.30 (KHTML, like Gecko) Version/10.0 Mobile/14E304 Safari/602.1";
+/// The mirror that last answered.
+static WORKING: Mutex<Option<String>> = Mutex::new(None);
 
+/// The mirrors to try, the one that last answered first.
+pub fn mirrors() -> Vec<String> {
+    let configured = http::scihub_mirrors();
+    let mut mirrors: Vec<String> = if configured.is_empty() {
+        DEFAULT_MIRRORS.iter().map(|m| m.to_string()).collect()
+    } else {
+        configured.iter().map(|m| normalize(m)).collect()
+    };
+    if let Some(working) = WORKING.lock().unwrap().as_ref() {
+        if let Some(i) = mirrors.iter().position(|m| m == working) {
+            let working = mirrors.remove(i);
+            mirrors.insert(0, working);
+        }
+    }
+    mirrors
+}
+
+/// A mirror as a base URL, e.g. `https://sci-hub.se` for `sci-hub.se/`.
+fn normalize(mirror: &str) -> String {
+    let mirror = mirror.trim().trim_end_matches('/');
+    if mirror.contains("://") {
+        mirror.to_string()
+    } else {
+        format!("https://{mirror}")
+    }
+}
+
+/// Fetches a PDF from the first mirror that answers. A mirror that answers
+/// without the paper ends the search, as the mirrors share their library.
 pub fn fetch_pdf(doi: &str) -> Result<Vec<u8>> {
-    let url = format!("https://sci-hub.ru/{}", doi);
-    let response = reqwest::blocking::Client::builder()
-        .user_agent(USER_AGENT)
-        .build()?
-        .get(&url)
-        .send()?;
+    let mut last = None;
+    for mirror in mirrors() {
+        match fetch_from(&mirror, doi) {
+            Err(err) if err.downcast_ref::<NotAvailable>().is_none() => {
+                warn!(%mirror, %err, "Sci-Hub mirror failed, trying the next");
+                last = Some(err);
+            }
+            result => {
+                *WORKING.lock().unwrap() = Some(mirror);
+                return result;
+            }
+        }
+    }
+    Err(last.unwrap_or_else(|| eyre!("no Sci-Hub mirrors configured")))
+}
+
+fn fetch_from(mirror: &str, doi: &str) -> Result<Vec<u8>> {
+    let url = format!("{mirror}/{}", doi::url_path(doi));
+    let response = http::SCIHUB.get(&url).send()?.error_for_status()?;
     let body = response.text()?;
-    // println!("{}", body);
 
     let pdf_url = body
         .lines()
         .find(|line| line.contains(r#"embed type="application/pdf" src=""#))
-        .ok_or_else(|| eyre!("Could not find embed src in the response from Sci-Hub"))?
+        .ok_or_else(|| {
+            NotAvailable("Could not find embed src in the response from Sci-Hub".to_string())
+        })?
         .split("src=\"")
         .nth(1)
         .unwrap()
@@ -28,13 +93,57 @@ pub fn fetch_pdf(doi: &str) -> Result<Vec<u8>> {
 
     debug!(?pdf_url, "pdf url found");
 
-    let pdf_url = if pdf_url.starts_with("/") {
-        format!("https://sci-hub.ru{}", pdf_url)
+    let pdf_url = if pdf_url.starts_with("//") {
+        format!("https:{pdf_url}")
+    } else if pdf_url.starts_with("/") {
+        format!("{mirror}{pdf_url}")
     } else {
         pdf_url.to_string()
     };
 
     debug!(?pdf_url, "fetching pdf");
-    let pdf_response = reqwest::blocking::get(pdf_url)?;
+    let pdf_response = http::SCIHUB.get(pdf_url).send()?.error_for_status()?;
     Ok(pdf_response.bytes()?.to_vec())
 }
+
+/// Checks that `mirror` answers, returning how long it took.
+pub fn probe(mirror: &str) -> Result<Duration> {
+    let start = Instant::now();
+    http::SCIHUB.get(mirror).send()?.error_for_status()?;
+    Ok(start.elapsed())
+}
+
+pub struct SciHub;
+
+impl Remote for SciHub {
+    fn name(&self) -> &'static str {
+        "scihub"
+    }
+
+    fn description(&self) -> &'static str {
+        "Sci-Hub"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            pdfs: Pdfs::Any,
+            ..Default::default()
+        }
+    }
+
+    fn fetch_pdf(&self, doi: &str) -> Result<Vec<u8>> {
+        fetch_pdf(doi)
+    }
+
+    /// Succeeds if any mirror answers, `zime mirrors` lists them all.
+    fn ping(&self) -> Result<()> {
+        let mut last = Err(eyre!("no Sci-Hub mirrors are configured"));
+        for mirror in mirrors() {
+            last = probe(&mirror).map(drop);
+            if last.is_ok() {
+                break;
+            }
+        }
+        last
+    }
+}
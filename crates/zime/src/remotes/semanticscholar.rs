@@ -0,0 +1,252 @@
+//! URL: `https://api.semanticscholar.org/graph/v1/paper/search?query={query}`
+//!
+//! Semantic Scholar covers all fields and indexes preprints quickly, which
+//! DBLP does not.
+//!
+//! Its recommendations, from
+//! `https://api.semanticscholar.org/recommendations/v1/papers/forpaper/{id}`,
+//! back `zime recommend`, and the papers citing a paper `zime cited-by`.
+
+use biblatex::Entry;
+use itertools::Itertools;
+
+use crate::{
+    Result, http,
+    provenance::{self, Provenance},
+    remotes::{
+        Candidate, Capabilities, Page, Remote,
+        record::{Author, Record},
+    },
+    venues::Venue,
+};
+
+const API: &str = "https://api.semanticscholar.org/graph/v1";
+const RECOMMENDATIONS_API: &str = "https://api.semanticscholar.org/recommendations/v1";
+const SEARCH_FIELDS: &str = "title,authors,year,venue,externalIds";
+const PAPER_FIELDS: &str =
+    "title,authors,year,venue,journal,externalIds,abstract,publicationTypes,url";
+
+pub fn search(query: &str, venue: Option<&str>) -> Result<Vec<response::Paper>> {
+    let mut params = vec![("query", query), ("fields", SEARCH_FIELDS), ("limit", "20")];
+    if let Some(venue) = venue {
+        params.push(("venue", venue));
+    }
+    let response: response::Search = http::SEMANTIC_SCHOLAR
+        .get(format!("{API}/paper/search"))
+        .query(&params)
+        .send()?
+        .error_for_status()?
+        .json()?;
+    Ok(response.data)
+}
+
+/// Fetches a paper by its Semantic Scholar ID, or an external ID such as
+/// `DOI:10.1145/3290380` or `ARXIV:2103.03230`.
+pub fn fetch_paper(id: &str) -> Result<response::Paper> {
+    http::SEMANTIC_SCHOLAR
+        .get(format!("{API}/paper/{id}"))
+        .query(&[("fields", PAPER_FIELDS)])
+        .send()?
+        .error_for_status()?
+        .json()
+        .map_err(Into::into)
+}
+
+/// How Semantic Scholar identifies `entry`, by its DOI, its Semantic Scholar
+/// ID or its arXiv ID.
+pub fn paper_id(entry: &Entry) -> Option<String> {
+    if let Ok(doi) = entry.doi() {
+        return Some(format!("DOI:{doi}"));
+    }
+    let provenance = provenance::get(entry)?;
+    match provenance.source.as_str() {
+        "semanticscholar" => Some(provenance.id),
+        "arxiv" => Some(format!("ARXIV:{}", provenance.id)),
+        _ => None,
+    }
+}
+
+/// Papers related to the paper with `id`, which takes the same forms as for
+/// [`fetch_paper`].
+pub fn recommend(id: &str, limit: usize) -> Result<Vec<response::Paper>> {
+    let response: response::Recommendations = http::SEMANTIC_SCHOLAR
+        .get(format!("{RECOMMENDATIONS_API}/papers/forpaper/{id}"))
+        .query(&[("fields", SEARCH_FIELDS), ("limit", &limit.to_string())])
+        .send()?
+        .error_for_status()?
+        .json()?;
+    Ok(response.recommended_papers)
+}
+
+/// Papers citing the paper with `id`, at most `limit` of them.
+pub fn citations(id: &str, limit: usize) -> Result<Vec<response::Paper>> {
+    let response: response::Citations = http::SEMANTIC_SCHOLAR
+        .get(format!("{API}/paper/{id}/citations"))
+        .query(&[("fields", SEARCH_FIELDS), ("limit", &limit.to_string())])
+        .send()?
+        .error_for_status()?
+        .json()?;
+    Ok(response
+        .data
+        .into_iter()
+        .map(|citation| citation.citing_paper)
+        .filter(|paper| !paper.paper_id.is_empty())
+        .collect())
+}
+
+impl response::Paper {
+    /// The DOI of the paper, or its arXiv DOI for preprints.
+    pub fn doi(&self) -> Option<String> {
+        let ids = self.external_ids.as_ref()?;
+        ids.doi
+            .clone()
+            .or_else(|| ids.arxiv.as_ref().map(|id| format!("10.48550/arXiv.{id}")))
+    }
+
+    pub fn candidate(self) -> Candidate {
+        Candidate {
+            authors: self.authors.iter().map(|a| &a.name).join(", "),
+            doi: self.doi(),
+            provenance: Provenance::new("semanticscholar", &self.paper_id),
+            title: self.title,
+        }
+    }
+
+    pub fn record(&self) -> Record {
+        let types = self.publication_types.as_deref().unwrap_or_default();
+        let entry_type = if types.iter().any(|t| t == "JournalArticle") {
+            "article"
+        } else if types.iter().any(|t| t == "Conference") {
+            "inproceedings"
+        } else {
+            "misc"
+        };
+        let journal = self.journal.as_ref();
+        Record {
+            entry_type,
+            title: self.title.clone(),
+            authors: self
+                .authors
+                .iter()
+                .map(|author| Author::from_full_name(&author.name))
+                .collect(),
+            year: self.year,
+            venue: journal
+                .and_then(|j| j.name.clone())
+                .or_else(|| self.venue.clone())
+                .filter(|venue| !venue.is_empty()),
+            volume: journal.and_then(|j| j.volume.clone()),
+            pages: journal.and_then(|j| j.pages.clone()),
+            doi: self.doi(),
+            url: self.url.clone(),
+            abstract_: self.abstract_.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+pub struct SemanticScholar;
+
+impl Remote for SemanticScholar {
+    fn name(&self) -> &'static str {
+        "semanticscholar"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["s2"]
+    }
+
+    fn description(&self) -> &'static str {
+        "Semantic Scholar, covering all fields"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            search: true,
+            fetch_entry: true,
+            ..Default::default()
+        }
+    }
+
+    fn search(&self, query: &str, venue: Option<&Venue>, _first: usize) -> Result<Page> {
+        Ok(Page::all(
+            search(query, venue.map(|v| v.name.as_str()))?
+                .into_iter()
+                .map(response::Paper::candidate),
+        ))
+    }
+
+    fn fetch_entry(&self, id: &str) -> Result<Entry> {
+        Ok(fetch_paper(id)?.record().into_entry())
+    }
+}
+
+pub mod response {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct Search {
+        #[serde(default)]
+        pub data: Vec<Paper>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Recommendations {
+        #[serde(default)]
+        pub recommended_papers: Vec<Paper>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct Citations {
+        #[serde(default)]
+        pub data: Vec<Citation>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Citation {
+        pub citing_paper: Paper,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Paper {
+        /// Missing for citing papers Semantic Scholar has not indexed
+        #[serde(default)]
+        pub paper_id: String,
+        #[serde(default)]
+        pub title: String,
+        #[serde(default)]
+        pub authors: Vec<Author>,
+        pub year: Option<i32>,
+        pub venue: Option<String>,
+        pub journal: Option<Journal>,
+        pub external_ids: Option<ExternalIds>,
+        #[serde(rename = "abstract")]
+        pub abstract_: Option<String>,
+        /// For example `JournalArticle` or `Conference`
+        pub publication_types: Option<Vec<String>>,
+        pub url: Option<String>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct Author {
+        pub name: String,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct Journal {
+        pub name: Option<String>,
+        pub volume: Option<String>,
+        pub pages: Option<String>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct ExternalIds {
+        #[serde(rename = "DOI")]
+        pub doi: Option<String>,
+        #[serde(rename = "ArXiv")]
+        pub arxiv: Option<String>,
+    }
+}
@@ -0,0 +1,118 @@
+//! URL: `https://papers.ssrn.com/sol3/Delivery.cfm?abstractid={id}` for PDFs
+//!
+//! SSRN has no public API, but registers its working papers with Crossref
+//! under `10.2139/ssrn.{id}`, which is where their metadata comes from. Only
+//! papers the authors made freely downloadable have a PDF.
+
+use color_eyre::eyre::eyre;
+use itertools::Itertools;
+
+use crate::{
+    Result, doi, http,
+    provenance::Provenance,
+    remotes::{Candidate, Capabilities, NotAvailable, Page, Pdfs, Remote, crossref},
+    venues::Venue,
+};
+
+const PREFIX: &str = "10.2139/ssrn.";
+
+pub fn is_ssrn(doi: &str) -> bool {
+    doi::normalize(doi).starts_with(PREFIX)
+}
+
+/// The SSRN abstract ID of `query`, given as a number, an `ssrn:` ID, an SSRN
+/// URL or an SSRN DOI.
+pub fn parse_id(query: &str) -> Option<String> {
+    let query = query.trim();
+    let id = if is_ssrn(query) {
+        doi::normalize(query)[PREFIX.len()..].to_string()
+    } else if let Some((_, rest)) = query.split_once("abstract_id=") {
+        rest.chars().take_while(char::is_ascii_digit).collect()
+    } else {
+        query
+            .strip_prefix("ssrn:")
+            .unwrap_or(query)
+            .trim()
+            .to_string()
+    };
+    (!id.is_empty() && id.chars().all(|c| c.is_ascii_digit())).then_some(id)
+}
+
+pub fn doi(id: &str) -> String {
+    format!("{PREFIX}{id}")
+}
+
+/// Looks up a working paper by SSRN ID, URL or DOI.
+pub fn fetch_work(query: &str) -> Result<crossref::response::Work> {
+    let id = parse_id(query).ok_or_else(|| eyre!("{query} is not an SSRN ID or DOI"))?;
+    crossref::fetch_work(&doi(&id))
+}
+
+/// Fetches the PDF of a working paper given its DOI, if it is downloadable
+/// without signing in.
+pub fn fetch_pdf(doi: &str) -> Result<Vec<u8>> {
+    let id = parse_id(doi).ok_or_else(|| eyre!("{doi} is not an SSRN DOI"))?;
+    let pdf = http::SSRN
+        .get("https://papers.ssrn.com/sol3/Delivery.cfm")
+        .query(&[("abstractid", id.as_str())])
+        .send()?
+        .error_for_status()?
+        .bytes()?;
+    // SSRN answers with a sign-in page for papers that are not free
+    if !pdf.starts_with(b"%PDF") {
+        return Err(NotAvailable("SSRN does not offer this paper for download".to_string()).into());
+    }
+    Ok(pdf.to_vec())
+}
+
+pub struct Ssrn;
+
+impl Remote for Ssrn {
+    fn name(&self) -> &'static str {
+        "ssrn"
+    }
+
+    fn description(&self) -> &'static str {
+        "SSRN working papers, looked up by SSRN ID or DOI"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            search: true,
+            pdfs: Pdfs::Hosted,
+            ..Default::default()
+        }
+    }
+
+    fn search(&self, query: &str, _venue: Option<&Venue>, _first: usize) -> Result<Page> {
+        let work = fetch_work(query)?;
+        Ok(Page::all([Candidate {
+            authors: work
+                .author
+                .iter()
+                .filter_map(|a| match (&a.given, &a.family) {
+                    (Some(given), Some(family)) => Some(format!("{given} {family}")),
+                    (_, family) => family.clone().or_else(|| a.name.clone()),
+                })
+                .join(", "),
+            title: work.title.first().cloned().unwrap_or_default(),
+            provenance: Provenance::new("crossref", &doi::normalize(&work.doi)),
+            doi: Some(work.doi),
+        }]))
+    }
+
+    fn hosts(&self, doi: &str) -> bool {
+        is_ssrn(doi)
+    }
+
+    fn fetch_pdf(&self, doi: &str) -> Result<Vec<u8>> {
+        fetch_pdf(doi)
+    }
+
+    /// Searching goes through Crossref, so only checks that SSRN answers,
+    /// whatever it answers with.
+    fn ping(&self) -> Result<()> {
+        http::SSRN.get("https://papers.ssrn.com").send()?;
+        Ok(())
+    }
+}
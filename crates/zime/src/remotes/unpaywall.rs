@@ -0,0 +1,104 @@
+//! URL: `https://api.unpaywall.org/v2/{doi}?email={mailto}`
+//!
+//! Unpaywall knows legal open access copies of papers, such as accepted
+//! manuscripts in institutional repositories. It requires a contact address,
+//! which is taken from `http.mailto` in the global config.
+
+use color_eyre::eyre::eyre;
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::{
+    Result, doi, http,
+    remotes::{Capabilities, NotAvailable, PING_DOI, Pdfs, Remote},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Response {
+    pub best_oa_location: Option<Location>,
+    #[serde(default)]
+    pub oa_locations: Vec<Location>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Location {
+    pub url_for_pdf: Option<String>,
+    /// `publisher` or `repository`
+    pub host_type: Option<String>,
+    pub license: Option<String>,
+}
+
+pub fn lookup(doi: &str) -> Result<Response> {
+    let mailto = http::mailto().ok_or_else(|| eyre!("Unpaywall requires http.mailto to be set"))?;
+    Ok(http::UNPAYWALL
+        .get(format!(
+            "https://api.unpaywall.org/v2/{}",
+            doi::url_path(doi)
+        ))
+        .query(&[("email", mailto)])
+        .send()?
+        .error_for_status()?
+        .json()?)
+}
+
+/// Fetches an open access PDF of the work with the given DOI, trying the best
+/// location first.
+pub fn fetch_pdf(doi: &str) -> Result<Vec<u8>> {
+    let response = lookup(doi)?;
+    let locations = response
+        .best_oa_location
+        .iter()
+        .chain(&response.oa_locations)
+        .filter_map(|location| Some((location.url_for_pdf.as_deref()?, location)));
+    for (url, location) in locations {
+        debug!(url, host=?location.host_type, license=?location.license, "fetching open access PDF");
+        let pdf = match http::OPEN_ACCESS
+            .get(url)
+            .send()
+            .and_then(|response| Ok(response.error_for_status()?.bytes()?))
+        {
+            Ok(pdf) => pdf,
+            Err(err) => {
+                debug!(url, %err, "failed to fetch open access PDF");
+                continue;
+            }
+        };
+        // landing pages are sometimes listed as PDFs
+        if pdf.starts_with(b"%PDF") {
+            return Ok(pdf.to_vec());
+        }
+        debug!(url, "open access location is not a PDF");
+    }
+    Err(NotAvailable("Unpaywall knows no open access PDF".to_string()).into())
+}
+
+pub struct Unpaywall;
+
+impl Remote for Unpaywall {
+    fn name(&self) -> &'static str {
+        "unpaywall"
+    }
+
+    fn description(&self) -> &'static str {
+        "Unpaywall, legal open access copies of papers"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            pdfs: Pdfs::Any,
+            ..Default::default()
+        }
+    }
+
+    fn fetch_pdf(&self, doi: &str) -> Result<Vec<u8>> {
+        fetch_pdf(doi)
+    }
+
+    fn missing_config(&self) -> Option<&'static str> {
+        http::mailto().is_none().then_some("http.mailto")
+    }
+
+    fn ping(&self) -> Result<()> {
+        lookup(PING_DOI).map(drop)
+    }
+}
@@ -0,0 +1,140 @@
+//! URL: `https://api.zbmath.org/v1/document/_search?search_string={query}`
+//!
+//! zbMATH Open indexes mathematics, including the journals and proceedings
+//! DBLP does not cover. Its BibTeX export is used for the entries themselves.
+
+use biblatex::Entry;
+use itertools::Itertools;
+use serde::Deserialize;
+
+use crate::{
+    Result, http,
+    provenance::Provenance,
+    remotes::{Candidate, Capabilities, Page, Remote, parse_entry},
+    venues::Venue,
+};
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    result: Vec<Document>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Document {
+    /// The zbMATH document number
+    pub id: u64,
+    pub title: Title,
+    #[serde(default)]
+    pub contributors: Contributors,
+    #[serde(default)]
+    pub links: Vec<Link>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Title {
+    pub title: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Contributors {
+    #[serde(default)]
+    pub authors: Vec<Person>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Person {
+    /// For example `Doe, Jane`
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Link {
+    #[serde(rename = "type")]
+    pub link_type: String,
+    pub identifier: Option<String>,
+}
+
+/// Searches zbMATH, see the [query syntax](https://zbmath.org/search-syntax).
+pub fn search(query: &str) -> Result<Vec<Document>> {
+    let response: SearchResponse = http::ZBMATH
+        .get("https://api.zbmath.org/v1/document/_search")
+        .query(&[
+            ("search_string", query),
+            ("page", "0"),
+            ("results_per_page", "20"),
+        ])
+        .send()?
+        .error_for_status()?
+        .json()?;
+    Ok(response.result)
+}
+
+/// Download the .bib of a document by its zbMATH document number
+pub fn fetch_bib(id: &str) -> Result<String> {
+    http::ZBMATH
+        .get(format!("https://zbmath.org/bibtex/{id}.bib"))
+        .send()?
+        .error_for_status()?
+        .text()
+        .map_err(Into::into)
+}
+
+impl Document {
+    pub fn doi(&self) -> Option<String> {
+        self.links
+            .iter()
+            .find(|link| link.link_type == "doi")
+            .and_then(|link| link.identifier.clone())
+    }
+}
+
+impl Person {
+    /// The name as `Jane Doe`.
+    pub fn display_name(&self) -> String {
+        match self.name.split_once(", ") {
+            Some((family, given)) => format!("{given} {family}"),
+            None => self.name.clone(),
+        }
+    }
+}
+
+pub struct Zbmath;
+
+impl Remote for Zbmath {
+    fn name(&self) -> &'static str {
+        "zbmath"
+    }
+
+    fn description(&self) -> &'static str {
+        "zbMATH Open, covering mathematics"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            search: true,
+            fetch_entry: true,
+            ..Default::default()
+        }
+    }
+
+    fn search(&self, query: &str, _venue: Option<&Venue>, _first: usize) -> Result<Page> {
+        Ok(Page::all(search(query)?.into_iter().map(|document| {
+            Candidate {
+                authors: document
+                    .contributors
+                    .authors
+                    .iter()
+                    .map(|a| a.display_name())
+                    .join(", "),
+                doi: document.doi(),
+                provenance: Provenance::new("zbmath", &document.id.to_string()),
+                title: document.title.title,
+            }
+        })))
+    }
+
+    fn fetch_entry(&self, id: &str) -> Result<Entry> {
+        parse_entry(&fetch_bib(id)?, self.name(), id)
+    }
+}
@@ -0,0 +1,214 @@
+//! Restoring the bibliography and notes from a backup snapshot or a commit in
+//! the library's git history.
+//!
+//! The bibliography comes back with the state kept next to it: `zime.lock`,
+//! the archive and the local-only entries, which commits only have the first
+//! two of.
+
+use std::{fs, io::Read};
+
+use camino::Utf8PathBuf;
+use color_eyre::{
+    eyre::{bail, eyre},
+    owo_colors::OwoColorize,
+};
+use duct::cmd;
+use tracing::debug;
+use zip::ZipArchive;
+
+use crate::{
+    Result, archive, backup, bundle, config::Setup, diff, dry_run, i18n::t, local, prompt, sidecar,
+    transaction::Transaction,
+};
+
+/// The files restored along with the bibliography, relative to the library
+const STATE: [&str; 5] = [
+    sidecar::FILE,
+    sidecar::LOCAL_FILE,
+    "archive.bib",
+    archive::LOCAL_FILE,
+    local::FILE,
+];
+
+#[derive(Debug)]
+pub enum Source {
+    Snapshot(Utf8PathBuf),
+    Commit(String),
+}
+
+/// Interprets `from` as a snapshot file, a snapshot name, or a git revision,
+/// in that order.
+pub fn resolve(setup: &Setup, from: &str) -> Result<Source> {
+    let path = Utf8PathBuf::from(from);
+    if path.is_file() {
+        return Ok(Source::Snapshot(path));
+    }
+
+    let config = setup.config()?.backup;
+    let matching: Vec<_> = backup::list(setup, &config)?
+        .into_iter()
+        .filter(|snapshot| snapshot.file_name().is_some_and(|name| name.contains(from)))
+        .collect();
+    match matching.as_slice() {
+        [snapshot] => return Ok(Source::Snapshot(snapshot.clone())),
+        [] => {}
+        _ => bail!(
+            "{from} matches {} snapshots, be more specific",
+            matching.len()
+        ),
+    }
+
+    if cmd!(
+        "git",
+        "rev-parse",
+        "--verify",
+        "--quiet",
+        format!("{from}^{{commit}}")
+    )
+    .dir(setup.root())
+    .stdout_null()
+    .stderr_null()
+    .run()
+    .is_ok()
+    {
+        return Ok(Source::Commit(from.to_string()));
+    }
+
+    bail!(
+        "{from} is neither a backup snapshot nor a commit in {}",
+        setup.root()
+    )
+}
+
+struct Restored {
+    bib: String,
+    /// The files of [`STATE`] that exist in the source and, if asked for,
+    /// notes, as paths relative to the library root and their content
+    files: Vec<(Utf8PathBuf, Vec<u8>)>,
+}
+
+fn load(setup: &Setup, source: &Source, with_notes: bool) -> Result<Restored> {
+    match source {
+        Source::Snapshot(path) => {
+            let mut archive = ZipArchive::new(fs::File::open(path)?)?;
+            let mut bib = String::new();
+            archive.by_name(bundle::BIB)?.read_to_string(&mut bib)?;
+            let mut files = Vec::new();
+            for name in STATE {
+                if let Ok(mut file) = archive.by_name(name) {
+                    let mut content = Vec::new();
+                    file.read_to_end(&mut content)?;
+                    files.push((Utf8PathBuf::from(name), content));
+                }
+            }
+            if with_notes {
+                for i in 0..archive.len() {
+                    let mut file = archive.by_index(i)?;
+                    let Some(name) = file
+                        .enclosed_name()
+                        .and_then(|name| Utf8PathBuf::from_path_buf(name).ok())
+                    else {
+                        continue;
+                    };
+                    if name.starts_with("notes") && file.is_file() {
+                        let mut content = Vec::new();
+                        file.read_to_end(&mut content)?;
+                        files.push((name, content));
+                    }
+                }
+            }
+            Ok(Restored { bib, files })
+        }
+        Source::Commit(rev) => {
+            let bib = cmd!("git", "show", format!("{rev}:{}", bundle::BIB))
+                .dir(setup.root())
+                .read()?;
+            let mut files = Vec::new();
+            for name in STATE {
+                // Not every state file is committed
+                if let Ok(output) = cmd!("git", "show", format!("{rev}:./{name}"))
+                    .dir(setup.root())
+                    .stdout_capture()
+                    .stderr_null()
+                    .run()
+                {
+                    files.push((Utf8PathBuf::from(name), output.stdout));
+                }
+            }
+            if with_notes {
+                let listed = cmd!("git", "ls-tree", "-r", "--name-only", rev, "--", "notes")
+                    .dir(setup.root())
+                    .read()?;
+                for file in listed.lines() {
+                    let content = cmd!("git", "show", format!("{rev}:{file}"))
+                        .dir(setup.root())
+                        .stdout_capture()
+                        .run()?
+                        .stdout;
+                    files.push((Utf8PathBuf::from(file), content));
+                }
+            }
+            Ok(Restored { bib, files })
+        }
+    }
+}
+
+pub fn run(setup: &Setup, from: &str, with_notes: bool, yes: bool) -> Result<()> {
+    let source = resolve(setup, from)?;
+    debug!(?source, "restoring");
+    let restored = load(setup, &source, with_notes)?;
+
+    let current = setup.bib()?;
+    let mut target = biblatex::Bibliography::parse(&restored.bib)
+        .map_err(|err| eyre!("failed to parse restored bibliography: {err}"))?;
+    // The local-only entries are part of the bibliography as zime sees it
+    if let Some((_, content)) = restored.files.iter().find(|(name, _)| name == local::FILE) {
+        let local = biblatex::Bibliography::parse(&String::from_utf8_lossy(content))
+            .map_err(|err| eyre!("failed to parse restored local-only entries: {err}"))?;
+        for entry in local {
+            target.insert(entry);
+        }
+    }
+    let changes = diff::diff(&current, &target);
+
+    let files: Vec<_> = restored
+        .files
+        .into_iter()
+        .filter(|(name, content)| fs::read(setup.root().join(name)).ok().as_ref() != Some(content))
+        .collect();
+
+    if changes.is_empty() && files.is_empty() {
+        println!("{}", t!("nothing-to-restore", from = from));
+        return Ok(());
+    }
+
+    diff::print(&changes);
+    for (name, _) in &files {
+        println!("{} {}", "~".yellow(), name.yellow());
+    }
+
+    if !yes && !prompt::confirm(t!("confirm-restore", from = from))? {
+        return Ok(());
+    }
+
+    backup::before_destructive(setup, "restore")?;
+    let mut tx = Transaction::begin(setup)?;
+    setup.write_bib(&target)?;
+    // After the bibliography, which reconciles the sidecar with it
+    for (name, content) in files {
+        let path = setup.root().join(name);
+        if dry_run::enabled() {
+            dry_run::would_write(&path);
+            continue;
+        }
+        tx.track(&path)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)?;
+    }
+    setup.sync_git()?;
+    tx.commit();
+
+    Ok(())
+}
@@ -0,0 +1,172 @@
+//! Finding entries in the library from a user query and letting the user pick
+//! one of the matches.
+
+use biblatex::{Bibliography, ChunksExt, Entry};
+use color_eyre::{eyre::eyre, owo_colors::OwoColorize};
+use itertools::Itertools;
+
+use crate::{Result, doi, entry, i18n::t, prompt};
+
+/// Minimum score for an entry to be considered a match.
+const THRESHOLD: f64 = 0.7;
+
+/// Entries matching the query, best matches first.
+///
+/// An entry whose DOI equals the query or whose title contains it matches
+/// exactly. Otherwise every word of the query is compared against the words
+/// of the title, the author names, the year and the key, tolerating typos, and
+/// the entry is scored by how well the query words matched on average.
+pub fn find<'a>(bib: &'a Bibliography, query: &str) -> Vec<&'a Entry> {
+    let query_lower = query.to_lowercase();
+    let query_words = words(&query_lower);
+
+    let mut scored: Vec<(f64, &Entry)> = bib
+        .iter()
+        .filter_map(|entry| {
+            let exact = entry
+                .doi()
+                .map(|doi| doi::eq(&doi, query))
+                .unwrap_or_default()
+                || entry
+                    .title()
+                    .map(|title| {
+                        title
+                            .to_biblatex_string(false)
+                            .to_lowercase()
+                            .contains(&query_lower)
+                    })
+                    .unwrap_or_default();
+            let score = if exact {
+                2.0
+            } else {
+                score(&query_words, &haystack(entry))
+            };
+            (score >= THRESHOLD).then_some((score, entry))
+        })
+        .collect();
+    scored.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+    scored.into_iter().map(|(_, entry)| entry).collect_vec()
+}
+
+fn haystack(entry: &Entry) -> Vec<String> {
+    let mut text = entry::title(entry);
+    for author in entry.author().unwrap_or_default() {
+        text.push(' ');
+        text.push_str(&author.given_name);
+        text.push(' ');
+        text.push_str(&author.name);
+    }
+    if let Some(year) = entry::year(entry) {
+        text.push_str(&format!(" {year}"));
+    }
+    text.push(' ');
+    text.push_str(&entry.key);
+    words(&text.to_lowercase())
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The average over the query words of their best similarity to any word in
+/// the haystack.
+fn score(query: &[String], haystack: &[String]) -> f64 {
+    if query.is_empty() || haystack.is_empty() {
+        return 0.0;
+    }
+    let total: f64 = query
+        .iter()
+        .map(|q| {
+            haystack
+                .iter()
+                .map(|h| {
+                    if q.len() >= 3 && h.starts_with(q.as_str()) {
+                        1.0
+                    } else {
+                        strsim::normalized_levenshtein(q, h)
+                    }
+                })
+                .fold(0.0, f64::max)
+        })
+        .sum();
+    total / query.len() as f64
+}
+
+/// Lets the user pick one of `entries`.
+pub fn select<'a>(prompt: &str, entries: &[&'a Entry]) -> Result<&'a Entry> {
+    let selection = prompt::select(
+        prompt,
+        entries.iter().map(|entry| {
+            (
+                format!("{} ({})", entry::title(entry).bold(), entry::authors(entry)),
+                if let Ok(doi) = entry.doi() {
+                    format!("DOI: {}", doi)
+                } else {
+                    "".to_string()
+                },
+            )
+        }),
+        0,
+    )?;
+    Ok(entries[selection])
+}
+
+/// Lets the user pick any of `entries`, all of them selected initially.
+pub fn select_many<'a>(prompt: &str, entries: &[&'a Entry]) -> Result<Vec<&'a Entry>> {
+    let selection = prompt::multiselect(
+        prompt,
+        entries.iter().map(|entry| {
+            (
+                format!("{} ({})", entry::title(entry).bold(), entry::authors(entry)),
+                "",
+            )
+        }),
+        &(0..entries.len()).collect_vec(),
+    )?;
+    Ok(selection.into_iter().map(|i| entries[i]).collect())
+}
+
+/// Finds the entries matching `query` and lets the user pick which of them to
+/// use, or uses all of them if `all` is set or there is only one.
+pub fn find_many<'a>(
+    bib: &'a Bibliography,
+    query: &str,
+    prompt: &str,
+    all: bool,
+) -> Result<Vec<&'a Entry>> {
+    let entries = find(bib, query);
+    if entries.is_empty() {
+        return Err(eyre!(t!("no-entry-found", query = query)));
+    }
+    if all || entries.len() == 1 {
+        return Ok(entries);
+    }
+    // Without anyone to pick, acting on every fuzzy match would be a guess
+    if !prompt::interactive() {
+        return Err(eyre!(t!(
+            "query-ambiguous-many",
+            query = query,
+            count = entries.len()
+        )));
+    }
+    select_many(prompt, &entries)
+}
+
+/// Finds the entries matching `query` and lets the user pick one.
+pub fn find_one<'a>(bib: &'a Bibliography, query: &str, prompt: &str) -> Result<&'a Entry> {
+    let entries = find(bib, query);
+    if entries.is_empty() {
+        return Err(eyre!(t!("no-entry-found", query = query)));
+    }
+    if entries.len() > 1 && !prompt::interactive() {
+        return Err(eyre!(t!(
+            "query-ambiguous",
+            query = query,
+            count = entries.len()
+        )));
+    }
+    select(prompt, &entries)
+}
@@ -0,0 +1,36 @@
+//! Printing a link to an entry, optionally as a QR code for scanning with a
+//! phone.
+
+use biblatex::Entry;
+use color_eyre::eyre::eyre;
+use qrcode::{QrCode, render::unicode};
+
+use crate::{Result, entry, links};
+
+/// The canonical URL of an entry: its arXiv abstract page, its DOI, or
+/// otherwise its `url` field.
+pub fn url(entry: &Entry) -> Option<String> {
+    links::all(entry).into_iter().next().map(|link| link.url)
+}
+
+pub fn run(entry: &Entry, qr: bool) -> Result<()> {
+    let url = url(entry).ok_or_else(|| {
+        eyre!(
+            "{} has neither a DOI nor a URL to share",
+            entry::title(entry)
+        )
+    })?;
+    println!("{url}");
+    if qr {
+        let code = QrCode::new(url.as_bytes())?;
+        // draw light modules as blocks, so that the code scans on the usual
+        // dark terminal background
+        let image = code
+            .render::<unicode::Dense1x2>()
+            .dark_color(unicode::Dense1x2::Light)
+            .light_color(unicode::Dense1x2::Dark)
+            .build();
+        println!("{image}");
+    }
+    Ok(())
+}
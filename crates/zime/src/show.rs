@@ -0,0 +1,111 @@
+//! Printing everything zime knows about a single entry.
+
+use biblatex::{Bibliography, ChunksExt, Entry};
+use color_eyre::owo_colors::OwoColorize;
+
+use tracing::warn;
+
+use crate::{
+    config::Setup, entry, language, links, meta, provenance, sidecar::EntryMeta, versions,
+};
+
+pub fn print(
+    setup: &Setup,
+    bib: &Bibliography,
+    entry: &Entry,
+    sidecar: Option<&EntryMeta>,
+    meta: Option<&serde_yaml::Mapping>,
+) {
+    println!("{}", entry::title(entry).bold());
+    println!("  {}", entry::authors(entry).italic());
+
+    let field = |name: &str, value: Option<String>| {
+        if let Some(value) = value {
+            println!("{:>10}: {value}", name.dimmed());
+        }
+    };
+    field("key", Some(entry.key.clone()));
+    field("type", Some(entry.entry_type.to_string()));
+    field("language", language::get(entry));
+    field("year", entry::year(entry).map(|y| y.to_string()));
+    field("venue", entry::venue(entry));
+    field("doi", entry.doi().ok());
+    for link in links::all(entry) {
+        field(&link.kind.to_string(), Some(link.url));
+    }
+    for (relation, other) in versions::related(bib, entry) {
+        field(
+            relation,
+            Some(format!("{} ({})", entry::title(other), other.key)),
+        );
+    }
+    let tags = entry::tags(entry);
+    field("tags", (!tags.is_empty()).then(|| tags.join(", ")));
+    field(
+        "pdf",
+        entry
+            .doi()
+            .ok()
+            .map(|doi| setup.pdf_path(&doi))
+            .filter(|path| path.exists())
+            .map(|path| path.to_string()),
+    );
+    field(
+        "source",
+        provenance::get(entry).map(|p| match p.fetched {
+            Some(fetched) => format!("{} ({}), {}", p.source, p.id, fetched.strftime("%Y-%m-%d")),
+            None => format!("{} ({})", p.source, p.id),
+        }),
+    );
+    if let Some(meta) = sidecar {
+        field("status", meta.status.map(|s| s.to_string()));
+        field("rating", meta.rating.map(|rating| format!("{rating}/5")));
+        for (member, opinion) in &meta.members {
+            let opinion = [
+                opinion.status.map(|s| s.to_string()),
+                opinion.rating.map(|rating| format!("{rating}/5")),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+            field(member, (!opinion.is_empty()).then(|| opinion.join(", ")));
+        }
+        field(
+            "queued",
+            meta.queue.as_ref().map(|item| match item.due {
+                Some(due) => format!("priority {}, due {due}", item.priority),
+                None => format!("priority {}", item.priority),
+            }),
+        );
+        field(
+            "added",
+            meta.added.map(|t| t.strftime("%Y-%m-%d").to_string()),
+        );
+        field(
+            "modified",
+            meta.modified.map(|t| t.strftime("%Y-%m-%d").to_string()),
+        );
+        field(
+            "attention",
+            meta.altmetrics
+                .as_ref()
+                .filter(|altmetrics| altmetrics.total() > 0)
+                .map(|altmetrics| altmetrics.describe()),
+        );
+        for (name, value) in &meta.settings {
+            field(name, Some(value.clone()));
+        }
+    }
+    for (name, value) in meta.into_iter().flatten() {
+        field(&meta::display(name), Some(meta::display(value)));
+    }
+
+    if let Ok(abstract_) = entry.abstract_() {
+        println!("\n{}", abstract_.format_verbatim());
+    }
+    match language::translated_abstract(entry) {
+        Ok(Some(translation)) => println!("\n{}", translation.italic()),
+        Ok(None) => {}
+        Err(err) => warn!(%err, "failed to translate abstract"),
+    }
+}
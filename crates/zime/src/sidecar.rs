@@ -0,0 +1,226 @@
+//! Per-entry data that does not belong in the bibliography.
+//!
+//! `zime.lock` is a JSON file in the library keyed by citation key, holding
+//! read status, position in the reading queue, timestamps, a checksum of the
+//! entry, free-form settings and ratings, per member in a reading group. It is
+//! reconciled with the bibliography whenever the bibliography is written (see
+//! [`Setup::write_bib`]), so entries that are removed lose their data, and it
+//! is committed and synced along with the rest of the library. The data of
+//! local-only entries is kept in `local.lock` instead, which is not committed,
+//! see [`crate::local`].
+
+use std::{collections::BTreeMap, fs};
+
+use biblatex::{Bibliography, Entry};
+use camino::{Utf8Path, Utf8PathBuf};
+use color_eyre::eyre::eyre;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{Result, config::Setup, dry_run, group, local};
+
+pub const FILE: &str = "zime.lock";
+/// The data of the local-only entries
+pub const LOCAL_FILE: &str = "local.lock";
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Sidecar {
+    pub entries: BTreeMap<String, EntryMeta>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct EntryMeta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<ReadStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub added: Option<jiff::Timestamp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified: Option<jiff::Timestamp>,
+    /// SHA-256 of the entry as written to the bibliography, used to notice
+    /// when it was modified
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue: Option<crate::queue::QueueItem>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub settings: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub altmetrics: Option<crate::altmetrics::Altmetrics>,
+    /// From 1 to 5
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+    /// The arXiv version of a preprint when it was last fetched, see
+    /// [`crate::updates`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arxiv_version: Option<u32>,
+    /// Status and rating of each member of a reading group, by name, which
+    /// take the place of `status` and `rating` in reading-group mode
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub members: BTreeMap<String, MemberMeta>,
+}
+
+/// What a member of a reading group thinks of an entry, see [`crate::group`].
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MemberMeta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<ReadStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+}
+
+impl EntryMeta {
+    /// The read status of `member`, or the shared one outside reading groups.
+    pub fn status_of(&self, member: Option<&str>) -> Option<ReadStatus> {
+        match member {
+            Some(member) => self.members.get(member)?.status,
+            None => self.status,
+        }
+    }
+
+    pub fn set_status(&mut self, member: Option<&str>, status: ReadStatus) {
+        match member {
+            Some(member) => {
+                self.members.entry(member.to_string()).or_default().status = Some(status)
+            }
+            None => self.status = Some(status),
+        }
+    }
+
+    pub fn set_rating(&mut self, member: Option<&str>, rating: u8) {
+        match member {
+            Some(member) => {
+                self.members.entry(member.to_string()).or_default().rating = Some(rating)
+            }
+            None => self.rating = Some(rating),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadStatus {
+    Unread,
+    Reading,
+    Read,
+}
+
+impl std::fmt::Display for ReadStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ReadStatus::Unread => "unread",
+            ReadStatus::Reading => "reading",
+            ReadStatus::Read => "read",
+        };
+        f.write_str(s)
+    }
+}
+
+pub fn path(setup: &Setup) -> Utf8PathBuf {
+    setup.root().join(FILE)
+}
+
+pub fn local_path(setup: &Setup) -> Utf8PathBuf {
+    setup.root().join(LOCAL_FILE)
+}
+
+pub fn checksum(entry: &Entry) -> String {
+    let digest = Sha256::digest(entry.to_biblatex_string());
+    digest.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+impl Sidecar {
+    pub fn load(setup: &Setup) -> Result<Self> {
+        let mut sidecar = Self::read(&path(setup))?;
+        sidecar
+            .entries
+            .extend(Self::read(&local_path(setup))?.entries);
+        Ok(sidecar)
+    }
+
+    fn read(path: &Utf8Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| eyre!("failed to parse {path}: {err}"))
+    }
+
+    /// Writes the data of the shared entries to `zime.lock` and that of the
+    /// local-only entries, archived or not, to `local.lock`.
+    pub fn save(&self, setup: &Setup) -> Result<()> {
+        if dry_run::enabled() {
+            dry_run::would_write(&path(setup));
+            return Ok(());
+        }
+        let local_keys = local::keys(setup)?;
+        let (local, shared): (BTreeMap<_, _>, BTreeMap<_, _>) = self
+            .entries
+            .iter()
+            .map(|(key, meta)| (key.clone(), meta.clone()))
+            .partition(|(key, _)| local_keys.contains(key));
+        fs::write(
+            path(setup),
+            serde_json::to_string_pretty(&Sidecar { entries: shared })?,
+        )?;
+        let local_path = local_path(setup);
+        if !local.is_empty() || local_path.exists() {
+            fs::write(
+                local_path,
+                serde_json::to_string_pretty(&Sidecar { entries: local })?,
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&EntryMeta> {
+        self.entries.get(key)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> &mut EntryMeta {
+        self.entries.entry(key.to_string()).or_default()
+    }
+
+    /// Brings the sidecar in line with `bib`: data of removed entries is
+    /// dropped unless they were archived, new entries are timestamped, and
+    /// entries whose checksum changed are marked as modified.
+    pub fn reconcile(&mut self, bib: &Bibliography, archive: &Bibliography) {
+        let now = jiff::Timestamp::now();
+        let mut entries = BTreeMap::new();
+        for entry in archive.iter() {
+            if let Some(meta) = self.entries.remove(&entry.key) {
+                entries.insert(entry.key.clone(), meta);
+            }
+        }
+        for entry in bib.iter() {
+            let mut meta = self.entries.remove(&entry.key).unwrap_or_default();
+            let checksum = checksum(entry);
+            if meta.added.is_none() {
+                meta.added = Some(now);
+            } else if meta.checksum.as_ref() != Some(&checksum) {
+                meta.modified = Some(now);
+            }
+            meta.checksum = Some(checksum);
+            entries.insert(entry.key.clone(), meta);
+        }
+        self.entries = entries;
+    }
+}
+
+/// Sets the read status of an entry, for the current member in reading-group
+/// mode.
+pub fn mark(setup: &Setup, key: &str, status: ReadStatus) -> Result<()> {
+    let member = group::member(setup)?;
+    let mut sidecar = Sidecar::load(setup)?;
+    sidecar.get_mut(key).set_status(member.as_deref(), status);
+    sidecar.save(setup)
+}
+
+/// Rates an entry from 1 to 5, for the current member in reading-group mode.
+pub fn rate(setup: &Setup, key: &str, rating: u8) -> Result<()> {
+    let member = group::member(setup)?;
+    let mut sidecar = Sidecar::load(setup)?;
+    sidecar.get_mut(key).set_rating(member.as_deref(), rating);
+    sidecar.save(setup)
+}
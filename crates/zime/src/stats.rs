@@ -0,0 +1,252 @@
+//! Summary statistics about the library, and with `--growth` how it grew
+//! month by month.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use color_eyre::{eyre::bail, owo_colors::OwoColorize};
+use duct::cmd;
+
+use crate::{Result, bundle, config::Setup, entry, i18n::t, pdfs::Manifest, provenance, rankings};
+
+pub fn run(setup: &Setup) -> Result<()> {
+    let bib = setup.bib()?;
+    let rankings = rankings::load_all(setup)?;
+
+    let total = bib.len();
+    let mut with_doi = 0;
+    let mut with_pdf = 0;
+    let mut with_abstract = 0;
+    let mut tagged = 0;
+    let mut types: BTreeMap<String, usize> = BTreeMap::new();
+    let mut years: BTreeMap<i32, usize> = BTreeMap::new();
+    let mut ranks: Vec<BTreeMap<String, usize>> = vec![BTreeMap::new(); rankings.len()];
+    let mut people: BTreeMap<String, usize> = BTreeMap::new();
+
+    for entry in bib.iter() {
+        if let Ok(doi) = entry.doi() {
+            with_doi += 1;
+            if setup.pdf_path(&doi).exists() {
+                with_pdf += 1;
+            }
+        }
+        if entry.abstract_().is_ok() {
+            with_abstract += 1;
+        }
+        if !entry::tags(entry).is_empty() {
+            tagged += 1;
+        }
+        *types.entry(entry.entry_type.to_string()).or_default() += 1;
+        if let Some(year) = entry::year(entry) {
+            *years.entry(year).or_default() += 1;
+        }
+        if let Some(name) = provenance::added_by(entry) {
+            *people.entry(name).or_default() += 1;
+        }
+        for (ranking, counts) in rankings.iter().zip(&mut ranks) {
+            let rank = ranking.lookup(entry).unwrap_or("unranked");
+            *counts.entry(rank.to_string()).or_default() += 1;
+        }
+    }
+
+    let line = |label: &str, count: usize| {
+        println!("{:>14}: {count}", label.dimmed());
+    };
+    line("entries", total);
+    line("with DOI", with_doi);
+    line("with PDF", with_pdf);
+    line("with abstract", with_abstract);
+    line("tagged", tagged);
+    if let (Some(first), Some(last)) = (years.keys().next(), years.keys().next_back()) {
+        println!("{:>14}: {first}–{last}", "years".dimmed());
+    }
+
+    println!("\n{}", "by type".bold());
+    for (ty, count) in &types {
+        line(ty, *count);
+    }
+    for (ranking, counts) in rankings.iter().zip(&ranks) {
+        println!("\n{}", format!("by {} rank", ranking.name).bold());
+        for (rank, count) in counts {
+            line(rank, *count);
+        }
+    }
+    if !people.is_empty() {
+        println!("\n{}", "by person".bold());
+        for (name, count) in &people {
+            line(name, *count);
+        }
+    }
+    Ok(())
+}
+
+/// How `stats --growth` prints the monthly counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum GrowthFormat {
+    /// One sparkline per series
+    #[default]
+    Sparkline,
+    /// A `month,entries,pdfs,notes` row per month
+    Csv,
+}
+
+/// Counts per month, keyed by `YYYY-MM`.
+type Monthly = BTreeMap<String, usize>;
+
+/// Entries added per month, from the commits touching the bibliography. An
+/// entry counts as added in a commit that adds its `@type{key,` line without
+/// removing it elsewhere, so entries that are only moved do not count.
+fn entries_added(setup: &Setup) -> Result<Monthly> {
+    let log = cmd!(
+        "git",
+        "log",
+        "--format=%x00%as",
+        "--unified=0",
+        "-p",
+        "--",
+        bundle::BIB
+    )
+    .dir(setup.root())
+    .read()?;
+    let mut months = Monthly::new();
+    for commit in log.split('\0').filter(|commit| !commit.is_empty()) {
+        let Some(month) = commit.get(..7) else {
+            continue;
+        };
+        let keys = |prefix: &str| -> BTreeSet<&str> {
+            commit
+                .lines()
+                .filter_map(|line| line.strip_prefix(prefix)?.split_once('{'))
+                .filter_map(|(_, rest)| Some(rest.split(',').next()?.trim()))
+                .collect()
+        };
+        let added = keys("+@").difference(&keys("-@")).count();
+        if added > 0 {
+            *months.entry(month.to_string()).or_default() += added;
+        }
+    }
+    Ok(months)
+}
+
+/// Notes written per month, by the commit that added each note file.
+fn notes_written(setup: &Setup) -> Result<Monthly> {
+    let log = cmd!(
+        "git",
+        "log",
+        "--format=%x00%as",
+        "--diff-filter=A",
+        "--name-only",
+        "--",
+        "notes"
+    )
+    .dir(setup.root())
+    .read()?;
+    let mut months = Monthly::new();
+    for commit in log.split('\0').filter(|commit| !commit.is_empty()) {
+        let Some(month) = commit.get(..7) else {
+            continue;
+        };
+        let files = commit.lines().skip(1).filter(|l| !l.is_empty()).count();
+        if files > 0 {
+            *months.entry(month.to_string()).or_default() += files;
+        }
+    }
+    Ok(months)
+}
+
+/// PDFs downloaded per month. The PDF directory is not committed, so these
+/// come from the download times in the manifest instead of the git history.
+fn pdfs_downloaded(setup: &Setup) -> Result<Monthly> {
+    let manifest = Manifest::load(setup)?;
+    let mut months = Monthly::new();
+    for downloaded in manifest.files.values().filter_map(|r| r.downloaded) {
+        *months
+            .entry(downloaded.strftime("%Y-%m").to_string())
+            .or_default() += 1;
+    }
+    Ok(months)
+}
+
+/// Every month from `first` to `last`, both `YYYY-MM`.
+fn month_range(first: &str, last: &str) -> Vec<String> {
+    let parse = |month: &str| -> Option<(i32, u32)> {
+        let (year, month) = month.split_once('-')?;
+        Some((year.parse().ok()?, month.parse().ok()?))
+    };
+    let (Some((mut year, mut month)), Some(end)) = (parse(first), parse(last)) else {
+        return Vec::new();
+    };
+    let mut months = Vec::new();
+    while (year, month) <= end {
+        months.push(format!("{year:04}-{month:02}"));
+        (year, month) = if month == 12 {
+            (year + 1, 1)
+        } else {
+            (year, month + 1)
+        };
+    }
+    months
+}
+
+fn sparkline(counts: &[usize]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = counts.iter().copied().max().unwrap_or(0).max(1);
+    counts
+        .iter()
+        .map(|&count| match count {
+            0 => ' ',
+            _ => BARS[(count * BARS.len()).div_ceil(max) - 1],
+        })
+        .collect()
+}
+
+/// Prints entries added, PDFs downloaded and notes written per month.
+pub fn growth(setup: &Setup, format: GrowthFormat) -> Result<()> {
+    if setup.git().is_none() {
+        bail!(t!("blame-no-git"));
+    }
+    let series = [
+        ("entries", entries_added(setup)?),
+        ("PDFs", pdfs_downloaded(setup)?),
+        ("notes", notes_written(setup)?),
+    ];
+    let seen: BTreeSet<&String> = series.iter().flat_map(|(_, m)| m.keys()).collect();
+    let (Some(first), Some(last)) = (seen.first(), seen.last()) else {
+        return Ok(());
+    };
+    let months = month_range(first, last);
+    let counts = |monthly: &Monthly| -> Vec<usize> {
+        months
+            .iter()
+            .map(|month| monthly.get(month).copied().unwrap_or(0))
+            .collect()
+    };
+
+    match format {
+        GrowthFormat::Csv => {
+            println!("month,entries,pdfs,notes");
+            for month in &months {
+                let row = series
+                    .iter()
+                    .map(|(_, monthly)| monthly.get(month).copied().unwrap_or(0).to_string())
+                    .collect::<Vec<_>>()
+                    .join(",");
+                println!("{month},{row}");
+            }
+        }
+        GrowthFormat::Sparkline => {
+            println!("{:>14}: {first} – {last}", "months".dimmed());
+            for (label, monthly) in &series {
+                let counts = counts(monthly);
+                let total: usize = counts.iter().sum();
+                let max = counts.iter().copied().max().unwrap_or(0);
+                println!(
+                    "{:>14}: {} {}",
+                    label.dimmed(),
+                    sparkline(&counts),
+                    format!("{total} total, at most {max} a month").dimmed()
+                );
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,226 @@
+//! Where the library's entries are stored.
+//!
+//! By default `references.bib` is the source of truth. With
+//! `store.format = "json"` the entries are instead kept in `references.json`,
+//! which stores tags and provenance as structured data, and `references.bib` is
+//! regenerated from it on every write for consumption by LaTeX. Edits made to
+//! the generated file directly are overwritten.
+//!
+//! A library switched to the JSON store is converted the next time it is
+//! written, until then the existing `references.bib` is read.
+//!
+//! Either way, local-only entries are kept apart in `local.bib`, see
+//! [`crate::local`].
+
+use std::{collections::BTreeMap, fmt::Write, fs};
+
+use biblatex::{Bibliography, Chunk, ChunksExt, Entry, RawBibliography, RawChunk, Spanned};
+use camino::Utf8PathBuf;
+use color_eyre::eyre::eyre;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Result, archive,
+    config::Setup,
+    entry,
+    links::{self, Link},
+    local, macros,
+    provenance::{self, Provenance},
+    sidecar::Sidecar,
+    versions::{self, Version},
+};
+
+pub const JSON: &str = "references.json";
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct StoreConfig {
+    pub format: Format,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// `references.bib` is the source of truth
+    #[default]
+    Bib,
+    /// `references.json` is the source of truth
+    Json,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct Store {
+    entries: Vec<StoredEntry>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct StoredEntry {
+    key: String,
+    #[serde(rename = "type")]
+    entry_type: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    provenance: Option<Provenance>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    added_by: Option<String>,
+    /// Links besides the DOI and `url`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    urls: Vec<Link>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    versions: Vec<Version>,
+    /// All other fields as BibLaTeX source, without the enclosing braces
+    fields: BTreeMap<String, String>,
+}
+
+impl StoredEntry {
+    fn from_entry(entry: &Entry) -> Self {
+        let fields = entry
+            .fields
+            .iter()
+            .filter(|(name, _)| *name != "keywords" && !name.starts_with("zime-"))
+            .map(|(name, value)| {
+                let src = value.to_biblatex_string(is_verbatim_field(name));
+                let src = src
+                    .strip_prefix('{')
+                    .and_then(|s| s.strip_suffix('}'))
+                    .unwrap_or(&src);
+                (name.clone(), src.to_string())
+            })
+            .collect();
+        Self {
+            key: entry.key.clone(),
+            entry_type: entry.entry_type.to_biblatex().to_string(),
+            tags: entry::tags(entry),
+            provenance: provenance::get(entry),
+            added_by: provenance::added_by(entry),
+            urls: links::extra(entry),
+            versions: versions::get(entry),
+            fields,
+        }
+    }
+
+    fn write_biblatex(&self, out: &mut String) {
+        writeln!(out, "@{}{{{},", self.entry_type, self.key).unwrap();
+        for (name, value) in &self.fields {
+            writeln!(out, "{name} = {{{value}}},").unwrap();
+        }
+        if !self.tags.is_empty() {
+            writeln!(out, "keywords = {{{}}},", self.tags.join(", ")).unwrap();
+        }
+        out.push_str("}\n\n");
+    }
+}
+
+/// Fields whose content BibLaTeX does not interpret, which biblatex escapes
+/// differently when serializing.
+fn is_verbatim_field(name: &str) -> bool {
+    matches!(
+        name,
+        "file" | "doi" | "uri" | "eprint" | "verba" | "verbb" | "verbc" | "pdf" | "url" | "urlraw"
+    )
+}
+
+pub fn json_path(setup: &Setup) -> Utf8PathBuf {
+    setup.root().join(JSON)
+}
+
+fn format(setup: &Setup) -> Result<Format> {
+    Ok(setup.config()?.store.format)
+}
+
+/// Loads the bibliography from the configured store, with the local-only
+/// entries.
+pub fn load(setup: &Setup) -> Result<Bibliography> {
+    let mut bib = load_shared(setup)?;
+    for entry in local::load(setup)? {
+        bib.insert(entry);
+    }
+    Ok(bib)
+}
+
+fn load_shared(setup: &Setup) -> Result<Bibliography> {
+    let path = json_path(setup);
+    if format(setup)? == Format::Json && path.exists() {
+        let store: Store = serde_json::from_str(&fs::read_to_string(&path)?)
+            .map_err(|err| eyre!("failed to parse {path}: {err}"))?;
+        let mut src = String::new();
+        for stored in &store.entries {
+            stored.write_biblatex(&mut src);
+        }
+        let mut bib =
+            parse(&src).map_err(|err| eyre!("failed to parse entries of {path}: {err}"))?;
+        for stored in &store.entries {
+            let Some(entry) = bib.get_mut(&stored.key) else {
+                continue;
+            };
+            if let Some(provenance) = &stored.provenance {
+                provenance::set(entry, provenance);
+            }
+            if let Some(name) = &stored.added_by {
+                provenance::set_added_by(entry, name);
+            }
+            links::set_extra(entry, &stored.urls);
+            versions::set(entry, &stored.versions);
+        }
+        return Ok(bib);
+    }
+
+    let bib_path = setup.bib_path();
+    if !bib_path.exists() {
+        fs::write(&bib_path, "")?;
+    }
+    let src = fs::read_to_string(&bib_path)?;
+    parse(&src).map_err(|err| eyre!("failed to parse {bib_path}: {err}"))
+}
+
+/// Parses BibLaTeX source, keeping the `xdata` fields the parser drops once
+/// it has inherited from them, so that they survive the next write.
+pub fn parse(src: &str) -> Result<Bibliography, biblatex::ParseError> {
+    let mut bib = Bibliography::parse(src)?;
+    let raw = RawBibliography::parse(src)?;
+    for raw_entry in &raw.entries {
+        let Some(entry) = bib.get_mut(raw_entry.v.key.v) else {
+            continue;
+        };
+        for pair in &raw_entry.v.fields {
+            if !pair.key.v.eq_ignore_ascii_case("xdata") {
+                continue;
+            }
+            let keys: String = pair
+                .value
+                .v
+                .iter()
+                .map(|chunk| match chunk.v {
+                    RawChunk::Normal(s) | RawChunk::Abbreviation(s) => s,
+                })
+                .collect();
+            entry.set("xdata", vec![Spanned::detached(Chunk::Normal(keys))]);
+        }
+    }
+    Ok(bib)
+}
+
+/// Writes the bibliography to the configured store, regenerating
+/// `references.bib` if it is not the source of truth, with the local-only
+/// entries in `local.bib`, and reconciles the sidecar with it.
+pub fn save(setup: &Setup, bib: &Bibliography) -> Result<()> {
+    let config = setup.config()?;
+    let (shared, local) = local::split(setup, bib)?;
+    if config.store.format == Format::Json {
+        let store = Store {
+            entries: shared.iter().map(StoredEntry::from_entry).collect(),
+        };
+        fs::write(json_path(setup), serde_json::to_string_pretty(&store)?)?;
+    }
+    let previous = fs::read_to_string(setup.bib_path()).unwrap_or_default();
+    fs::write(
+        setup.bib_path(),
+        macros::to_biblatex_string(&shared, &previous, &config.strings),
+    )?;
+    local::save(setup, &local)?;
+
+    let mut sidecar = Sidecar::load(setup)?;
+    sidecar.reconcile(bib, &archive::load(setup)?);
+    sidecar.save(setup)
+}
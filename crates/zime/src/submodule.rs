@@ -0,0 +1,113 @@
+//! Project libraries kept as a git submodule of the paper repository.
+//!
+//! `zime init --local --git <url> --submodule` pushes the new library to its
+//! own repository and adds it to the enclosing repository as the `.zime`
+//! submodule, so that collaborators cloning the paper get the bibliography.
+//! On every sync the parent repository's pointer to the submodule is
+//! committed, and a library whose submodule was never checked out is checked
+//! out the first time zime looks for it.
+
+use camino::{Utf8Path, Utf8PathBuf};
+use duct::cmd;
+use tracing::{debug, info};
+
+use crate::Result;
+
+/// The working tree of the repository that has the library at `root` as a
+/// submodule.
+pub fn superproject(root: &Utf8Path) -> Option<Utf8PathBuf> {
+    let parent = cmd!("git", "rev-parse", "--show-superproject-working-tree")
+        .dir(root)
+        .stderr_null()
+        .read()
+        .ok()?;
+    (!parent.is_empty()).then(|| Utf8PathBuf::from(parent))
+}
+
+/// Checks out `main` if the submodule is on a detached head, as submodules
+/// are after `git submodule update`, so that the sync can commit and rebase.
+pub fn attach_head(root: &Utf8Path) -> Result<()> {
+    let on_branch = cmd!("git", "symbolic-ref", "-q", "HEAD")
+        .dir(root)
+        .stdout_null()
+        .unchecked()
+        .run()?
+        .status
+        .success();
+    if !on_branch {
+        debug!(%root, "checking out main in detached submodule");
+        cmd!("git", "checkout", "main").dir(root).run()?;
+    }
+    Ok(())
+}
+
+/// Commits the new commit of the submodule at `root` in the `parent`
+/// repository, if it moved. The parent is not pushed, that is left to the
+/// paper's authors.
+pub fn commit_in_superproject(root: &Utf8Path, parent: &Utf8Path) -> Result<()> {
+    let path = root.strip_prefix(parent).unwrap_or(root);
+    cmd!("git", "add", "--", path).dir(parent).run()?;
+    let unchanged = cmd!("git", "diff", "--cached", "--quiet", "--", path)
+        .dir(parent)
+        .unchecked()
+        .run()?
+        .status
+        .success();
+    if !unchanged {
+        info!(%parent, "committing bibliography in parent repository");
+        cmd!(
+            "git",
+            "commit",
+            "-m",
+            "zime: update bibliography",
+            "--",
+            path
+        )
+        .dir(parent)
+        .run()?;
+    }
+    Ok(())
+}
+
+/// Adds the library at `root`, pushed to `url`, as a submodule of the
+/// repository enclosing it, and commits it there.
+pub fn add(root: &Utf8Path, url: &str) -> Result<()> {
+    let dir = root.parent().unwrap_or(Utf8Path::new("."));
+    let name = root.file_name().unwrap_or(".zime");
+    cmd!("git", "submodule", "add", url, name).dir(dir).run()?;
+    cmd!(
+        "git",
+        "commit",
+        "-m",
+        "zime: add bibliography",
+        "--",
+        ".gitmodules",
+        name
+    )
+    .dir(dir)
+    .run()?;
+    Ok(())
+}
+
+/// Checks out the submodule at `dir` if it was cloned with the paper but
+/// never initialized, which leaves an empty directory.
+pub fn init_if_empty(dir: &Utf8Path) -> Result<()> {
+    if dir.read_dir_utf8()?.next().is_some() {
+        return Ok(());
+    }
+    let (Some(parent), Some(name)) = (dir.parent(), dir.file_name()) else {
+        return Ok(());
+    };
+    if cmd!("git", "ls-files", "--stage", "--", name)
+        .dir(parent)
+        .stderr_null()
+        .read()
+        .is_ok_and(|staged| staged.starts_with("160000"))
+    {
+        info!(%dir, "checking out bibliography submodule");
+        cmd!("git", "submodule", "update", "--init", "--", name)
+            .dir(parent)
+            .run()?;
+    }
+    Ok(())
+}
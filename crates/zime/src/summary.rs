@@ -0,0 +1,93 @@
+//! The summary printed at the end of bulk commands such as `pdfs` and
+//! `bundle import`, as a table or, with `--json`, as a single line of JSON for
+//! scripts.
+
+use std::collections::BTreeMap;
+
+use color_eyre::owo_colors::OwoColorize;
+use serde::Serialize;
+
+use crate::{Result, i18n::t};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Succeeded,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Item {
+    pub key: String,
+    pub status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Summary {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub items: Vec<Item>,
+}
+
+impl Summary {
+    fn push(&mut self, key: &str, status: Status, reason: Option<String>) {
+        match status {
+            Status::Succeeded => self.succeeded += 1,
+            Status::Failed => self.failed += 1,
+            Status::Skipped => self.skipped += 1,
+        }
+        self.items.push(Item {
+            key: key.to_string(),
+            status,
+            reason,
+        });
+    }
+
+    pub fn succeed(&mut self, key: &str) {
+        self.push(key, Status::Succeeded, None);
+    }
+
+    pub fn fail(&mut self, key: &str, reason: impl ToString) {
+        self.push(key, Status::Failed, Some(reason.to_string()));
+    }
+
+    pub fn skip(&mut self, key: &str, reason: impl ToString) {
+        self.push(key, Status::Skipped, Some(reason.to_string()));
+    }
+
+    /// How many items ended with `status` for each reason.
+    fn reasons(&self, status: Status) -> BTreeMap<&str, usize> {
+        let mut reasons = BTreeMap::new();
+        for item in self.items.iter().filter(|item| item.status == status) {
+            if let Some(reason) = &item.reason {
+                *reasons.entry(reason.as_str()).or_default() += 1;
+            }
+        }
+        reasons
+    }
+
+    pub fn print(&self, json: bool) -> Result<()> {
+        if json {
+            println!("{}", serde_json::to_string(self)?);
+            return Ok(());
+        }
+        for (status, label, count) in [
+            (Status::Succeeded, t!("summary-succeeded"), self.succeeded),
+            (Status::Failed, t!("summary-failed"), self.failed),
+            (Status::Skipped, t!("summary-skipped"), self.skipped),
+        ] {
+            let reasons = self
+                .reasons(status)
+                .into_iter()
+                .map(|(reason, count)| format!("{reason}: {count}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            println!("{:>10} {count:>5}  {}", label.bold(), reasons.dimmed());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+//! Tags added automatically to entries matching a query, when they are added
+//! and by `zime retag` across the library:
+//!
+//! ```toml
+//! [[tag_rules]]
+//! when = "venue:CAV OR venue:TACAS"
+//! tags = ["verification"]
+//!
+//! [[tag_rules]]
+//! when = "author:reynolds"
+//! tags = ["group"]
+//! ```
+//!
+//! Rules only add tags, so tags removed by hand from an entry that still
+//! matches come back on the next `retag`. They are matched against the
+//! bibliography only, so `status:` and `text:` terms are rejected.
+
+use std::collections::BTreeMap;
+
+use biblatex::{Bibliography, Entry};
+use color_eyre::eyre::{bail, eyre};
+use serde::{Deserialize, Serialize};
+
+use crate::{Result, config::Setup, entry, query::Query};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TagRule {
+    /// Query in the syntax of `list`, matched against the bibliography only
+    pub when: String,
+    pub tags: Vec<String>,
+}
+
+/// The parsed tag rules of a config.
+#[derive(Debug, Default)]
+pub struct Rules(Vec<(Query, Vec<String>)>);
+
+impl Rules {
+    pub fn load(setup: &Setup) -> Result<Self> {
+        setup
+            .config()?
+            .tag_rules
+            .iter()
+            .map(|rule| {
+                let query = Query::parse(&rule.when)
+                    .map_err(|err| eyre!("invalid tag rule {:?}: {err}", rule.when))?;
+                // Entries are tagged as they are added, before they have a
+                // read status or a PDF
+                if !query.bib_only() {
+                    bail!(
+                        "invalid tag rule {:?}: status: and text: terms are not supported",
+                        rule.when
+                    );
+                }
+                Ok((query, rule.tags.clone()))
+            })
+            .collect::<Result<_>>()
+            .map(Rules)
+    }
+
+    /// Adds the tags of every rule `entry` matches, returning those it did
+    /// not have.
+    pub fn apply(&self, entry: &mut Entry) -> Vec<String> {
+        let mut tags = entry::tags(entry);
+        let mut added = Vec::new();
+        for (query, rule_tags) in &self.0 {
+            if !query.matches(entry) {
+                continue;
+            }
+            for tag in rule_tags {
+                if !tags.contains(tag) {
+                    tags.push(tag.clone());
+                    added.push(tag.clone());
+                }
+            }
+        }
+        if !added.is_empty() {
+            entry::set_tags(entry, &tags);
+        }
+        added
+    }
+
+    /// Applies the rules to every entry of `bib`, returning the tags added by
+    /// key.
+    pub fn apply_all(&self, bib: &mut Bibliography) -> BTreeMap<String, Vec<String>> {
+        bib.iter_mut()
+            .filter_map(|entry| {
+                let added = self.apply(entry);
+                (!added.is_empty()).then(|| (entry.key.clone(), added))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,192 @@
+//! Normalizing the casing of titles, which remotes disagree on: DBLP keeps the
+//! publisher's title case, Crossref sometimes has titles in all caps.
+//!
+//! With a style configured, titles are cased when entries are added and by
+//! `zime fmt`:
+//!
+//! ```toml
+//! [title_case]
+//! style = "sentence"
+//! ```
+//!
+//! Acronyms and words with inner capitals, e.g. `SAT` or `LaTeX`, are wrapped
+//! in braces so that bibliography styles do not lowercase them. Math and text
+//! already in braces are left as they are. Only English titles are cased, as
+//! the rules differ between languages, e.g. German capitalizes every noun.
+
+use biblatex::{Bibliography, Chunk, Entry, Spanned};
+use serde::{Deserialize, Serialize};
+
+use crate::{Result, config::Setup, language};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TitleCaseConfig {
+    pub style: Style,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Style {
+    /// Leave titles as the remotes give them
+    #[default]
+    Keep,
+    /// Only the first word, and the first after a colon, capitalized
+    Sentence,
+    /// Every word capitalized, except articles, conjunctions and short
+    /// prepositions
+    Title,
+}
+
+/// Words not capitalized in title case, unless first or last.
+const SMALL_WORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "for", "from", "if", "in", "into", "nor", "of",
+    "off", "on", "onto", "or", "per", "so", "the", "to", "up", "via", "vs", "with", "yet",
+];
+
+/// Languages whose titles are cased, as BibLaTeX names them.
+const ENGLISH: &[&str] = &["english", "american", "british", "canadian", "australian"];
+
+enum Token {
+    Word(String),
+    Separator(String),
+    /// A chunk kept as it is
+    Kept(Chunk),
+}
+
+/// Splits the title into words, the text between them and chunks that are
+/// kept as they are.
+fn tokenize(chunks: &[Spanned<Chunk>]) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    for chunk in chunks {
+        let Chunk::Normal(text) = &chunk.v else {
+            tokens.push(Token::Kept(chunk.v.clone()));
+            continue;
+        };
+        let mut current = String::new();
+        let mut in_word = false;
+        for c in text.chars() {
+            let is_word = c.is_alphanumeric() || c == '\'' || c == '’';
+            if is_word != in_word && !current.is_empty() {
+                let token = std::mem::take(&mut current);
+                tokens.push(if in_word {
+                    Token::Word(token)
+                } else {
+                    Token::Separator(token)
+                });
+            }
+            in_word = is_word;
+            current.push(c);
+        }
+        if !current.is_empty() {
+            tokens.push(if in_word {
+                Token::Word(current)
+            } else {
+                Token::Separator(current)
+            });
+        }
+    }
+    tokens
+}
+
+/// Whether the casing of `word` carries meaning, e.g. `SAT`, `LaTeX` or `3D`.
+fn is_acronym(word: &str) -> bool {
+    word.chars().skip(1).any(char::is_uppercase)
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// The title chunks cased in `style`.
+pub fn apply(chunks: &[Spanned<Chunk>], style: Style) -> Vec<Spanned<Chunk>> {
+    if style == Style::Keep {
+        return chunks.to_vec();
+    }
+    let tokens = tokenize(chunks);
+    // A title in all caps has no acronyms to tell apart, so it is cased from
+    // lowercase
+    let shouting = !tokens.iter().any(|token| match token {
+        Token::Word(word) => word.chars().any(char::is_lowercase),
+        _ => false,
+    });
+    let last_word = tokens
+        .iter()
+        .rposition(|token| matches!(token, Token::Word(_)));
+
+    let mut out: Vec<Chunk> = Vec::new();
+    let mut push = |chunk: Chunk| match (out.last_mut(), chunk) {
+        (Some(Chunk::Normal(prev)), Chunk::Normal(text)) => prev.push_str(&text),
+        (_, chunk) => out.push(chunk),
+    };
+    let mut starts_sentence = true;
+    for (i, token) in tokens.into_iter().enumerate() {
+        match token {
+            Token::Separator(text) => {
+                if text.contains([':', '?', '!', '.']) {
+                    starts_sentence = true;
+                }
+                push(Chunk::Normal(text));
+            }
+            Token::Kept(chunk) => {
+                starts_sentence = false;
+                push(chunk);
+            }
+            Token::Word(word) if !shouting && is_acronym(&word) => {
+                starts_sentence = false;
+                push(Chunk::Verbatim(word));
+            }
+            Token::Word(word) => {
+                let word = if shouting { word.to_lowercase() } else { word };
+                let lower = word.to_lowercase();
+                let cased = match style {
+                    _ if starts_sentence => capitalize(&word),
+                    Style::Sentence => lower,
+                    Style::Title
+                        if Some(i) != last_word && SMALL_WORDS.contains(&lower.as_str()) =>
+                    {
+                        lower
+                    }
+                    _ => capitalize(&word),
+                };
+                starts_sentence = false;
+                push(Chunk::Normal(cased));
+            }
+        }
+    }
+    out.into_iter().map(Spanned::detached).collect()
+}
+
+/// Cases the title of `entry` as configured, unless it is not in English.
+/// Returns whether the title changed.
+pub fn normalize(entry: &mut Entry, style: Style) -> bool {
+    if style == Style::Keep {
+        return false;
+    }
+    if language::get(entry).is_some_and(|lang| !ENGLISH.contains(&lang.as_str())) {
+        return false;
+    }
+    let Ok(title) = entry.title() else {
+        return false;
+    };
+    let cased = apply(title, style);
+    let changed = cased.iter().map(|c| &c.v).ne(title.iter().map(|c| &c.v));
+    if changed {
+        entry.set("title", cased);
+    }
+    changed
+}
+
+/// Cases the titles of every entry of `bib`, returning the keys of those that
+/// changed.
+pub fn normalize_all(setup: &Setup, bib: &mut Bibliography) -> Result<Vec<String>> {
+    let style = setup.config()?.title_case.style;
+    Ok(bib
+        .iter_mut()
+        .filter_map(|entry| normalize(entry, style).then(|| entry.key.clone()))
+        .collect())
+}
@@ -0,0 +1,134 @@
+//! Undoing partially applied commands.
+//!
+//! Commands such as `index` fetch from the network, write the bibliography and
+//! sync git as separate steps. A [`Transaction`] records the library files and
+//! the git `HEAD` before the first step, and if it is dropped without being
+//! committed, because a later step returned an error, puts them back as they
+//! were, along with other files registered through [`Transaction::track`].
+//! Files moved through [`Transaction::rename`], such as the notes and PDFs
+//! [`crate::consistency`] renames along with their entries, are moved back.
+
+use std::fs;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use tracing::{debug, warn};
+
+use crate::{Result, archive, config::Setup, dry_run, local, pdfs, sidecar, store, venues};
+
+pub struct Transaction<'a> {
+    setup: &'a Setup,
+    /// The files the transaction may change and their content before it, or
+    /// `None` if they did not exist
+    files: Vec<(Utf8PathBuf, Option<Vec<u8>>)>,
+    /// The files moved since the transaction began, in order
+    moves: Vec<(Utf8PathBuf, Utf8PathBuf)>,
+    head: Option<String>,
+    done: bool,
+}
+
+impl<'a> Transaction<'a> {
+    pub fn begin(setup: &'a Setup) -> Result<Self> {
+        let mut files = Vec::new();
+        for path in [
+            setup.bib_path(),
+            store::json_path(setup),
+            sidecar::path(setup),
+            sidecar::local_path(setup),
+            setup.archive_path(),
+            archive::local_path(setup),
+            local::path(setup),
+            setup.pdf_dir().join(pdfs::MANIFEST),
+            venues::path(setup),
+        ] {
+            let content = path.exists().then(|| fs::read(&path)).transpose()?;
+            files.push((path, content));
+        }
+        let head = if setup.git().is_some() {
+            duct::cmd!("git", "rev-parse", "--verify", "-q", "HEAD")
+                .dir(setup.root())
+                .stderr_null()
+                .read()
+                .ok()
+        } else {
+            None
+        };
+        Ok(Self {
+            setup,
+            files,
+            moves: Vec::new(),
+            head,
+            done: false,
+        })
+    }
+
+    /// Records `path` as it is now, to be put back if the transaction is
+    /// rolled back. For files outside the library state, such as notes and
+    /// PDFs, which are written in place.
+    pub fn track(&mut self, path: &Utf8Path) -> Result<()> {
+        if self.files.iter().any(|(tracked, _)| tracked == path) {
+            return Ok(());
+        }
+        let content = path.exists().then(|| fs::read(path)).transpose()?;
+        self.files.push((path.to_path_buf(), content));
+        Ok(())
+    }
+
+    /// Moves the file or directory `from` to `to`, to be moved back if the
+    /// transaction is rolled back.
+    pub fn rename(&mut self, from: &Utf8Path, to: &Utf8Path) -> Result<()> {
+        if dry_run::enabled() {
+            dry_run::would_write(to);
+            return Ok(());
+        }
+        if let Some(dir) = to.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::rename(from, to)?;
+        debug!(%from, %to, "moved");
+        self.moves.push((from.to_path_buf(), to.to_path_buf()));
+        Ok(())
+    }
+
+    /// Keeps the changes made since [`Transaction::begin`].
+    pub fn commit(mut self) {
+        self.done = true;
+    }
+
+    fn rollback(&self) -> Result<()> {
+        let root = self.setup.root();
+        if let Some(head) = &self.head {
+            let git_dir = root.join(".git");
+            if git_dir.join("rebase-merge").exists() || git_dir.join("rebase-apply").exists() {
+                duct::cmd!("git", "rebase", "--abort").dir(&root).run()?;
+            }
+            // drops the automatic commit, if any, and unstages the changes
+            duct::cmd!("git", "reset", "-q", "--mixed", head)
+                .dir(&root)
+                .run()?;
+        }
+        for (from, to) in self.moves.iter().rev() {
+            fs::rename(to, from)?;
+        }
+        for (path, content) in &self.files {
+            match content {
+                Some(content) => fs::write(path, content)?,
+                None if path.exists() => fs::remove_file(path)?,
+                None => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Transaction<'_> {
+    fn drop(&mut self) {
+        if self.done || dry_run::enabled() {
+            return;
+        }
+        warn!("command failed, rolling back changes to the library");
+        match self.rollback() {
+            Ok(()) => debug!("rolled back"),
+            Err(err) => warn!(%err, "failed to roll back"),
+        }
+    }
+}
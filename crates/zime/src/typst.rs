@@ -0,0 +1,93 @@
+//! Integration with Typst projects.
+//!
+//! `zime typst` scans the `.typ` files of a project for citations and writes
+//! the cited entries to a project-local Hayagriva file, the Typst counterpart
+//! of `zime latexmk`.
+
+use std::{collections::BTreeSet, fs};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use color_eyre::eyre::eyre;
+use tracing::{debug, info};
+
+use crate::{Result, bundle, config::Setup, latex};
+
+fn is_label_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | ':' | '.')
+}
+
+/// Candidate citation keys in Typst source: `@key` references, `<key>` labels
+/// as in `#cite(<key>)`, and `label("key")`.
+///
+/// References to figures and headings use the same syntax, so the result has
+/// to be matched against the library.
+pub fn citations(src: &str) -> BTreeSet<String> {
+    let mut keys = BTreeSet::new();
+    let mut take = |rest: &str| {
+        let key: String = rest.chars().take_while(|&c| is_label_char(c)).collect();
+        // like Typst, do not include trailing punctuation in `@key.`
+        let key = key.trim_end_matches(['.', ':']);
+        if !key.is_empty() {
+            keys.insert(key.to_string());
+        }
+    };
+    let mut prev = ' ';
+    for (i, c) in src.char_indices() {
+        let rest = &src[i + c.len_utf8()..];
+        match c {
+            // skip e-mail addresses
+            '@' if !prev.is_alphanumeric() => take(rest),
+            '<' => {
+                let label: String = rest.chars().take_while(|&c| is_label_char(c)).collect();
+                if rest[label.len()..].starts_with('>') {
+                    take(rest);
+                }
+            }
+            _ => {}
+        }
+        prev = c;
+    }
+    for (i, _) in src.match_indices("label(\"") {
+        take(&src[i + "label(\"".len()..]);
+    }
+    keys
+}
+
+/// The `.typ` files below `dir`, skipping hidden directories.
+fn typ_files(dir: &Utf8Path) -> Result<Vec<Utf8PathBuf>> {
+    Ok(bundle::walk(dir)?
+        .into_iter()
+        .filter(|path| path.extension() == Some("typ"))
+        .filter(|path| {
+            path.strip_prefix(dir)
+                .unwrap_or(path)
+                .components()
+                .all(|c| !c.as_str().starts_with('.'))
+        })
+        .collect())
+}
+
+/// Writes the entries cited in the project in `dir` to `out` as Hayagriva
+/// YAML, only touching the file when its content changes.
+pub fn run(setup: &Setup, dir: &Utf8Path, out: &Utf8Path) -> Result<()> {
+    let mut keys = BTreeSet::new();
+    for file in typ_files(dir)? {
+        keys.extend(citations(&fs::read_to_string(&file)?));
+    }
+    let bib = setup.bib()?;
+    keys.retain(|key| bib.get(key).is_some());
+    debug!(?keys, "found citations");
+
+    let (subset, _) = latex::subset(&bib, &keys);
+    let library = hayagriva::io::from_biblatex(&subset)
+        .map_err(|errors| eyre!("failed to convert entries to Hayagriva: {errors:?}"))?;
+    let content = hayagriva::io::to_yaml_str(&library)?;
+
+    if fs::read_to_string(out).ok().as_deref() == Some(content.as_str()) {
+        debug!(%out, "bibliography is up to date");
+        return Ok(());
+    }
+    fs::write(out, content)?;
+    info!(%out, entries = subset.len(), "wrote bibliography");
+    Ok(())
+}
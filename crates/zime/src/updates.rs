@@ -0,0 +1,161 @@
+//! `zime check-updates`: noticing newer versions of the arXiv preprints in the
+//! library.
+//!
+//! The version of each preprint is kept in `zime.lock` from the first check
+//! on. A preprint checked for the first time counts as updated if arXiv got a
+//! new version after it was added. Updating takes the title, authors and
+//! abstract of the new version and downloads its PDF again, if one was
+//! downloaded before.
+
+use std::collections::BTreeMap;
+
+use color_eyre::owo_colors::OwoColorize;
+use itertools::Itertools;
+use tracing::{info, warn};
+
+use crate::{
+    Result, cancel,
+    config::Setup,
+    dry_run, entry,
+    i18n::t,
+    pdf_steps,
+    pdfs::Manifest,
+    prompt, refetch,
+    remotes::arxiv::{self, Paper},
+    sidecar::Sidecar,
+    transaction::Transaction,
+};
+
+/// A preprint with a newer version on arXiv.
+struct Update {
+    key: String,
+    doi: String,
+    /// The version last fetched, if it was recorded
+    from: Option<u32>,
+    paper: Paper,
+}
+
+pub fn run(setup: &Setup, yes: bool) -> Result<()> {
+    let mut bib = setup.bib()?;
+    let mut sidecar = Sidecar::load(setup)?;
+    let preprints = bib
+        .iter()
+        .filter_map(|entry| {
+            let doi = entry.doi().ok()?;
+            Some((entry.key.clone(), arxiv::id(&doi)?, doi))
+        })
+        .collect_vec();
+    if preprints.is_empty() {
+        println!("{}", t!("updates-found", count = 0));
+        return Ok(());
+    }
+
+    let spinner = prompt::spinner();
+    spinner.start(t!("updates-checking", count = preprints.len()));
+    let ids = preprints.iter().map(|(_, id, _)| id.clone()).collect_vec();
+    let papers: BTreeMap<String, Paper> = arxiv::fetch_many(&ids)?
+        .into_iter()
+        .map(|paper| (paper.id.to_lowercase(), paper))
+        .collect();
+    spinner.stop("");
+
+    let mut updates = Vec::new();
+    let mut tracked = 0;
+    for (key, id, doi) in preprints {
+        let Some(paper) = papers.get(&id.to_lowercase()) else {
+            warn!(%key, %id, "arXiv did not return the preprint");
+            continue;
+        };
+        let Some(latest) = paper.version else {
+            continue;
+        };
+        let meta = sidecar.get_mut(&key);
+        let newer = match meta.arxiv_version {
+            Some(recorded) => latest > recorded,
+            None => meta
+                .added
+                .zip(paper.updated)
+                .is_some_and(|(added, updated)| updated > added),
+        };
+        if newer {
+            updates.push(Update {
+                key,
+                doi,
+                from: meta.arxiv_version,
+                paper: paper.clone(),
+            });
+        } else if meta.arxiv_version.is_none() {
+            meta.arxiv_version = Some(latest);
+            tracked += 1;
+        }
+    }
+
+    for update in &updates {
+        let title = bib.get(&update.key).map(entry::title).unwrap_or_default();
+        let from = update.from.map_or("?".to_string(), |v| format!("v{v}"));
+        let to = update.paper.version.unwrap_or_default();
+        println!(
+            "{}  {title}  {}",
+            update.key,
+            format!("{from} → v{to}").yellow()
+        );
+    }
+    println!("{}", t!("updates-found", count = updates.len()));
+
+    let chosen = if yes || updates.is_empty() {
+        updates.iter().collect_vec()
+    } else {
+        prompt::multiselect(
+            t!("select-updates"),
+            updates.iter().map(|update| (&update.key, "")),
+            &(0..updates.len()).collect_vec(),
+        )?
+        .into_iter()
+        .map(|i| &updates[i])
+        .collect_vec()
+    };
+    if chosen.is_empty() && tracked == 0 {
+        return Ok(());
+    }
+
+    let mut tx = Transaction::begin(setup)?;
+    let mut manifest = Manifest::load(setup)?;
+    let steps = setup.config()?.pdf_steps;
+    for update in chosen {
+        let Some(entry) = bib.get_mut(&update.key) else {
+            continue;
+        };
+        let remote = update.paper.record().into_entry();
+        // Only what arXiv knows better, the entry may have been published
+        // since and keep its venue and type
+        let changes = refetch::changes(entry, &remote)
+            .into_iter()
+            .filter(|change| ["title", "author", "abstract"].contains(&change.name.as_str()))
+            .collect_vec();
+        refetch::apply(entry, &remote, &changes.iter().collect_vec());
+
+        let path = setup.pdf_path(&update.doi);
+        if path.exists() && !dry_run::enabled() {
+            match arxiv::fetch_pdf(&update.doi) {
+                Ok(pdf) => {
+                    // Put the old version back if a later step fails
+                    tx.track(&path)?;
+                    cancel::write(&path, pdf)?;
+                    pdf_steps::run(&steps, &path, "arxiv");
+                    manifest.record(&update.doi, &path, Some("arxiv"))?;
+                    info!(%path, "downloaded the new version");
+                }
+                Err(err) => warn!(key = %update.key, %err, "failed to download the new version"),
+            }
+        }
+        sidecar.get_mut(&update.key).arxiv_version = update.paper.version;
+    }
+    if !dry_run::enabled() {
+        manifest.save(setup)?;
+    }
+    sidecar.save(setup)?;
+    setup.write_bib(&bib)?;
+    setup.sync_git()?;
+    tx.commit();
+    Ok(())
+}
@@ -0,0 +1,187 @@
+//! Venues of interest, kept in `venues.toml` in the library.
+//!
+//! Venues are tracked independently of entries, with an optional submission
+//! deadline. For each venue zime shows the entries of the library published
+//! there and the authors who publish there most, and `index --venue` limits
+//! DBLP searches to it.
+
+use std::{collections::BTreeMap, fs};
+
+use camino::Utf8PathBuf;
+use color_eyre::{
+    eyre::{bail, eyre},
+    owo_colors::OwoColorize,
+};
+use itertools::Itertools;
+use jiff::civil::Date;
+use serde::{Deserialize, Serialize};
+
+use crate::{Result, config::Setup, dry_run, entry, i18n::t};
+
+pub const FILE: &str = "venues.toml";
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Venues {
+    #[serde(default, rename = "venue")]
+    pub venues: Vec<Venue>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Venue {
+    /// Short name, e.g. `POPL`
+    pub name: String,
+    /// Other names the venue appears under in entries
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Name of the venue in DBLP, if it differs from `name`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dblp: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<Date>,
+}
+
+impl Venue {
+    /// Whether `entry` was published at this venue.
+    pub fn matches(&self, entry: &biblatex::Entry) -> bool {
+        let Some(venue) = entry::venue(entry) else {
+            return false;
+        };
+        let venue = venue.to_lowercase();
+        std::iter::once(&self.name)
+            .chain(&self.aliases)
+            .any(|name| venue.contains(&name.to_lowercase()))
+    }
+
+    /// The term restricting a DBLP search to this venue.
+    pub fn dblp_query(&self) -> String {
+        format!("venue:{}:", self.dblp.as_deref().unwrap_or(&self.name))
+    }
+}
+
+pub fn path(setup: &Setup) -> Utf8PathBuf {
+    setup.root().join(FILE)
+}
+
+impl Venues {
+    pub fn load(setup: &Setup) -> Result<Self> {
+        let path = path(setup);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        toml::from_str(&fs::read_to_string(&path)?)
+            .map_err(|err| eyre!("failed to parse {path}: {err}"))
+    }
+
+    pub fn save(&self, setup: &Setup) -> Result<()> {
+        if dry_run::enabled() {
+            dry_run::would_write(&path(setup));
+            return Ok(());
+        }
+        fs::write(path(setup), toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Result<&Venue> {
+        self.venues
+            .iter()
+            .find(|venue| venue.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| eyre!(t!("venue-not-tracked", name = name)))
+    }
+
+    /// Adds a venue, or updates it if it is already tracked.
+    pub fn track(&mut self, venue: Venue) {
+        match self
+            .venues
+            .iter_mut()
+            .find(|v| v.name.eq_ignore_ascii_case(&venue.name))
+        {
+            Some(existing) => *existing = venue,
+            None => self.venues.push(venue),
+        }
+        self.venues.sort_by_key(|v| v.name.to_lowercase());
+    }
+
+    pub fn untrack(&mut self, name: &str) -> Result<()> {
+        let before = self.venues.len();
+        self.venues.retain(|v| !v.name.eq_ignore_ascii_case(name));
+        if self.venues.len() == before {
+            bail!(t!("venue-not-tracked", name = name));
+        }
+        Ok(())
+    }
+}
+
+/// Lists the tracked venues with their deadlines and number of entries,
+/// upcoming deadlines first.
+pub fn list(setup: &Setup) -> Result<()> {
+    let venues = Venues::load(setup)?;
+    if venues.venues.is_empty() {
+        println!("{}", t!("venues-empty"));
+        return Ok(());
+    }
+    let bib = setup.bib()?;
+    let today = jiff::Zoned::now().date();
+    let ordered = venues
+        .venues
+        .iter()
+        .sorted_by_key(|v| (v.deadline.is_none_or(|d| d < today), v.deadline));
+    for venue in ordered {
+        let count = bib.iter().filter(|entry| venue.matches(entry)).count();
+        let deadline = match venue.deadline {
+            Some(deadline) if deadline < today => deadline.to_string().dimmed().to_string(),
+            Some(deadline) => deadline.to_string().yellow().to_string(),
+            None => String::new(),
+        };
+        println!(
+            "{:<12} {:>10}  {}",
+            venue.name.bold(),
+            deadline,
+            t!("venue-entries", count = count).dimmed()
+        );
+    }
+    Ok(())
+}
+
+/// Shows the entries published at a venue and the authors with most entries
+/// there.
+pub fn show(setup: &Setup, name: &str) -> Result<()> {
+    let venues = Venues::load(setup)?;
+    let venue = venues.get(name)?;
+    let bib = setup.bib()?;
+    let entries = bib
+        .iter()
+        .filter(|entry| venue.matches(entry))
+        .sorted_by_key(|entry| std::cmp::Reverse(entry::year(entry)))
+        .collect_vec();
+
+    println!("{}", venue.name.bold());
+    if let Some(deadline) = venue.deadline {
+        println!("  {}", t!("venue-deadline", date = deadline.to_string()));
+    }
+    println!();
+    for entry in &entries {
+        let year = entry::year(entry)
+            .map(|y| y.to_string())
+            .unwrap_or_default();
+        println!("{:>4}  {}", year.dimmed(), entry::title(entry));
+    }
+
+    let mut authors: BTreeMap<String, usize> = BTreeMap::new();
+    for entry in &entries {
+        for author in entry.author().unwrap_or_default() {
+            *authors.entry(author.to_string()).or_default() += 1;
+        }
+    }
+    if !authors.is_empty() {
+        println!();
+        println!("{}", t!("venue-authors").bold());
+        for (author, count) in authors
+            .into_iter()
+            .sorted_by_key(|(author, count)| (std::cmp::Reverse(*count), author.clone()))
+            .take(10)
+        {
+            println!("{count:>4}  {author}");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,118 @@
+//! Links between the versions of a work: a conference paper, its extended
+//! journal version and its preprint.
+//!
+//! The links are kept on the shorter version, in the reserved `zime-versions`
+//! field as space separated `kind=key` pairs, e.g. `extended=doe2021journal`.
+//! `show` and `list --tree` print the versions together, and `latexmk` warns
+//! when a paper cites a version that has an extended one.
+
+use biblatex::{Bibliography, Chunk, ChunksExt, Entry, Spanned};
+use serde::{Deserialize, Serialize};
+
+pub const FIELD: &str = "zime-versions";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    /// The journal or otherwise extended version
+    Extended,
+    /// The preprint, e.g. on arXiv
+    Preprint,
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Extended => "extended",
+            Kind::Preprint => "preprint",
+        }
+    }
+
+    /// What the entry holding the link is to the linked one.
+    fn inverse(self) -> &'static str {
+        match self {
+            Kind::Extended => "short",
+            Kind::Preprint => "published",
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        [Kind::Extended, Kind::Preprint]
+            .into_iter()
+            .find(|kind| kind.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Version {
+    pub kind: Kind,
+    pub key: String,
+}
+
+/// The versions linked from the entry.
+pub fn get(entry: &Entry) -> Vec<Version> {
+    let Some(value) = entry.get(FIELD) else {
+        return Vec::new();
+    };
+    value
+        .format_verbatim()
+        .split_whitespace()
+        .filter_map(|pair| {
+            let (kind, key) = pair.split_once('=')?;
+            Some(Version {
+                kind: Kind::parse(kind)?,
+                key: key.to_string(),
+            })
+        })
+        .collect()
+}
+
+pub fn set(entry: &mut Entry, versions: &[Version]) {
+    if versions.is_empty() {
+        entry.remove(FIELD);
+        return;
+    }
+    let value = versions
+        .iter()
+        .map(|version| format!("{}={}", version.kind.name(), version.key))
+        .collect::<Vec<_>>()
+        .join(" ");
+    entry.set(FIELD, vec![Spanned::detached(Chunk::Verbatim(value))]);
+}
+
+/// Links `key` as the `kind` version of the entry, replacing an earlier link
+/// of that kind.
+pub fn link(entry: &mut Entry, kind: Kind, key: &str) {
+    let mut versions = get(entry);
+    versions.retain(|version| version.kind != kind);
+    versions.push(Version {
+        kind,
+        key: key.to_string(),
+    });
+    set(entry, &versions);
+}
+
+/// The other versions of the entry, linked from it or linking to it, with
+/// what they are to it, e.g. `extended` or `short`.
+pub fn related<'a>(bib: &'a Bibliography, entry: &Entry) -> Vec<(&'static str, &'a Entry)> {
+    let mut related: Vec<_> = get(entry)
+        .into_iter()
+        .filter_map(|version| Some((version.kind.name(), bib.get(&version.key)?)))
+        .collect();
+    for other in bib.iter() {
+        for version in get(other) {
+            if version.key == entry.key {
+                related.push((version.kind.inverse(), other));
+            }
+        }
+    }
+    related
+}
+
+/// The extended version of the entry, if it has one in `bib`.
+pub fn extended<'a>(bib: &'a Bibliography, entry: &Entry) -> Option<&'a Entry> {
+    get(entry)
+        .into_iter()
+        .find(|version| version.kind == Kind::Extended)
+        .and_then(|version| bib.get(&version.key))
+}
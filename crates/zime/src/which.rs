@@ -0,0 +1,50 @@
+//! `zime which`: the paths of the library a command in the current directory
+//! would use, and why that library was chosen.
+
+use camino::Utf8Path;
+use color_eyre::owo_colors::OwoColorize;
+
+use crate::{
+    Result, archive,
+    config::{self, Setup},
+    i18n::t,
+    store::{self, Format},
+};
+
+pub fn run(setup: &Setup) -> Result<()> {
+    let cwd = std::env::current_dir()?;
+    let cwd = cwd.display().to_string();
+    let rule = if config::has_explicit_root() {
+        t!("which-explicit")
+    } else if setup.is_global() {
+        t!("which-global", cwd = cwd)
+    } else {
+        t!("which-local", cwd = cwd)
+    };
+
+    let line = |label: &str, path: &Utf8Path| {
+        let state = if path.exists() {
+            String::new()
+        } else {
+            format!(" ({})", t!("which-missing"))
+        };
+        println!("{:>14}: {path}{}", label.dimmed(), state.dimmed());
+    };
+    line("root", &setup.root());
+    println!("{:>14}  {}", "", rule.italic());
+    line("config", &setup.config_file());
+    line("global config", &config::global_config_file()?);
+    line("bibliography", &setup.bib_path());
+    if setup.config()?.store.format == Format::Json {
+        line("json store", &store::json_path(setup));
+    }
+    line("archive", &setup.archive_path());
+    line("local archive", &archive::local_path(setup));
+    line("pdfs", &setup.pdf_dir());
+    line("notes", &setup.notes_dir());
+    line("cache", &config::cache_dir()?);
+    if let Some(git) = setup.git() {
+        println!("{:>14}: {git}", "git remote".dimmed());
+    }
+    Ok(())
+}